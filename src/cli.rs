@@ -1,34 +1,438 @@
-use clap::{CommandFactory, Parser, Subcommand};
-use clap_complete::Shell;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use clap::builder::{EnumValueParser, TypedValueParser};
+use clap::error::ErrorKind;
+use clap::{ArgAction, CommandFactory, Parser, Subcommand, ValueEnum, ValueHint};
+use tracing::level_filters::LevelFilter;
+
+use cert_tree::io::StartTls;
+use cert_tree::models::{DisplayTimezone, FingerprintAlgorithm, ValidityStatus};
+use cert_tree::parser::InputFormat;
+use cert_tree::tree::{CertificateFilter, SortOrder};
+
+use crate::completions::CompletionShell;
+use crate::display::Verbosity;
+use crate::fields::Field;
 
 #[derive(Parser)]
+#[allow(clippy::struct_excessive_bools)]
 #[command(name = "cert-tree")]
 #[command(about = "X.509 certificate inspection utility")]
 #[command(version)]
-#[command(after_help = "Github: https://github.com/tdslot/cert-tree.rs")]
+#[command(after_help = "Exit codes with --check:
+  0  All certificates are valid
+  1  At least one certificate has expired
+  2  At least one certificate expires within 30 days
+  3  At least one certificate is not yet valid
+
+Exit code 4 means a URL, --connect or --unix-socket target could not be
+fetched (the others are still shown); --keep-going turns that into 0.
+
+Github: https://github.com/tdslot/cert-tree.rs")]
 pub struct Args {
     #[command(subcommand)]
     pub command: Option<Commands>,
 
-    /// Certificate file path (PEM or DER)
+    /// Certificate file path (PEM or DER); repeat to combine several files
+    #[arg(short, long, action = ArgAction::Append, value_hint = ValueHint::FilePath, global = true)]
+    pub file: Vec<String>,
+
+    /// Directory of .pem/.crt/.der files to inspect together; repeatable
+    #[arg(short = 'd', long, action = ArgAction::Append, value_name = "PATH", value_hint = ValueHint::DirPath, global = true)]
+    pub dir: Vec<String>,
+
+    /// Also scan subdirectories of --dir or the `scan` directory
+    #[arg(short = 'r', long, global = true)]
+    pub recursive: bool,
+
+    /// Hex-encoded DER certificate, e.g. `xxd -p cert.der`; whitespace and colons are ignored; repeatable
+    #[arg(long, action = ArgAction::Append, value_name = "STRING", global = true)]
+    pub hex: Vec<String>,
+
+    /// How to parse --file and --dir inputs
+    #[arg(long, value_parser = EnumValueParser::<InputFormatArg>::new().map(InputFormat::from), default_value = "auto", global = true)]
+    pub input_format: InputFormat,
+
+    /// Certificate URL, or a bare host[:port] to connect to; repeatable
+    #[arg(short = 'U', long, action = ArgAction::Append, global = true)]
+    pub url: Vec<String>,
+
+    /// TLS endpoint to read the chain from, skipping the HTTP download probe; repeatable
+    #[arg(long, action = ArgAction::Append, value_name = "HOST:PORT", global = true)]
+    pub connect: Vec<String>,
+
+    /// Port to connect to, overriding the port in the URL (default 443)
     #[arg(short, long, global = true)]
-    pub file: Option<String>,
+    pub port: Option<u16>,
+
+    /// Unix domain socket of a local TLS service to read the chain from; needs --servername; repeatable
+    #[arg(long, action = ArgAction::Append, value_name = "PATH", value_hint = ValueHint::FilePath, requires = "servername", global = true)]
+    pub unix_socket: Vec<String>,
+
+    /// Negotiate STARTTLS with the given protocol before the TLS handshake
+    /// (connects to the protocol's well-known port unless the target or --port names one)
+    #[arg(long, value_parser = EnumValueParser::<StartTlsArg>::new().map(StartTls::from), value_name = "PROTO", global = true)]
+    pub starttls: Option<StartTls>,
+
+    /// Server name to send as SNI and HTTP Host (defaults to the URL host; none for IPs)
+    #[arg(long, value_name = "NAME", global = true)]
+    pub servername: Option<String>,
+
+    /// PEM client certificate (chain) for servers that require mutual TLS
+    #[arg(long, value_name = "PEM", value_hint = ValueHint::FilePath, requires = "client_key", global = true)]
+    pub client_cert: Option<String>,
+
+    /// PEM private key matching --client-cert
+    #[arg(long, value_name = "PEM", value_hint = ValueHint::FilePath, requires = "client_cert", global = true)]
+    pub client_key: Option<String>,
+
+    /// HTTP proxy for fetches, tunnelling TLS with CONNECT (default: `HTTPS_PROXY` or `ALL_PROXY`; `NO_PROXY` hosts go direct)
+    #[arg(long, value_name = "URL", global = true)]
+    pub proxy: Option<String>,
 
-    /// Certificate URL
-    #[arg(short = 'U', long, global = true)]
-    pub url: Option<String>,
+    /// Retry URL fetches this many times on timeouts and reset connections, backing off exponentially
+    #[arg(long, value_name = "N", default_value_t = 0, global = true)]
+    pub retries: u32,
+
+    /// Show only the leaf (the first certificate) instead of the whole chain (e.g. with `--fields`)
+    #[arg(long, visible_aliases = ["leaf", "first"], conflicts_with = "fetch_root", global = true)]
+    pub leaf_only: bool,
+
+    /// Reduce the output to the topmost ancestor of the first certificate, typically the root
+    #[arg(long, conflicts_with = "leaf_only", global = true)]
+    pub root: bool,
+
+    /// Download issuers missing from the chain via their CA Issuers URL, up to the root (also completes a lone leaf)
+    #[arg(
+        long,
+        visible_alias = "build-chain",
+        conflicts_with = "leaf_only",
+        global = true
+    )]
+    pub fetch_root: bool,
+
+    /// PEM file of additional trusted root CAs, used for the TLS handshake and chain validation
+    #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath, global = true)]
+    pub ca_bundle: Option<String>,
+
+    /// JSON or TOML file of OID-to-name mappings that override or extend the built-in names
+    #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath, global = true)]
+    pub oid_map: Option<String>,
 
     /// Interactive TUI mode
     #[arg(short = 'i', long, default_value = "false", global = true)]
     pub interactive: bool,
 
-    /// Force text output mode (non-interactive)
-    #[arg(short = 't', long, default_value = "true", global = true)]
+    /// In interactive mode, re-fetch the certificates every N seconds and refresh the tree
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        value_parser = clap::value_parser!(u64).range(1..),
+        requires = "interactive",
+        global = true
+    )]
+    pub watch: Option<u64>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Tree, global = true)]
+    pub format: OutputFormat,
+
+    /// Show at most N levels of the tree, summarizing deeper certificates as "… N more"
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..), global = true)]
+    pub max_depth: Option<u32>,
+
+    /// Order of the certificate list and the tree's top-level roots
+    #[arg(long, value_parser = EnumValueParser::<SortOrderArg>::new().map(SortOrder::from), default_value = "none", global = true)]
+    pub sort: SortOrder,
+
+    /// Deprecated and ignored: text output is already the default
+    #[arg(short = 't', long, hide = true, global = true)]
     pub text: bool,
+
+    /// Time zone of displayed dates; CSV, JSON and YAML stay in UTC
+    #[arg(long, value_parser = EnumValueParser::<DisplayTimezoneArg>::new().map(DisplayTimezone::from), default_value = "utc", global = true)]
+    pub timezone: DisplayTimezone,
+
+    /// Disable colored output (also honors the `NO_COLOR` environment variable)
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// Draw the tree with ASCII connectors (default when the locale is not UTF-8)
+    #[arg(long, global = true)]
+    pub ascii: bool,
+
+    /// Set the exit code from certificate validity (see exit codes below)
+    #[arg(long, global = true)]
+    pub check: bool,
+
+    /// Exit 0 even if some URLs or endpoints could not be fetched (see exit codes below)
+    #[arg(long, global = true)]
+    pub keep_going: bool,
+
+    /// Show only expired certificates; with --only-expiring, both kinds
+    #[arg(long, global = true)]
+    pub only_expired: bool,
+
+    /// Show only certificates expiring soon; with --only-expired, both kinds
+    #[arg(long, global = true)]
+    pub only_expiring: bool,
+
+    /// Show only certificates expiring before this date (`YYYY-MM-DD` as midnight UTC, or RFC 3339)
+    #[arg(long, value_name = "DATE", value_parser = parse_cutoff, global = true)]
+    pub expire_before: Option<DateTime<Utc>>,
+
+    /// Print one `key=value` line with the whole chain's health instead of the usual output
+    #[arg(long, conflicts_with = "interactive", global = true)]
+    pub summary: bool,
+
+    /// Print only the number of certificates found, across every file, directory and URL
+    #[arg(long, conflicts_with_all = ["interactive", "summary", "fields", "dump", "pem_out", "lint"], global = true)]
+    pub count: bool,
+
+    /// Check each certificate for extensions its role requires (SAN and serverAuth on a TLS leaf, CA:TRUE and keyCertSign on a CA) and print the findings
+    #[arg(long, conflicts_with_all = ["interactive", "summary", "fields", "dump", "pem_out"], global = true)]
+    pub lint: bool,
+
+    /// Print only these comma-separated fields, in order (e.g. `cn,serial,not_after`)
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        value_name = "FIELDS",
+        conflicts_with_all = ["interactive", "summary"],
+        global = true
+    )]
+    pub fields: Vec<Field>,
+
+    /// Dump each certificate's DER as hex (default) or as an indented ASN.1 outline
+    #[arg(
+        long,
+        value_enum,
+        value_name = "KIND",
+        num_args = 0..=1,
+        default_missing_value = "hex",
+        conflicts_with_all = ["interactive", "summary", "fields", "pem_out"],
+        global = true
+    )]
+    pub dump: Option<DumpFormat>,
+
+    /// Print only the leaf's fingerprint with this digest, as bare colon-separated hex
+    #[arg(
+        long,
+        value_parser = EnumValueParser::<FingerprintAlgorithmArg>::new().map(FingerprintAlgorithm::from),
+        value_name = "ALGORITHM",
+        conflicts_with_all = ["interactive", "summary", "fields", "dump", "pem_out", "lint", "count"],
+        global = true
+    )]
+    pub fingerprint: Option<FingerprintAlgorithm>,
+
+    /// With `--fingerprint`, print one line for every certificate instead of only the leaf
+    #[arg(long, requires = "fingerprint", global = true)]
+    pub all: bool,
+
+    /// Write the certificates re-encoded as concatenated PEM instead of the usual output
+    #[arg(long, conflicts_with_all = ["interactive", "summary"], global = true)]
+    pub pem_out: bool,
+
+    /// Skip chain signature validation, e.g. for speed on large bundles; chains show as "Not Checked"
+    #[arg(long, global = true)]
+    pub no_validate: bool,
+
+    /// Check revocation status via OCSP for certificates whose issuer is in the chain
+    #[arg(long, global = true)]
+    pub ocsp: bool,
+
+    /// Print only each certificate's CN and validity status
+    #[arg(short = 'q', long, conflicts_with = "verbose", global = true)]
+    pub quiet: bool,
+
+    /// Add fingerprints and raw extension values; also logs network diagnostics (-vv for trace)
+    #[arg(short = 'v', long, action = ArgAction::Count, global = true)]
+    pub verbose: u8,
+
+    /// Never pipe long output through $PAGER (default `less -R`)
+    #[arg(long, global = true)]
+    pub no_pager: bool,
+
+    /// Write output to a file instead of stdout
+    #[arg(short = 'o', long, value_name = "PATH", value_hint = ValueHint::FilePath, global = true)]
+    pub output: Option<String>,
+}
+
+/// Non-interactive output formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Chain tree (a single certificate is shown in full)
+    Tree,
+    /// Full details of every certificate
+    Text,
+    /// JSON: the nested chain tree, even for one certificate
+    Json,
+    /// YAML: the nested chain tree, even for one certificate
+    Yaml,
+    /// CSV: one row per certificate for expiry monitoring
+    Csv,
+    /// NDJSON: one JSON object per certificate per line, streamed
+    Ndjson,
+    /// Aligned table of CN, issuer, expiry and status, fitted to the terminal width
+    Table,
+    /// One line per leaf-to-root path, e.g. `leaf [VALID until DATE] <- issuer <- root`
+    Compact,
+}
+
+/// Layouts for `--dump`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DumpFormat {
+    /// Offset, hex bytes and ASCII, like `xxd`
+    Hex,
+    /// Indented ASN.1 structure with decoded OIDs, strings and times
+    Asn1,
+}
+
+/// `--input-format` values, parsed into [`InputFormat`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum InputFormatArg {
+    /// PEM when the data contains a `-----BEGIN` armor line, DER otherwise
+    Auto,
+    Pem,
+    Der,
+    /// JSON Web Key or Key Set carrying certificate chains in `x5c`
+    Jwk,
+}
+
+impl From<InputFormatArg> for InputFormat {
+    fn from(arg: InputFormatArg) -> Self {
+        match arg {
+            InputFormatArg::Auto => InputFormat::Auto,
+            InputFormatArg::Pem => InputFormat::Pem,
+            InputFormatArg::Der => InputFormat::Der,
+            InputFormatArg::Jwk => InputFormat::Jwk,
+        }
+    }
+}
+
+/// `--starttls` values, parsed into [`StartTls`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum StartTlsArg {
+    Smtp,
+    Imap,
+    Pop3,
+    Ftp,
+}
+
+impl From<StartTlsArg> for StartTls {
+    fn from(arg: StartTlsArg) -> Self {
+        match arg {
+            StartTlsArg::Smtp => StartTls::Smtp,
+            StartTlsArg::Imap => StartTls::Imap,
+            StartTlsArg::Pop3 => StartTls::Pop3,
+            StartTlsArg::Ftp => StartTls::Ftp,
+        }
+    }
+}
+
+/// `--sort` values, parsed into [`SortOrder`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum SortOrderArg {
+    /// Soonest `not_after` first
+    Expiry,
+    /// Subject common name, case-insensitive
+    Cn,
+    /// Issuer common name, case-insensitive
+    Issuer,
+    /// Keep tree order
+    None,
+}
+
+impl From<SortOrderArg> for SortOrder {
+    fn from(arg: SortOrderArg) -> Self {
+        match arg {
+            SortOrderArg::Expiry => SortOrder::Expiry,
+            SortOrderArg::Cn => SortOrder::Cn,
+            SortOrderArg::Issuer => SortOrder::Issuer,
+            SortOrderArg::None => SortOrder::None,
+        }
+    }
+}
+
+/// `--timezone` values, parsed into [`DisplayTimezone`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum DisplayTimezoneArg {
+    /// Coordinated Universal Time, shown as `UTC`
+    Utc,
+    /// The system zone (`TZ` or `/etc/localtime`), shown as its UTC offset, e.g. `+02:00`
+    Local,
+}
+
+impl From<DisplayTimezoneArg> for DisplayTimezone {
+    fn from(arg: DisplayTimezoneArg) -> Self {
+        match arg {
+            DisplayTimezoneArg::Utc => DisplayTimezone::Utc,
+            DisplayTimezoneArg::Local => DisplayTimezone::Local,
+        }
+    }
+}
+
+/// `--fingerprint` values, parsed into [`FingerprintAlgorithm`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum FingerprintAlgorithmArg {
+    Sha256,
+    Sha1,
+}
+
+impl From<FingerprintAlgorithmArg> for FingerprintAlgorithm {
+    fn from(arg: FingerprintAlgorithmArg) -> Self {
+        match arg {
+            FingerprintAlgorithmArg::Sha256 => FingerprintAlgorithm::Sha256,
+            FingerprintAlgorithmArg::Sha1 => FingerprintAlgorithm::Sha1,
+        }
+    }
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
+    /// Inspect certificates; the default when no subcommand is given
+    ///
+    /// Examples:
+    ///   cert-tree inspect --file chain.pem
+    ///   cert-tree --file chain.pem
+    Inspect,
+    /// Check each certificate for extensions its role requires, like `--lint`
+    ///
+    /// Examples:
+    ///   cert-tree lint --file chain.pem
+    ///   cert-tree lint --dir /etc/ssl/certs
+    Lint,
+    /// Inspect every certificate file in a directory, like `--dir`
+    ///
+    /// Examples:
+    ///   cert-tree scan /etc/ssl/certs
+    ///   cert-tree scan certs --recursive --format table
+    Scan {
+        /// Directory of .pem/.crt/.der files
+        #[arg(value_name = "DIR", value_hint = ValueHint::DirPath)]
+        path: String,
+    },
+    /// Compare two certificates or chains field by field
+    ///
+    /// Certificates are paired by position in each file, leaf first. Exits
+    /// with 1 when they differ, like `diff`.
+    ///
+    /// Examples:
+    ///   cert-tree diff old.pem new.pem
+    ///   cert-tree diff chain.pem renewed-chain.pem
+    Diff {
+        /// Certificate or chain before the change
+        #[arg(value_name = "OLD", value_hint = ValueHint::FilePath)]
+        old: String,
+        /// Certificate or chain after the change
+        #[arg(value_name = "NEW", value_hint = ValueHint::FilePath)]
+        new: String,
+    },
+    /// Print the JSON Schema of the `--format json` output (and its `--format ndjson` records)
+    ///
+    /// Examples:
+    ///   cert-tree schema > cert-tree.schema.json
+    Schema,
     /// Manage shell completions
     #[command(subcommand)]
     Completion(CompletionCommands),
@@ -42,10 +446,11 @@ pub enum CompletionCommands {
     ///   cert-tree completion generate bash > cert-tree.bash
     ///   cert-tree completion generate zsh > _cert-tree
     ///   cert-tree completion generate fish > cert-tree.fish
+    ///   cert-tree completion generate nushell > cert-tree.nu
     Generate {
         /// Shell type
         #[arg(value_enum)]
-        shell: Shell,
+        shell: CompletionShell,
     },
     /// Install shell completion (auto-detects shell)
     ///
@@ -55,26 +460,239 @@ pub enum CompletionCommands {
     /// Examples:
     ///   cert-tree completion install              # Auto-detect and install
     ///   cert-tree completion install --shell bash # Install for bash
+    ///   cert-tree completion install --dry-run    # Show where it would go
     Install {
         /// Shell type (optional, will auto-detect if not provided)
         #[arg(short, long, value_enum)]
-        shell: Option<Shell>,
+        shell: Option<CompletionShell>,
+
+        /// Print the target path and script size without writing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Replace an existing completion file without asking
+        #[arg(long)]
+        force: bool,
     },
 }
 
+impl Args {
+    /// Detail level for certificate output
+    pub fn verbosity(&self) -> Verbosity {
+        match (self.quiet, self.verbose) {
+            (true, _) => Verbosity::Quiet,
+            (false, 0) => Verbosity::Normal,
+            (false, _) => Verbosity::Verbose,
+        }
+    }
+
+    /// Certificates to keep from --only-expired / --only-expiring / --expire-before
+    pub fn certificate_filter(&self) -> CertificateFilter {
+        let mut statuses = Vec::new();
+        if self.only_expired {
+            statuses.push(ValidityStatus::Expired);
+        }
+        if self.only_expiring {
+            statuses.push(ValidityStatus::ExpiringSoon);
+        }
+        CertificateFilter {
+            statuses,
+            expire_before: self.expire_before,
+        }
+    }
+
+    /// Treat the directory of the `scan` subcommand as one more `--dir`
+    ///
+    /// `--recursive` is checked here rather than with `requires = "dir"`, which
+    /// would not see the `scan` directory.
+    fn apply_scan_dir(&mut self) -> Result<(), clap::Error> {
+        if let Some(Commands::Scan { path }) = &self.command {
+            self.dir.push(path.clone());
+        }
+        if self.recursive && self.dir.is_empty() {
+            return Err(Args::command().error(
+                ErrorKind::MissingRequiredArgument,
+                "--recursive needs --dir or `scan DIR`",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Reject the options `--lint` conflicts with when linting via the `lint` subcommand
+    ///
+    /// clap only sees the conflicts of the `--lint` flag itself; without this check
+    /// `lint --pem-out` would print PEM and exit 0 without linting anything.
+    fn check_lint_subcommand(&self) -> Result<(), clap::Error> {
+        if !matches!(self.command, Some(Commands::Lint)) {
+            return Ok(());
+        }
+        let conflicting = [
+            ("--interactive", self.interactive),
+            ("--summary", self.summary),
+            ("--count", self.count),
+            ("--fields", !self.fields.is_empty()),
+            ("--dump", self.dump.is_some()),
+            ("--pem-out", self.pem_out),
+            ("--fingerprint", self.fingerprint.is_some()),
+        ];
+        match conflicting.iter().find(|(_, set)| *set) {
+            Some((flag, _)) => Err(Args::command().error(
+                ErrorKind::ArgumentConflict,
+                format!("the `lint` subcommand cannot be used with {flag}"),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether to print lint findings, from `--lint` or the `lint` subcommand
+    pub fn linting(&self) -> bool {
+        self.lint || matches!(self.command, Some(Commands::Lint))
+    }
+
+    /// Diagnostics written to stderr: errors only when quiet, debug with -v, trace with -vv
+    pub fn log_level(&self) -> LevelFilter {
+        match (self.quiet, self.verbose) {
+            (true, _) => LevelFilter::ERROR,
+            (false, 0) => LevelFilter::WARN,
+            (false, 1) => LevelFilter::DEBUG,
+            (false, _) => LevelFilter::TRACE,
+        }
+    }
+}
+
+/// Parse an `--expire-before` date: a bare date means midnight UTC
+fn parse_cutoff(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_time(NaiveTime::MIN).and_utc());
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|_| format!("expected YYYY-MM-DD or an RFC 3339 time, got '{value}'"))
+}
+
 pub fn parse_args() -> Args {
-    let args = Args::parse();
+    let mut args = Args::parse();
+    if let Err(err) = args
+        .apply_scan_dir()
+        .and_then(|()| args.check_lint_subcommand())
+    {
+        err.exit();
+    }
 
-    // If subcommand is provided, it's handled in main
-    if args.command.is_some() {
+    // Completion, schema and diff are handled in main and need no other input;
+    // inspect and lint do
+    if matches!(
+        args.command,
+        Some(Commands::Completion(_) | Commands::Schema | Commands::Diff { .. })
+    ) {
         return args;
     }
 
     // If no input arguments provided, show help
-    if args.file.is_none() && args.url.is_none() {
+    if args.file.is_empty()
+        && args.dir.is_empty()
+        && args.hex.is_empty()
+        && args.url.is_empty()
+        && args.connect.is_empty()
+        && args.unix_socket.is_empty()
+    {
         Args::command().print_help().unwrap();
         std::process::exit(0);
     }
 
     args
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inspect_is_implicit_or_explicit() {
+        let implicit = Args::try_parse_from(["cert-tree", "--file", "chain.pem"]).unwrap();
+        assert!(implicit.command.is_none());
+        assert_eq!(implicit.file, ["chain.pem"]);
+
+        let explicit =
+            Args::try_parse_from(["cert-tree", "inspect", "--file", "chain.pem"]).unwrap();
+        assert!(matches!(explicit.command, Some(Commands::Inspect)));
+        assert_eq!(explicit.file, ["chain.pem"]);
+
+        // Options before the subcommand apply too
+        let leading =
+            Args::try_parse_from(["cert-tree", "--format", "json", "inspect", "-f", "a.pem"])
+                .unwrap();
+        assert_eq!(leading.format, OutputFormat::Json);
+        assert_eq!(leading.file, ["a.pem"]);
+    }
+
+    #[test]
+    fn test_lint_subcommand_matches_flag() {
+        let subcommand = Args::try_parse_from(["cert-tree", "lint", "--file", "a.pem"]).unwrap();
+        assert!(subcommand.linting());
+
+        let flag = Args::try_parse_from(["cert-tree", "--file", "a.pem", "--lint"]).unwrap();
+        assert!(flag.command.is_none());
+        assert!(flag.linting());
+
+        let inspect = Args::try_parse_from(["cert-tree", "inspect", "--file", "a.pem"]).unwrap();
+        assert!(!inspect.linting());
+    }
+
+    #[test]
+    fn test_lint_rejects_outputs_that_skip_findings() {
+        let outputs: [&[&str]; 3] = [&["--pem-out"], &["--dump"], &["--fields", "cn"]];
+        for flag in outputs {
+            let mut with_flag = vec!["cert-tree", "--file", "a.pem", "--lint"];
+            with_flag.extend(flag);
+            assert!(Args::try_parse_from(&with_flag).is_err(), "--lint {flag:?}");
+
+            let mut subcommand = vec!["cert-tree", "lint", "--file", "a.pem"];
+            subcommand.extend(flag);
+            let args = Args::try_parse_from(&subcommand).unwrap();
+            assert!(args.check_lint_subcommand().is_err(), "lint {flag:?}");
+        }
+
+        let plain = Args::try_parse_from(["cert-tree", "lint", "--file", "a.pem"]).unwrap();
+        assert!(plain.check_lint_subcommand().is_ok());
+    }
+
+    #[test]
+    fn test_scan_subcommand_is_dir() {
+        let mut scan = Args::try_parse_from(["cert-tree", "scan", "certs", "--recursive"]).unwrap();
+        scan.apply_scan_dir().unwrap();
+        assert!(matches!(scan.command, Some(Commands::Scan { .. })));
+        assert_eq!(scan.dir, ["certs"]);
+        assert!(scan.recursive);
+
+        let mut flat = Args::try_parse_from(["cert-tree", "scan", "certs"]).unwrap();
+        flat.apply_scan_dir().unwrap();
+        assert!(!flat.recursive);
+
+        // Without a directory to descend into, --recursive is still an error
+        let mut stray = Args::try_parse_from(["cert-tree", "--file", "a.pem", "-r"]).unwrap();
+        assert!(stray.apply_scan_dir().is_err());
+    }
+
+    #[test]
+    fn test_diff_subcommand_takes_two_files() {
+        let diff = Args::try_parse_from(["cert-tree", "diff", "old.pem", "new.pem"]).unwrap();
+        let Some(Commands::Diff { old, new }) = diff.command else {
+            panic!("expected the diff subcommand");
+        };
+        assert_eq!((old.as_str(), new.as_str()), ("old.pem", "new.pem"));
+
+        // Global options still apply, and both files are required
+        let der = Args::try_parse_from([
+            "cert-tree",
+            "diff",
+            "a.der",
+            "b.der",
+            "--input-format",
+            "der",
+        ])
+        .unwrap();
+        assert_eq!(der.input_format, InputFormat::Der);
+        assert!(Args::try_parse_from(["cert-tree", "diff", "old.pem"]).is_err());
+    }
+}
@@ -1,17 +1,49 @@
 //! Shell completion generation module
 //!
 //! This module provides functionality to generate and install shell completion scripts
-//! for various shells (bash, zsh, fish, `PowerShell`).
+//! for various shells (bash, zsh, fish, `PowerShell`, Elvish, Nushell).
 
-use clap::CommandFactory;
-use clap_complete::{generate, Shell};
+use clap::{CommandFactory, ValueEnum};
+use clap_complete::{generate, Generator, Shell};
+use clap_complete_nushell::Nushell;
 use std::env;
 use std::fs;
-use std::io;
-use std::path::PathBuf;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
 use crate::cli::Args;
 
+/// Shells a completion script can be generated for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+    #[value(name = "powershell")]
+    PowerShell,
+    Elvish,
+    #[value(alias = "nu")]
+    Nushell,
+}
+
+/// Write the completion script for `shell` to `out`
+fn write_completion(shell: CompletionShell, out: &mut dyn Write) {
+    fn emit(generator: impl Generator, out: &mut dyn Write) {
+        let mut cmd = Args::command();
+        let bin_name = cmd.get_name().to_string();
+        generate(generator, &mut cmd, bin_name, out);
+    }
+
+    match shell {
+        CompletionShell::Bash => emit(Shell::Bash, out),
+        CompletionShell::Zsh => emit(Shell::Zsh, out),
+        CompletionShell::Fish => emit(Shell::Fish, out),
+        CompletionShell::PowerShell => emit(Shell::PowerShell, out),
+        CompletionShell::Elvish => emit(Shell::Elvish, out),
+        CompletionShell::Nushell => emit(Nushell, out),
+    }
+}
+
 /// Generate shell completion script and write to stdout
 ///
 /// # Arguments
@@ -25,37 +57,53 @@ use crate::cli::Args;
 /// cert-tree completion zsh > _cert-tree
 /// cert-tree completion fish > cert-tree.fish
 /// cert-tree completion powershell > _cert-tree.ps1
+/// cert-tree completion elvish > cert-tree.elv
+/// cert-tree completion nushell > cert-tree.nu
 /// ```
-pub fn generate_completion(shell: Shell) {
-    let mut cmd = Args::command();
-    let bin_name = cmd.get_name().to_string();
-
-    generate(shell, &mut cmd, bin_name, &mut io::stdout());
+pub fn generate_completion(shell: CompletionShell) {
+    write_completion(shell, &mut io::stdout());
 }
 
 /// Detect the current shell from environment variables
 ///
 /// Returns the detected shell or None if unable to detect
-pub fn detect_shell() -> Option<Shell> {
+pub fn detect_shell() -> Option<CompletionShell> {
     // Check SHELL environment variable (Unix-like systems)
-    if let Ok(shell_path) = env::var("SHELL") {
-        if shell_path.contains("bash") {
-            return Some(Shell::Bash);
-        } else if shell_path.contains("zsh") {
-            return Some(Shell::Zsh);
-        } else if shell_path.contains("fish") {
-            return Some(Shell::Fish);
-        }
+    if let Some(shell) = env::var("SHELL")
+        .ok()
+        .and_then(|path| shell_from_path(&path))
+    {
+        return Some(shell);
     }
 
     // Check for PowerShell on Windows
     if cfg!(windows) {
-        return Some(Shell::PowerShell);
+        return Some(CompletionShell::PowerShell);
     }
 
     None
 }
 
+/// Shell named by a `$SHELL` path such as `/usr/bin/zsh` or `/opt/homebrew/bin/nu`
+///
+/// Nushell's binary is just `nu`, so the file name must match it exactly.
+fn shell_from_path(shell_path: &str) -> Option<CompletionShell> {
+    let name = Path::new(shell_path).file_name()?.to_str()?;
+    if name.contains("bash") {
+        Some(CompletionShell::Bash)
+    } else if name.contains("zsh") {
+        Some(CompletionShell::Zsh)
+    } else if name.contains("fish") {
+        Some(CompletionShell::Fish)
+    } else if name.contains("elvish") {
+        Some(CompletionShell::Elvish)
+    } else if name == "nu" || name.contains("nushell") {
+        Some(CompletionShell::Nushell)
+    } else {
+        None
+    }
+}
+
 /// Get the default installation path for shell completion
 ///
 /// # Arguments
@@ -65,11 +113,11 @@ pub fn detect_shell() -> Option<Shell> {
 /// # Returns
 ///
 /// The default installation path or None if unable to determine
-pub fn get_completion_path(shell: Shell) -> Option<PathBuf> {
+pub fn get_completion_path(shell: CompletionShell) -> Option<PathBuf> {
     let home = env::var("HOME").ok()?;
 
     match shell {
-        Shell::Bash => {
+        CompletionShell::Bash => {
             // Try user-local first, then system-wide
             if cfg!(target_os = "macos") {
                 Some(PathBuf::from("/usr/local/etc/bash_completion.d/cert-tree"))
@@ -79,77 +127,125 @@ pub fn get_completion_path(shell: Shell) -> Option<PathBuf> {
                 )))
             }
         }
-        Shell::Zsh => Some(PathBuf::from(format!(
-            "{home}/.zsh/completion/_cert-tree"
-        ))),
-        Shell::Fish => Some(PathBuf::from(format!(
+        CompletionShell::Zsh => Some(PathBuf::from(format!("{home}/.zsh/completion/_cert-tree"))),
+        CompletionShell::Fish => Some(PathBuf::from(format!(
             "{home}/.config/fish/completions/cert-tree.fish"
         ))),
-        Shell::PowerShell => {
+        // A module in Elvish's library path, loaded with `use cert-tree`
+        CompletionShell::Elvish => Some(PathBuf::from(format!(
+            "{home}/.config/elvish/lib/cert-tree.elv"
+        ))),
+        CompletionShell::Nushell => Some(PathBuf::from(format!(
+            "{home}/.config/nushell/completions/cert-tree.nu"
+        ))),
+        CompletionShell::PowerShell => {
             // PowerShell profile location
             None // PowerShell requires adding to profile, not a completion file
         }
-        _ => None,
     }
 }
 
+/// How `install_completion` treats the target file
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InstallOptions {
+    /// Report the target path and script size without writing anything
+    pub dry_run: bool,
+    /// Replace an existing completion file without asking
+    pub force: bool,
+}
+
 /// Install completion script to the appropriate location
 ///
 /// # Arguments
 ///
 /// * `shell` - The target shell (if None, will auto-detect)
+/// * `options` - Dry-run and overwrite behavior
+/// * `confirm_overwrite` - Asked whether to replace an existing file unless `options.force` is set
 ///
 /// # Returns
 ///
 /// Result indicating success or error message
-pub fn install_completion(shell: Option<Shell>) -> Result<String, String> {
+pub fn install_completion(
+    shell: Option<CompletionShell>,
+    options: InstallOptions,
+    confirm_overwrite: impl FnOnce(&Path) -> bool,
+) -> Result<String, String> {
     let detected_shell = match shell {
         Some(s) => s,
         None => detect_shell().ok_or("Unable to detect shell. Please specify shell explicitly.")?,
     };
 
     // Special handling for PowerShell
-    if matches!(detected_shell, Shell::PowerShell) {
+    if matches!(detected_shell, CompletionShell::PowerShell) {
         return Err("PowerShell completion requires manual setup. Run: cert-tree completion powershell >> $PROFILE".to_string());
     }
 
     let install_path = get_completion_path(detected_shell)
         .ok_or("Unable to determine installation path for this shell")?;
 
+    install_completion_at(detected_shell, &install_path, options, confirm_overwrite)
+}
+
+/// Write the completion script for `shell` to `install_path`, honoring `options`
+fn install_completion_at(
+    shell: CompletionShell,
+    install_path: &Path,
+    options: InstallOptions,
+    confirm_overwrite: impl FnOnce(&Path) -> bool,
+) -> Result<String, String> {
+    // Generate completion script
+    let mut buffer = Vec::new();
+    write_completion(shell, &mut buffer);
+
+    let shell_name = format!("{shell:?}").to_lowercase();
+    let path_str = install_path.display();
+    let exists = install_path.exists();
+
+    if options.dry_run {
+        let action = if exists { "replace" } else { "create" };
+        return Ok(format!(
+            "Dry run: nothing was written.\n\n\
+            Shell: {shell_name}\n\
+            Location: {path_str} (would {action})\n\
+            Size: {} bytes\n",
+            buffer.len()
+        ));
+    }
+
+    if exists && !options.force && !confirm_overwrite(install_path) {
+        return Err(format!(
+            "Not overwriting existing {path_str}; use --force to replace it"
+        ));
+    }
+
     // Create parent directory if it doesn't exist
     if let Some(parent) = install_path.parent() {
         fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {e}"))?;
     }
 
-    // Generate completion script
-    let mut cmd = Args::command();
-    let bin_name = cmd.get_name().to_string();
-    let mut buffer = Vec::new();
-    generate(detected_shell, &mut cmd, bin_name, &mut buffer);
-
     // Write to file
-    fs::write(&install_path, buffer)
-        .map_err(|e| format!("Failed to write completion file: {e}"))?;
-
-    let shell_name = format!("{detected_shell:?}").to_lowercase();
-    let path_str = install_path.display();
+    fs::write(install_path, buffer).map_err(|e| format!("Failed to write completion file: {e}"))?;
 
     // Provide post-install instructions
-    let instructions = match detected_shell {
-        Shell::Bash => {
+    let instructions = match shell {
+        CompletionShell::Bash => {
             if cfg!(target_os = "macos") {
                 "Reload your shell: source ~/.bash_profile"
             } else {
                 "Reload your shell: source ~/.bashrc"
             }
         }
-        Shell::Zsh => {
+        CompletionShell::Zsh => {
             "Add to ~/.zshrc if not present:\nfpath=(~/.zsh/completion $fpath)\nautoload -Uz compinit && compinit\n\nThen reload: source ~/.zshrc"
         }
-        Shell::Fish => {
+        CompletionShell::Fish => {
             "Completions will be available in new fish shells automatically"
         }
-        _ => "Restart your shell to activate completions",
+        CompletionShell::Elvish => "Add to ~/.config/elvish/rc.elv:\nuse cert-tree",
+        CompletionShell::Nushell => {
+            "Add to your config.nu ($nu.config-path):\nsource ~/.config/nushell/completions/cert-tree.nu"
+        }
+        CompletionShell::PowerShell => "Restart your shell to activate completions",
     };
 
     Ok(format!(
@@ -167,20 +263,144 @@ mod tests {
     #[test]
     fn test_completion_generation() {
         // Test that completion generation doesn't panic
-        let shells = [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell];
-
-        for shell in shells {
-            let mut cmd = Args::command();
-            let bin_name = cmd.get_name().to_string();
+        for &shell in CompletionShell::value_variants() {
             let mut buffer = Vec::new();
-            generate(shell, &mut cmd, bin_name, &mut buffer);
+            write_completion(shell, &mut buffer);
 
             // Verify some output was generated
             assert!(
                 !buffer.is_empty(),
-                "Completion generation should produce output for {:?}",
-                shell
+                "Completion generation should produce output for {shell:?}"
             );
         }
     }
+
+    #[test]
+    fn test_file_arguments_complete_paths() {
+        let mut bash = Vec::new();
+        write_completion(CompletionShell::Bash, &mut bash);
+        let bash = String::from_utf8(bash).unwrap();
+        let file_case = bash.split("--file)").nth(1).unwrap();
+        let file_case = &file_case[..file_case.find(";;").unwrap()];
+        assert!(file_case.contains("compgen -f"));
+        assert!(file_case.contains("compopt -o filenames"));
+
+        let mut zsh = Vec::new();
+        write_completion(CompletionShell::Zsh, &mut zsh);
+        let zsh = String::from_utf8(zsh).unwrap();
+        assert!(zsh.contains(":FILE:_files'"));
+        assert!(zsh.contains(":PATH:_files -/'"));
+    }
+
+    #[test]
+    fn test_elvish_and_nushell_scripts() {
+        let mut elvish = Vec::new();
+        write_completion(CompletionShell::Elvish, &mut elvish);
+        assert!(String::from_utf8(elvish)
+            .unwrap()
+            .contains("edit:completion:arg-completer"));
+
+        let mut nushell = Vec::new();
+        write_completion(CompletionShell::Nushell, &mut nushell);
+        let nushell = String::from_utf8(nushell).unwrap();
+        assert!(nushell.contains("export extern cert-tree ["));
+        assert!(nushell.contains("--oid-map"));
+    }
+
+    #[test]
+    fn test_shell_from_path() {
+        assert_eq!(shell_from_path("/bin/bash"), Some(CompletionShell::Bash));
+        assert_eq!(
+            shell_from_path("/usr/bin/elvish"),
+            Some(CompletionShell::Elvish)
+        );
+        assert_eq!(
+            shell_from_path("/opt/homebrew/bin/nu"),
+            Some(CompletionShell::Nushell)
+        );
+        // "nu" elsewhere in the path is not Nushell
+        assert_eq!(shell_from_path("/nu/bin/sh"), None);
+        assert_eq!(
+            CompletionShell::from_str("nu", false),
+            Ok(CompletionShell::Nushell)
+        );
+    }
+
+    #[test]
+    fn test_dry_run_writes_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("completions").join("cert-tree");
+        let options = InstallOptions {
+            dry_run: true,
+            force: false,
+        };
+
+        let message = install_completion_at(CompletionShell::Bash, &path, options, |_| {
+            panic!("a dry run never asks to overwrite")
+        })
+        .unwrap();
+
+        assert!(!path.exists());
+        assert!(message.contains(&format!("{} (would create)", path.display())));
+        assert!(message.contains(" bytes"));
+
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "old").unwrap();
+        let message =
+            install_completion_at(CompletionShell::Bash, &path, options, |_| false).unwrap();
+        assert!(message.contains("(would replace)"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+    }
+
+    #[test]
+    fn test_existing_file_needs_confirmation_or_force() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cert-tree.fish");
+        fs::write(&path, "old").unwrap();
+
+        // Declined: the file is left alone
+        let result = install_completion_at(
+            CompletionShell::Fish,
+            &path,
+            InstallOptions::default(),
+            |asked| {
+                assert_eq!(asked, path.as_path());
+                false
+            },
+        );
+        assert!(result.unwrap_err().contains("--force"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+
+        // Confirmed, or forced without asking: the file is replaced
+        install_completion_at(
+            CompletionShell::Fish,
+            &path,
+            InstallOptions::default(),
+            |_| true,
+        )
+        .unwrap();
+        assert_ne!(fs::read_to_string(&path).unwrap(), "old");
+
+        fs::write(&path, "old").unwrap();
+        let forced = InstallOptions {
+            dry_run: false,
+            force: true,
+        };
+        install_completion_at(CompletionShell::Fish, &path, forced, |_| {
+            panic!("--force never asks")
+        })
+        .unwrap();
+        assert_ne!(fs::read_to_string(&path).unwrap(), "old");
+
+        // A new file is written without asking
+        let fresh = dir.path().join("new.fish");
+        install_completion_at(
+            CompletionShell::Fish,
+            &fresh,
+            InstallOptions::default(),
+            |_| panic!("nothing to overwrite"),
+        )
+        .unwrap();
+        assert!(fresh.exists());
+    }
 }
@@ -0,0 +1,182 @@
+//! Certificate Transparency logs
+//!
+//! Names the logs behind embedded Signed Certificate Timestamps (RFC 6962
+//! section 3.2). A log is identified by its log ID, the SHA-256 hash of its
+//! public key, written here in base64 as in the published log lists.
+
+/// A known Certificate Transparency log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CtLog {
+    /// Base64 log ID
+    pub id: &'static str,
+    /// Description from the log list, e.g. "Google 'Pilot' log"
+    pub name: &'static str,
+    /// Organization running the log
+    pub operator: &'static str,
+}
+
+/// Logs recognized by their ID; SCTs from any other log show the bare ID
+///
+/// The usable 2025 and 2026 shards of the Google and Apple log lists, followed by
+/// retired logs still found in older certificates (and in the test fixtures).
+const KNOWN_LOGS: &[CtLog] = &[
+    CtLog {
+        id: "TnWjJ1yaEMM4W2zU3z9S6x3w4I4bjWnAsfpksWKaOd8=",
+        name: "Google 'Argon2025h1' log",
+        operator: "Google",
+    },
+    CtLog {
+        id: "EvFONL1TckyEBhnDjz96E/jntWKHiJxtMAWE6+WGJjo=",
+        name: "Google 'Argon2025h2' log",
+        operator: "Google",
+    },
+    CtLog {
+        id: "DleUvPOuqT4zGyyZB7P3kN+bwj1xMiXdIaklrGHFTiE=",
+        name: "Google 'Argon2026h1' log",
+        operator: "Google",
+    },
+    CtLog {
+        id: "1219ENGn9XfCx+lf1wC/+YLJM1pl4dCzAXMXwMjFaXc=",
+        name: "Google 'Argon2026h2' log",
+        operator: "Google",
+    },
+    CtLog {
+        id: "zxFW7tUufK/zh1vZaS6b6RpxZ0qwF+ysAdJbd87MOwg=",
+        name: "Google 'Xenon2025h1' log",
+        operator: "Google",
+    },
+    CtLog {
+        id: "3dzKNJXX4RYF55Uy+sef+D0cUN/bADoUEnYKLKy7yCo=",
+        name: "Google 'Xenon2025h2' log",
+        operator: "Google",
+    },
+    CtLog {
+        id: "lpdkv1VYl633Q4doNwhCd+nwOtX2pPM2bkakPw/KqcY=",
+        name: "Google 'Xenon2026h1' log",
+        operator: "Google",
+    },
+    CtLog {
+        id: "zPsPaoVxCWX+lZtTzumyfCLphVwNl422qX5UwP5MDbA=",
+        name: "Cloudflare 'Nimbus2025' Log",
+        operator: "Cloudflare",
+    },
+    CtLog {
+        id: "yzj3FYl8hKFEX1vB3fvJbvKaWc1HCmkFhbDLFMMUWOc=",
+        name: "Cloudflare 'Nimbus2026' Log",
+        operator: "Cloudflare",
+    },
+    CtLog {
+        id: "fVkeEuF4KnscYWd8Xv340IdcFKBOlZ65Ay/ZDowuebg=",
+        name: "DigiCert Yeti2025 Log",
+        operator: "DigiCert",
+    },
+    CtLog {
+        id: "5tIxY0B3jMEQQQbXcbnOwdJA9paEhvu6hzId/R43jlA=",
+        name: "DigiCert Nessie2025 Log",
+        operator: "DigiCert",
+    },
+    CtLog {
+        id: "ouMK5EXvva2bfjjtR2d3U9eCW4SU1yteGyzEuVCkR+c=",
+        name: "Let's Encrypt 'Oak2025h1'",
+        operator: "Let's Encrypt",
+    },
+    CtLog {
+        id: "DeHyMCvTDcFAYhIJ6lUu/Ed0fLHX6TDvDkIetH5OqjQ=",
+        name: "Let's Encrypt 'Oak2025h2'",
+        operator: "Let's Encrypt",
+    },
+    CtLog {
+        id: "GYbUxyiqb/66A294Kk0BkarOLXIxD67OXXBBLSVMx9Q=",
+        name: "Let's Encrypt 'Oak2026h1'",
+        operator: "Let's Encrypt",
+    },
+    CtLog {
+        id: "rKswcGzr7IQx9BPS9JFfER5CJEOx8qaMTzwrO6ceAsM=",
+        name: "Let's Encrypt 'Oak2026h2'",
+        operator: "Let's Encrypt",
+    },
+    CtLog {
+        id: "4JKz/AwdyOdoNh/eYbmWTQpSeBmKctZyxLBNpW1vVAQ=",
+        name: "Sectigo 'Sabre2025h1'",
+        operator: "Sectigo",
+    },
+    CtLog {
+        id: "GgT/SdBUHUCv9qDDv/HYxGcvTuzuI0BomGsXQC7ciX0=",
+        name: "Sectigo 'Sabre2025h2'",
+        operator: "Sectigo",
+    },
+    // Retired
+    CtLog {
+        id: "pLkJkLQYWBSHuxOizGdwCjw1mAT5G9+443fNDsgN3BA=",
+        name: "Google 'Pilot' log",
+        operator: "Google",
+    },
+    CtLog {
+        id: "3esdK3oNT6Ygi4GtgWhwfi6OnQHVXIiNPRHEzbbsvsw=",
+        name: "Symantec log",
+        operator: "Symantec",
+    },
+];
+
+/// The known log with this base64 log ID
+#[must_use]
+pub fn log_by_id(id: &str) -> Option<&'static CtLog> {
+    KNOWN_LOGS.iter().find(|log| log.id == id)
+}
+
+/// TLS `HashAlgorithm` name (RFC 5246 section 7.4.1.4.1)
+#[must_use]
+pub fn hash_algorithm_name(id: u8) -> String {
+    match id {
+        0 => "none".to_string(),
+        1 => "MD5".to_string(),
+        2 => "SHA-1".to_string(),
+        3 => "SHA-224".to_string(),
+        4 => "SHA-256".to_string(),
+        5 => "SHA-384".to_string(),
+        6 => "SHA-512".to_string(),
+        other => format!("unknown ({other})"),
+    }
+}
+
+/// TLS `SignatureAlgorithm` name (RFC 5246 section 7.4.1.4.1)
+#[must_use]
+pub fn signature_algorithm_name(id: u8) -> String {
+    match id {
+        0 => "anonymous".to_string(),
+        1 => "RSA".to_string(),
+        2 => "DSA".to_string(),
+        3 => "ECDSA".to_string(),
+        other => format!("unknown ({other})"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+
+    #[test]
+    fn test_known_log_ids_are_sha256_hashes() {
+        for log in KNOWN_LOGS {
+            let id = STANDARD.decode(log.id).unwrap();
+            assert_eq!(id.len(), 32, "{}", log.name);
+            assert_eq!(
+                log_by_id(log.id),
+                Some(log),
+                "duplicate ID for {}",
+                log.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_current_logs_are_named() {
+        let argon = log_by_id("TnWjJ1yaEMM4W2zU3z9S6x3w4I4bjWnAsfpksWKaOd8=").unwrap();
+        assert_eq!(argon.operator, "Google");
+        let oak = log_by_id("ouMK5EXvva2bfjjtR2d3U9eCW4SU1yteGyzEuVCkR+c=").unwrap();
+        assert_eq!(oak.operator, "Let's Encrypt");
+        assert!(log_by_id("AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=").is_none());
+    }
+}
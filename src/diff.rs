@@ -0,0 +1,211 @@
+//! Certificate comparison for the `diff` subcommand
+//!
+//! Two certificates or chains are compared position by position: the leaf
+//! against the leaf, the first intermediate against the first intermediate,
+//! and so on. Identical certificates (same SHA-256 fingerprint) are skipped.
+
+use cert_tree::models::CertificateInfo;
+use cert_tree::parser::extract_cn;
+use std::io::{self, Write};
+
+/// One field whose value differs between the two certificates
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub old: String,
+    pub new: String,
+}
+
+/// How the certificate at one chain position differs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CertificateDiff {
+    /// Present in both chains with different contents
+    Changed {
+        position: usize,
+        changes: Vec<FieldChange>,
+    },
+    /// Only in the old chain
+    Removed { position: usize, subject: String },
+    /// Only in the new chain
+    Added { position: usize, subject: String },
+}
+
+/// Renders one certificate field for comparison and display
+type RenderField = fn(&CertificateInfo) -> String;
+
+/// Fields compared between certificates, with how to render each
+const COMPARED_FIELDS: &[(&str, RenderField)] = &[
+    ("Subject", |cert| cert.subject.clone()),
+    ("Issuer", |cert| cert.issuer.clone()),
+    ("Serial Number", |cert| cert.serial_number.clone()),
+    ("Not Before", |cert| cert.not_before.clone()),
+    ("Not After", |cert| cert.not_after.clone()),
+    ("Public Key Algorithm", |cert| match cert.rsa_key_bits {
+        Some(bits) => format!("{} ({bits} bit)", cert.public_key_algorithm),
+        None => cert.public_key_algorithm.clone(),
+    }),
+    ("Signature Algorithm", |cert| {
+        cert.signature_algorithm.clone()
+    }),
+    ("CA", |cert| cert.is_ca.to_string()),
+    ("Key Usage", |cert| {
+        cert.key_usage.clone().unwrap_or_default()
+    }),
+    ("Extended Key Usage", |cert| {
+        cert.extended_key_usage.join(", ")
+    }),
+    ("Subject Alternative Names", |cert| {
+        cert.subject_alt_names.join(", ")
+    }),
+    ("SHA-256 Fingerprint", |cert| {
+        cert.sha256_fingerprint.clone()
+    }),
+];
+
+/// Compare two chains position by position; an empty result means they are identical
+#[must_use]
+pub fn diff_chains(old: &[CertificateInfo], new: &[CertificateInfo]) -> Vec<CertificateDiff> {
+    let mut diffs = Vec::new();
+    for position in 0..old.len().max(new.len()) {
+        match (old.get(position), new.get(position)) {
+            (Some(before), Some(after)) => {
+                if before.sha256_fingerprint == after.sha256_fingerprint {
+                    continue;
+                }
+                let changes = COMPARED_FIELDS
+                    .iter()
+                    .filter_map(|(field, render)| {
+                        let (old, new) = (render(before), render(after));
+                        (old != new).then_some(FieldChange { field, old, new })
+                    })
+                    .collect();
+                diffs.push(CertificateDiff::Changed { position, changes });
+            }
+            (Some(before), None) => diffs.push(CertificateDiff::Removed {
+                position,
+                subject: before.subject.clone(),
+            }),
+            (None, Some(after)) => diffs.push(CertificateDiff::Added {
+                position,
+                subject: after.subject.clone(),
+            }),
+            (None, None) => unreachable!("position is below the longer chain's length"),
+        }
+    }
+    diffs
+}
+
+/// Write the differences as `-`/`+` lines per certificate, red and green with `color`
+///
+/// # Errors
+///
+/// Returns an error if writing to `out` fails.
+pub fn write_diff(diffs: &[CertificateDiff], out: &mut impl Write, color: bool) -> io::Result<()> {
+    let (red, green, reset) = if color {
+        ("\x1b[31m", "\x1b[32m", "\x1b[0m")
+    } else {
+        ("", "", "")
+    };
+
+    if diffs.is_empty() {
+        return writeln!(out, "Certificates are identical");
+    }
+    for diff in diffs {
+        match diff {
+            CertificateDiff::Changed { position, changes } => {
+                writeln!(out, "Certificate {}:", position + 1)?;
+                for change in changes {
+                    writeln!(out, "  {}:", change.field)?;
+                    writeln!(out, "{red}  - {}{reset}", change.old)?;
+                    writeln!(out, "{green}  + {}{reset}", change.new)?;
+                }
+            }
+            CertificateDiff::Removed { position, subject } => {
+                writeln!(
+                    out,
+                    "{red}- Certificate {}: {}{reset}",
+                    position + 1,
+                    extract_cn(subject)
+                )?;
+            }
+            CertificateDiff::Added { position, subject } => {
+                writeln!(
+                    out,
+                    "{green}+ Certificate {}: {}{reset}",
+                    position + 1,
+                    extract_cn(subject)
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cert_tree::parser::parse_certificate_chain;
+
+    fn load(pem: &str) -> Vec<CertificateInfo> {
+        parse_certificate_chain(pem.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_identical_chains_have_no_diff() {
+        let chain = load(include_str!("../test/chain.pem"));
+        assert!(diff_chains(&chain, &chain).is_empty());
+
+        let mut out = Vec::new();
+        write_diff(&[], &mut out, false).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Certificates are identical\n"
+        );
+    }
+
+    #[test]
+    fn test_changed_certificate_lists_differing_fields() {
+        let chain = load(include_str!("../test/chain.pem"));
+        let other = load(include_str!("../test/self_signed_leaf.pem"));
+
+        let diffs = diff_chains(&chain[..1], &other);
+        let [CertificateDiff::Changed { position, changes }] = diffs.as_slice() else {
+            panic!("expected one changed certificate, got {diffs:?}");
+        };
+        assert_eq!(*position, 0);
+        let fields: Vec<&str> = changes.iter().map(|change| change.field).collect();
+        assert!(fields.contains(&"Issuer"));
+        assert!(fields.contains(&"SHA-256 Fingerprint"));
+        let fingerprint = changes.last().unwrap();
+        assert_eq!(fingerprint.old, chain[0].sha256_fingerprint);
+        assert_eq!(fingerprint.new, other[0].sha256_fingerprint);
+    }
+
+    #[test]
+    fn test_longer_chain_reports_added_and_removed_certificates() {
+        let chain = load(include_str!("../test/chain.pem"));
+
+        let shorter = diff_chains(&chain, &chain[..2]);
+        assert_eq!(
+            shorter,
+            [CertificateDiff::Removed {
+                position: 2,
+                subject: chain[2].subject.clone()
+            }]
+        );
+
+        let longer = diff_chains(&chain[..2], &chain);
+        assert!(matches!(
+            longer.as_slice(),
+            [CertificateDiff::Added { position: 2, .. }]
+        ));
+
+        let mut out = Vec::new();
+        write_diff(&shorter, &mut out, false).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(
+            text,
+            format!("- Certificate 3: {}\n", extract_cn(&chain[2].subject))
+        );
+    }
+}
@@ -1,22 +1,36 @@
-use crate::models::{
-    CertificateDisplayItem, CertificateInfo, CertificateNode, CertificateTree, ValidityStatus,
+use crate::fields::Field;
+use cert_tree::models::{
+    AnchorStatus, CertificateDisplayItem, CertificateInfo, CertificateNode, CertificateRole,
+    CertificateTree, DisplayTimezone, ExtensionInfo, FingerprintAlgorithm, HostnameCheck,
+    HostnameMatch, NameConstraints, RevocationStatus, SctInfo, TrustStatus, ValidationStatus,
+    ValidityStatus,
 };
-use chrono::DateTime;
+use cert_tree::tree::SortOrder;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers,
+        MouseButton, MouseEventKind,
+    },
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::{
+        self, disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+    },
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
     Terminal,
 };
-use std::io;
-use std::time::Duration;
+use serde::Serialize;
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Once};
+use std::thread;
+use std::time::{Duration, Instant};
 
 /// Maximum scroll limit for TUI details pane
 const MAX_SCROLL_LIMIT: u16 = 50;
@@ -24,70 +38,731 @@ const MAX_SCROLL_LIMIT: u16 = 50;
 /// Page size for navigation (items per page)
 const PAGE_SIZE: usize = 10;
 
+/// Rows of the single-certificate TUI's extension list before it scrolls
+const EXTENSION_LIST_ROWS: usize = 8;
+
+/// Share of the screen, in percent, covered by the extension value popup
+const POPUP_PERCENT: u16 = 70;
+
 /// Sleep duration in milliseconds for TUI initialization
 const SLEEP_MS: u64 = 50;
 
-/// Starting position for date column in text display
-const DATE_COLUMN_START: usize = 78;
-
-pub fn display_verbose(cert: &CertificateInfo) {
-    println!("Certificate Information:");
-    println!("======================");
-    let cn = crate::parser::extract_cn(&cert.subject);
-    println!("CN: {cn}");
-    println!("Issuer: {}", cert.issuer);
-    println!("Serial Number: {}", cert.serial_number);
-    println!("Validity:");
-    println!("  Not Before: {}", cert.not_before);
-    println!("  Not After: {}", cert.not_after);
-    println!("Public Key Algorithm: {}", cert.public_key_algorithm);
-    println!("Signature Algorithm: {}", cert.signature_algorithm);
-    println!("Version: {}", cert.version);
-    println!("Is CA: {}", cert.is_ca);
+/// Terminal width assumed for the text tree when stdout is not a terminal
+const DEFAULT_TERMINAL_WIDTH: usize = 80;
+
+/// Narrowest the CN and issuer columns of the table are shortened to
+const MIN_TABLE_NAME_WIDTH: usize = 8;
+
+/// Room kept right of the date column for the status and zoned date, e.g. "[EXPIRES SOON] [until: ... +02:00]"
+const DATE_COLUMN_RESERVED: usize = 50;
+
+/// Leftmost position of the date column, so deep tree levels keep some room for names
+const MIN_DATE_COLUMN_START: usize = 24;
+
+/// Marker shown next to weak signature algorithms and keys
+const WEAK_MARKER: &str = "⚠ WEAK";
+
+/// How much detail `display_verbose` writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Verbosity {
+    /// Only the CN and validity status
+    Quiet,
+    #[default]
+    Normal,
+    /// Adds fingerprints and raw extension values
+    Verbose,
+}
+
+/// Terminal color a status is drawn in
+trait StatusColor {
+    fn color(&self) -> Color;
+}
+
+impl StatusColor for ValidityStatus {
+    fn color(&self) -> Color {
+        match self {
+            ValidityStatus::Valid => Color::Green,
+            ValidityStatus::ExpiringSoon => Color::Yellow,
+            ValidityStatus::Expired => Color::Red,
+            ValidityStatus::NotYetValid => Color::Blue,
+        }
+    }
+}
+
+impl StatusColor for ValidationStatus {
+    fn color(&self) -> Color {
+        match self {
+            ValidationStatus::Valid => Color::Green,
+            ValidationStatus::InvalidChain(_) => Color::Red,
+            ValidationStatus::NotChecked => Color::Gray,
+        }
+    }
+}
+
+impl StatusColor for RevocationStatus {
+    fn color(&self) -> Color {
+        match self {
+            RevocationStatus::NotChecked => Color::Gray,
+            RevocationStatus::Good => Color::Green,
+            RevocationStatus::Revoked => Color::Red,
+            RevocationStatus::Unknown => Color::Yellow,
+        }
+    }
+}
+
+impl StatusColor for CertificateRole {
+    fn color(&self) -> Color {
+        match self {
+            CertificateRole::RootCa | CertificateRole::Intermediate | CertificateRole::Leaf => {
+                Color::White
+            }
+            CertificateRole::SelfSigned | CertificateRole::OrphanIntermediate => Color::Yellow,
+        }
+    }
+}
+
+impl StatusColor for TrustStatus {
+    fn color(&self) -> Color {
+        match self {
+            TrustStatus::NotChecked => Color::Gray,
+            TrustStatus::Anchored => Color::Green,
+            TrustStatus::Untrusted => Color::Red,
+        }
+    }
+}
+
+impl StatusColor for AnchorStatus {
+    fn color(&self) -> Color {
+        match self {
+            AnchorStatus::TrustedRoot => Color::Green,
+            AnchorStatus::UntrustedRoot => Color::Red,
+            AnchorStatus::IncompleteChain => Color::Yellow,
+        }
+    }
+}
+
+impl StatusColor for HostnameCheck {
+    fn color(&self) -> Color {
+        match self.status {
+            HostnameMatch::Match => Color::Green,
+            HostnameMatch::Mismatch => Color::Red,
+        }
+    }
+}
+
+/// Write the details of a single certificate at the given verbosity
+///
+/// # Errors
+///
+/// Returns an error if writing to `out` fails.
+pub fn display_verbose(
+    cert: &CertificateInfo,
+    verbosity: Verbosity,
+    zone: DisplayTimezone,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let cn = cert_tree::parser::extract_cn(&cert.subject);
+    if verbosity == Verbosity::Quiet {
+        let status = ValidityStatus::from_dates(cert.not_before_dt, cert.not_after_dt);
+        return writeln!(out, "{cn}: {} ({})", status.text(), cert.expiry_text());
+    }
+
+    writeln!(out, "Certificate Information:")?;
+    writeln!(out, "======================")?;
+    writeln!(out, "CN: {cn}")?;
+    let cns = cert_tree::parser::extract_all_cns(&cert.subject);
+    if verbosity == Verbosity::Verbose && cns.len() > 1 {
+        writeln!(out, "All CNs: {}", cns.join("; "))?;
+    }
+    writeln!(out, "Issuer: {}", cert.issuer)?;
+    writeln!(out, "Serial Number: {}", cert.serial_number)?;
+    writeln!(out, "Validity:")?;
+    writeln!(out, "  Not Before: {}", cert.not_before_text(zone))?;
+    writeln!(
+        out,
+        "  Not After: {} ({})",
+        cert.not_after_text(zone),
+        cert.expiry_text()
+    )?;
+    if cert.is_weak_key() {
+        writeln!(
+            out,
+            "Public Key Algorithm: {} {WEAK_MARKER}",
+            cert.public_key_algorithm
+        )?;
+    } else {
+        writeln!(out, "Public Key Algorithm: {}", cert.public_key_algorithm)?;
+    }
+    if cert.is_weak_signature() {
+        writeln!(
+            out,
+            "Signature Algorithm: {} {WEAK_MARKER}",
+            cert.signature_algorithm
+        )?;
+    } else {
+        writeln!(out, "Signature Algorithm: {}", cert.signature_algorithm)?;
+    }
+    writeln!(out, "Version: {}", cert.version_display())?;
+    writeln!(out, "Is CA: {}", cert.is_ca_display())?;
+    if let Some(check) = &cert.hostname_check {
+        writeln!(out, "Hostname: {}", check.text())?;
+    }
+    if let Some(connection) = &cert.tls_connection {
+        writeln!(out, "TLS Connection: {}", connection.text())?;
+        writeln!(out, "TLS Verification: {}", connection.verification_text())?;
+    }
+    if verbosity == Verbosity::Verbose {
+        if let Some(connection) = &cert.tls_connection {
+            writeln!(out, "TLS Handshake: {}", connection.handshake_text())?;
+        }
+        writeln!(out, "SHA-256 Fingerprint: {}", cert.sha256_fingerprint)?;
+        writeln!(out, "SHA-1 Fingerprint: {}", cert.sha1_fingerprint)?;
+        writeln!(out, "Size: {}", cert.size_text())?;
+    }
 
     if let Some(ku) = &cert.key_usage {
-        println!("Key Usage: {ku}");
+        writeln!(out, "Key Usage: {ku}")?;
+    }
+
+    if !cert.extended_key_usage.is_empty() {
+        writeln!(out, "Extended Key Usage:")?;
+        for purpose in &cert.extended_key_usage {
+            writeln!(out, "  {purpose}")?;
+        }
+    }
+
+    if !cert.policies.is_empty() {
+        writeln!(out, "Certificate Policies:")?;
+        for policy in &cert.policies {
+            writeln!(out, "  {policy}")?;
+        }
+    }
+
+    if let Some(constraints) = &cert.name_constraints {
+        writeln!(out, "Name Constraints:")?;
+        for permitted in &constraints.permitted {
+            writeln!(out, "  Permitted: {permitted}")?;
+        }
+        for excluded in &constraints.excluded {
+            writeln!(out, "  Excluded: {excluded}")?;
+        }
     }
 
     if !cert.subject_alt_names.is_empty() {
-        println!("Subject Alternative Names:");
+        writeln!(out, "Subject Alternative Names:")?;
         for san in &cert.subject_alt_names {
-            println!("  {san}");
+            writeln!(out, "  {san}")?;
+        }
+    }
+
+    for (title, urls) in [
+        ("CRL Distribution Points", &cert.crl_urls),
+        ("OCSP Responders", &cert.ocsp_urls),
+        ("CA Issuers", &cert.ca_issuer_urls),
+    ] {
+        if !urls.is_empty() {
+            writeln!(out, "{title}:")?;
+            for url in urls {
+                writeln!(out, "  {url}")?;
+            }
+        }
+    }
+
+    if !cert.scts.is_empty() {
+        writeln!(out, "Signed Certificate Timestamps: {}", cert.scts.len())?;
+        for sct in &cert.scts {
+            writeln!(out, "  {}", sct.text())?;
+        }
+    }
+
+    if !cert.violations.is_empty() {
+        writeln!(out, "Conformance Violations:")?;
+        for violation in &cert.violations {
+            writeln!(out, "  {violation}")?;
         }
     }
 
-    println!("Extensions:");
+    writeln!(out, "Extensions:")?;
     for ext in &cert.extensions {
-        println!(
-            "  {} ({}) - {}",
-            ext.name.as_deref().unwrap_or(&ext.oid),
-            if ext.critical {
-                "critical"
-            } else {
-                "non-critical"
-            },
-            ext.value
-        );
+        let name = ext.name.as_deref().unwrap_or(&ext.oid);
+        let criticality = if ext.critical {
+            "critical"
+        } else {
+            "non-critical"
+        };
+        if verbosity == Verbosity::Verbose {
+            writeln!(out, "  {name} ({criticality}) - {}", ext.value)?;
+        } else {
+            writeln!(out, "  {name} ({criticality})")?;
+        }
+    }
+
+    Ok(())
+}
+
+// Function to explain signature algorithm in simple terms
+fn explain_signature_algorithm(alg: &str) -> String {
+    if alg.contains("Ed25519") || alg.contains("Ed448") {
+        "This certificate uses the Edwards-curve Digital Signature Algorithm (EdDSA). It's a modern signature scheme built on twisted Edwards curves that is fast, uses small keys and signatures, and is designed to avoid common implementation pitfalls such as weak random numbers. Like other signature algorithms, it proves the certificate was issued by the holder of the signing key and hasn't been tampered with.".to_string()
+    } else if alg.contains("RSA") {
+        "This certificate uses RSA encryption with hashing. RSA is like a digital lock that only the certificate issuer has the key to open. The hashing creates a unique fingerprint of the certificate data. Together, they create a digital signature that proves the certificate is genuine and hasn't been tampered with. This is essential for secure websites and encrypted communications.".to_string()
+    } else if alg.contains("ECDSA") {
+        "This certificate uses Elliptic Curve Digital Signature Algorithm (ECDSA). It's a modern, efficient way to create digital signatures using advanced mathematics with elliptic curves. Like RSA, it creates a unique signature that proves the certificate's authenticity, but it's faster and uses smaller keys. This helps keep internet communications secure and private.".to_string()
+    } else if alg.contains("DSA") {
+        "This certificate uses Digital Signature Algorithm (DSA). It's a method for creating digital signatures that verify the authenticity of the certificate. Using mathematical techniques, it creates a unique code that only the legitimate issuer can produce. This prevents fake certificates and ensures trust in online communications.".to_string()
+    } else {
+        "This is a cryptographic signature method that verifies the certificate's authenticity. It uses mathematical algorithms to create a unique digital signature that proves the certificate is legitimate and hasn't been altered. This is crucial for establishing secure and trustworthy connections on the internet.".to_string()
+    }
+}
+
+/// Render a titled list of URLs for the TUI details panes (empty when there are none)
+fn url_section_lines<'a>(title: &'a str, urls: &'a [String]) -> Vec<Line<'a>> {
+    if urls.is_empty() {
+        return Vec::new();
+    }
+
+    let mut lines = vec![Line::from(vec![Span::styled(
+        title,
+        Style::default().fg(Color::Blue),
+    )])];
+    for url in urls {
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled(url, Style::default().fg(Color::Cyan)),
+        ]));
+    }
+    lines
+}
+
+/// The SCT count as a section title for the TUI details pane, then one line per SCT
+fn sct_lines(scts: &[SctInfo]) -> Vec<Line<'static>> {
+    if scts.is_empty() {
+        return Vec::new();
+    }
+
+    let mut lines = vec![Line::from(vec![
+        Span::styled(
+            "Signed Certificate Timestamps: ",
+            Style::default().fg(Color::Blue),
+        ),
+        Span::styled(scts.len().to_string(), Style::default().fg(Color::White)),
+    ])];
+    for sct in scts {
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled(sct.text(), Style::default().fg(Color::Cyan)),
+        ]));
+    }
+    lines
+}
+
+/// Render a DN for the TUI details pane as a titled list, one RDN component per line
+///
+/// A DN without `attr=value` components (e.g. an empty subject) stays on the title line.
+fn dn_lines<'a>(title: &'a str, dn: &'a str) -> Vec<Line<'a>> {
+    let components = cert_tree::parser::dn_components(dn);
+    if components.is_empty() {
+        return vec![Line::from(vec![
+            Span::styled(format!("{title}: "), Style::default().fg(Color::Blue)),
+            Span::styled(dn, Style::default().fg(Color::White)),
+        ])];
+    }
+
+    let mut lines = vec![Line::from(vec![Span::styled(
+        format!("{title}:"),
+        Style::default().fg(Color::Blue),
+    )])];
+    for (attribute, value) in components {
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled(format!("{attribute}: "), Style::default().fg(Color::Blue)),
+            Span::styled(value, Style::default().fg(Color::White)),
+        ]));
+    }
+    lines
+}
+
+/// Render Name Constraints for the TUI details panes: permitted entries green, excluded red
+fn name_constraint_lines(constraints: &NameConstraints) -> Vec<Line<'_>> {
+    let mut lines = vec![Line::from(vec![Span::styled(
+        "Name Constraints:",
+        Style::default().fg(Color::Blue),
+    )])];
+    let entries = [
+        ("Permitted: ", &constraints.permitted, Color::Green),
+        ("Excluded: ", &constraints.excluded, Color::Red),
+    ];
+    for (label, names, color) in entries {
+        for name in names {
+            lines.push(Line::from(vec![
+                Span::raw("  "),
+                Span::styled(label, Style::default().fg(color)),
+                Span::styled(name, Style::default().fg(Color::White)),
+            ]));
+        }
+    }
+    lines
+}
+
+/// "critical" in red or "non-critical" in green, for extension lists
+fn criticality_span(critical: bool) -> Span<'static> {
+    if critical {
+        Span::styled("critical", Style::default().fg(Color::Red))
+    } else {
+        Span::styled("non-critical", Style::default().fg(Color::Green))
+    }
+}
+
+/// Content of the single-certificate TUI's extension popup
+///
+/// The header names the extension, its OID and criticality; the decoded value
+/// follows with each `;`- or `,`-separated item on its own line.
+fn extension_popup_lines(ext: &ExtensionInfo) -> Vec<Line<'_>> {
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Name: ", Style::default().fg(Color::Blue)),
+            Span::styled(
+                ext.name.as_deref().unwrap_or("Unknown"),
+                Style::default().fg(Color::Cyan),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("OID: ", Style::default().fg(Color::Blue)),
+            Span::styled(&ext.oid, Style::default().fg(Color::White)),
+        ]),
+        Line::from(vec![
+            Span::styled("Criticality: ", Style::default().fg(Color::Blue)),
+            criticality_span(ext.critical),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled("Value:", Style::default().fg(Color::Blue))),
+    ];
+    for item in ext.value.split("; ").flat_map(|group| group.split(", ")) {
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled(item, Style::default().fg(Color::White)),
+        ]));
+    }
+    lines
+}
+
+/// Border style marking which TUI pane has keyboard focus
+fn focus_style(active: bool) -> Style {
+    if active {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    }
+}
+
+/// Area of `percent` of `area`'s width and height, centered within it
+fn centered_rect(area: Rect, percent: u16) -> Rect {
+    let width = area.width * percent / 100;
+    let height = area.height * percent / 100;
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// Red weak marker for the TUI when `weak`, or an empty span
+fn weak_span(weak: bool) -> Span<'static> {
+    if weak {
+        Span::styled(
+            format!(" {WEAK_MARKER}"),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )
+    } else {
+        Span::raw("")
+    }
+}
+
+/// Write a certificate or certificate tree as pretty-printed JSON
+///
+/// # Errors
+///
+/// Returns an error if serialization or writing to `out` fails.
+pub fn display_json<T: Serialize>(value: &T, out: &mut impl Write) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut *out, value)?;
+    writeln!(out)
+}
+
+/// Write each certificate (or `--fields` record) as one compact JSON object per line (NDJSON)
+///
+/// `out` is flushed after every line so consumers can process certificates as they arrive.
+///
+/// # Errors
+///
+/// Returns an error if serialization or writing to `out` fails.
+pub fn display_ndjson<T: Serialize>(
+    records: impl IntoIterator<Item = T>,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    for record in records {
+        serde_json::to_writer(&mut *out, &record)?;
+        writeln!(out)?;
+        out.flush()?;
+    }
+    Ok(())
+}
+
+/// Re-encode each certificate's DER as a `CERTIFICATE` PEM block, in the given order
+///
+/// # Errors
+///
+/// Returns an error if writing to `out` fails.
+pub fn display_pem<'a>(
+    certificates: impl IntoIterator<Item = &'a CertificateInfo>,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    for cert_info in certificates {
+        let block = pem::Pem::new("CERTIFICATE", cert_info.der.clone());
+        out.write_all(pem::encode(&block).as_bytes())?;
+    }
+    Ok(())
+}
+
+/// Write one fingerprint per line with nothing else, for `grep` and `diff`
+///
+/// # Errors
+///
+/// Returns an error if writing to `out` fails.
+pub fn display_fingerprints<'a>(
+    certificates: impl IntoIterator<Item = &'a CertificateInfo>,
+    algorithm: FingerprintAlgorithm,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    for cert_info in certificates {
+        writeln!(out, "{}", algorithm.of(cert_info))?;
+    }
+    Ok(())
+}
+
+/// Quote a CSV field if it contains a delimiter, quote or line break (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Write every certificate in the tree as one CSV row, for expiry monitoring
+///
+/// # Errors
+///
+/// Returns an error if writing to `out` fails.
+pub fn display_csv(
+    tree: &CertificateTree,
+    sort: SortOrder,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    writeln!(
+        out,
+        "cn,issuer_cn,serial,not_before,not_after,days_until_expiry,validity_status,is_ca"
+    )?;
+
+    for item in flatten_certificate_tree(tree, sort) {
+        let cert = &item.certificate_info;
+        let days = cert.days_until_expiry().to_string();
+        let fields = [
+            cert_tree::parser::extract_cn(&cert.subject),
+            cert_tree::parser::extract_cn(&cert.issuer),
+            cert.serial_number.clone(),
+            cert.not_before.clone(),
+            cert.not_after.clone(),
+            days,
+            item.validity_status.label().to_string(),
+            cert.is_ca.to_string(),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        writeln!(out, "{}", row.join(","))?;
+    }
+
+    Ok(())
+}
+
+/// Write every certificate in the tree as a row of a bordered table: CN, issuer, expiry and status
+///
+/// The CN and issuer columns are shortened with `…` when the table would be
+/// wider than the terminal (80 columns when stdout is not a terminal).
+///
+/// # Errors
+///
+/// Returns an error if writing to `out` fails.
+pub fn display_table(
+    tree: &CertificateTree,
+    sort: SortOrder,
+    zone: DisplayTimezone,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let rows: Vec<[String; 4]> = flatten_certificate_tree(tree, sort)
+        .iter()
+        .map(|item| {
+            let cert = &item.certificate_info;
+            [
+                cert_tree::parser::extract_cn(&cert.subject),
+                cert_tree::parser::extract_cn(&cert.issuer),
+                cert.not_after_text(zone),
+                item.validity_status.label().to_string(),
+            ]
+        })
+        .collect();
+    write_table(
+        &["CN", "Issuer", "Expiry", "Status"],
+        &rows,
+        terminal_width(),
+        out,
+    )
+}
+
+/// Write `rows` under `header` as a box-drawn table at most `width` columns wide
+///
+/// Only the first two columns give way when space runs out; the others keep
+/// their natural width.
+fn write_table(
+    header: &[&str; 4],
+    rows: &[[String; 4]],
+    width: usize,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let mut widths = header.map(|title| title.chars().count());
+    for row in rows {
+        for (column, value) in row.iter().enumerate() {
+            widths[column] = widths[column].max(value.chars().count());
+        }
+    }
+
+    // Each column is padded by a space on both sides and followed by a border
+    let fixed = 1 + widths[2] + widths[3] + 3 * 4;
+    let available = width.saturating_sub(fixed).max(2 * MIN_TABLE_NAME_WIDTH);
+    if widths[0] + widths[1] > available {
+        let half = available / 2;
+        if widths[0] <= half {
+            widths[1] = available - widths[0];
+        } else if widths[1] <= half {
+            widths[0] = available - widths[1];
+        } else {
+            widths[0] = half;
+            widths[1] = available - half;
+        }
+    }
+
+    let border = |left: &str, middle: &str, right: &str| {
+        let segments: Vec<String> = widths.iter().map(|&w| "─".repeat(w + 2)).collect();
+        format!("{left}{}{right}", segments.join(middle))
+    };
+    let line = |values: [&str; 4]| {
+        let cells: Vec<String> = values
+            .iter()
+            .zip(widths)
+            .map(|(value, w)| format!(" {:<w$} ", truncate_cell(value, w)))
+            .collect();
+        format!("│{}│", cells.join("│"))
+    };
+
+    writeln!(out, "{}", border("┌", "┬", "┐"))?;
+    writeln!(out, "{}", line(*header))?;
+    writeln!(out, "{}", border("├", "┼", "┤"))?;
+    for row in rows {
+        writeln!(out, "{}", line([&row[0], &row[1], &row[2], &row[3]]))?;
+    }
+    writeln!(out, "{}", border("└", "┴", "┘"))
+}
+
+/// `value` cut to `width` characters, ending in `…` when shortened
+fn truncate_cell(value: &str, width: usize) -> String {
+    if value.chars().count() <= width {
+        value.to_string()
+    } else {
+        let kept: String = value.chars().take(width.saturating_sub(1)).collect();
+        format!("{kept}…")
     }
 }
 
-pub fn display_tui(cert: &CertificateInfo) -> Result<(), Box<dyn std::error::Error>> {
-    // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
+/// Write the chosen fields of a certificate as `Label: value` lines, in the given order
+///
+/// Validity dates are shown in `zone`, like the full certificate display.
+///
+/// # Errors
+///
+/// Returns an error if writing to `out` fails.
+pub fn display_fields(
+    cert: &CertificateInfo,
+    fields: &[Field],
+    zone: DisplayTimezone,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    for field in fields {
+        writeln!(out, "{}: {}", field.label(), field.zoned_text(cert, zone))?;
+    }
+    Ok(())
+}
+
+/// Write the chosen fields as CSV, with the field names as the header row
+///
+/// # Errors
+///
+/// Returns an error if writing to `out` fails.
+pub fn display_fields_csv<'a>(
+    certificates: impl IntoIterator<Item = &'a CertificateInfo>,
+    fields: &[Field],
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let header: Vec<String> = fields.iter().map(|field| field.name()).collect();
+    writeln!(out, "{}", header.join(","))?;
+    for cert in certificates {
+        let row: Vec<String> = fields
+            .iter()
+            .map(|field| csv_field(&field.text(cert)))
+            .collect();
+        writeln!(out, "{}", row.join(","))?;
+    }
+    Ok(())
+}
+
+/// Write a certificate or certificate tree as YAML
+///
+/// # Errors
+///
+/// Returns an error if serialization or writing to `out` fails.
+pub fn display_yaml<T: Serialize>(value: &T, out: &mut impl Write) -> io::Result<()> {
+    serde_yaml::to_writer(out, value).map_err(io::Error::other)
+}
+
+/// Show a single certificate in the interactive terminal UI
+///
+/// # Errors
+///
+/// Returns an error if the terminal cannot be set up, drawn to or restored.
+pub fn display_tui(
+    cert: &CertificateInfo,
+    zone: DisplayTimezone,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Setup terminal; the guard restores it if anything below panics or returns early
+    let guard = TerminalGuard::enter()?;
+    let backend = CrosstermBackend::new(io::stdout());
     let mut terminal = Terminal::new(backend)?;
 
-    let validity_status = ValidityStatus::from_dates(&cert.not_after);
+    let validity_status = ValidityStatus::from_dates(cert.not_before_dt, cert.not_after_dt);
 
     // Force initial clear and small delay to ensure proper layout on startup
     terminal.clear()?;
     std::thread::sleep(Duration::from_millis(SLEEP_MS));
 
-    // Scroll state for certificate details pane (unused in single cert view)
-    #[allow(unused_variables)]
-    let details_scroll: u16 = 0;
+    // Scroll state for certificate details pane
+    let mut details_scroll: u16 = 0;
+
+    // Extension list selection; Tab moves focus between it and the details pane
+    let mut extension_state = ratatui::widgets::ListState::default();
+    extension_state.select(Some(0));
+    let mut extensions_active = false;
+    let mut popup: Option<usize> = None;
+    let mut popup_scroll: u16 = 0;
+    let extensions_height =
+        u16::try_from(cert.extensions.len().min(EXTENSION_LIST_ROWS)).unwrap_or(0) + 2;
 
     loop {
         terminal.draw(|f| {
@@ -97,9 +772,10 @@ pub fn display_tui(cert: &CertificateInfo) -> Result<(), Box<dyn std::error::Err
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([
-                    Constraint::Length(3), // Title
-                    Constraint::Min(10),   // Certificate info
-                    Constraint::Length(3), // Footer
+                    Constraint::Length(3),                 // Title
+                    Constraint::Min(6),                    // Certificate info
+                    Constraint::Length(extensions_height), // Extensions
+                    Constraint::Length(3),                 // Footer
                 ])
                 .split(size);
 
@@ -114,9 +790,9 @@ pub fn display_tui(cert: &CertificateInfo) -> Result<(), Box<dyn std::error::Err
             f.render_widget(title, chunks[0]);
 
             // Certificate information
-            let cn = crate::parser::extract_cn(&cert.subject);
+            let cn = cert_tree::parser::extract_cn(&cert.subject);
             let sig_explanation =
-                crate::parser::explain_signature_algorithm(&cert.signature_algorithm);
+                explain_signature_algorithm(&cert.signature_algorithm);
             let mut cert_info = vec![
                 Line::from(vec![
                     Span::styled("CN: ", Style::default().fg(Color::Blue)),
@@ -132,9 +808,13 @@ pub fn display_tui(cert: &CertificateInfo) -> Result<(), Box<dyn std::error::Err
                 ]),
                 Line::from(vec![
                     Span::styled("Validity: ", Style::default().fg(Color::Blue)),
-                    Span::styled(&cert.not_before, Style::default().fg(Color::White)),
+                    Span::styled(cert.not_before_text(zone), Style::default().fg(Color::White)),
                     Span::raw(" → "),
-                    Span::styled(&cert.not_after, Style::default().fg(Color::White)),
+                    Span::styled(cert.not_after_text(zone), Style::default().fg(Color::White)),
+                    Span::styled(
+                        format!(" ({})", cert.expiry_text()),
+                        Style::default().fg(validity_status.color()),
+                    ),
                 ]),
                 Line::from(vec![
                     Span::styled("Status: ", Style::default().fg(Color::Blue)),
@@ -149,19 +829,25 @@ pub fn display_tui(cert: &CertificateInfo) -> Result<(), Box<dyn std::error::Err
                         &cert.public_key_algorithm,
                         Style::default().fg(Color::Green),
                     ),
+                    weak_span(cert.is_weak_key()),
                 ]),
                 Line::from(vec![
                     Span::styled("Signature Algorithm: ", Style::default().fg(Color::Blue)),
                     Span::styled(sig_explanation.as_str(), Style::default().fg(Color::Green)),
+                    weak_span(cert.is_weak_signature()),
                 ]),
                 Line::from(vec![
                     Span::styled("Version: ", Style::default().fg(Color::Blue)),
-                    Span::styled(cert.version.to_string(), Style::default().fg(Color::White)),
+                    Span::styled(cert.version_display(), Style::default().fg(Color::White)),
+                ]),
+                Line::from(vec![
+                    Span::styled("Size: ", Style::default().fg(Color::Blue)),
+                    Span::styled(cert.size_text(), Style::default().fg(Color::White)),
                 ]),
                 Line::from(vec![
                     Span::styled("Is CA: ", Style::default().fg(Color::Blue)),
                     Span::styled(
-                        cert.is_ca.to_string(),
+                        cert.is_ca_display(),
                         Style::default().fg(if cert.is_ca {
                             Color::Yellow
                         } else {
@@ -169,6 +855,14 @@ pub fn display_tui(cert: &CertificateInfo) -> Result<(), Box<dyn std::error::Err
                         }),
                     ),
                 ]),
+                Line::from(vec![
+                    Span::styled("SHA-256: ", Style::default().fg(Color::Blue)),
+                    Span::styled(&cert.sha256_fingerprint, Style::default().fg(Color::White)),
+                ]),
+                Line::from(vec![
+                    Span::styled("SHA-1: ", Style::default().fg(Color::Blue)),
+                    Span::styled(&cert.sha1_fingerprint, Style::default().fg(Color::White)),
+                ]),
             ];
 
             if let Some(ku) = &cert.key_usage {
@@ -178,6 +872,56 @@ pub fn display_tui(cert: &CertificateInfo) -> Result<(), Box<dyn std::error::Err
                 ]));
             }
 
+            if !cert.extended_key_usage.is_empty() {
+                cert_info.push(Line::from(vec![
+                    Span::styled("Extended Key Usage: ", Style::default().fg(Color::Blue)),
+                    Span::styled(
+                        cert.extended_key_usage.join(", "),
+                        Style::default().fg(Color::Magenta),
+                    ),
+                ]));
+            }
+
+            if let Some(check) = &cert.hostname_check {
+                cert_info.push(Line::from(vec![
+                    Span::styled("Hostname: ", Style::default().fg(Color::Blue)),
+                    Span::styled(check.text(), Style::default().fg(check.color())),
+                ]));
+            }
+
+            if let Some(connection) = &cert.tls_connection {
+                cert_info.push(Line::from(vec![
+                    Span::styled("TLS Connection: ", Style::default().fg(Color::Blue)),
+                    Span::styled(connection.text(), Style::default().fg(Color::Green)),
+                ]));
+                let verification_color = if connection.verification_error.is_none() {
+                    Color::Green
+                } else {
+                    Color::Red
+                };
+                cert_info.push(Line::from(vec![
+                    Span::styled("TLS Verification: ", Style::default().fg(Color::Blue)),
+                    Span::styled(
+                        connection.verification_text(),
+                        Style::default().fg(verification_color),
+                    ),
+                ]));
+            }
+
+            if !cert.policies.is_empty() {
+                cert_info.push(Line::from(vec![
+                    Span::styled("Certificate Policies: ", Style::default().fg(Color::Blue)),
+                    Span::styled(
+                        cert.policies.join(", "),
+                        Style::default().fg(Color::Magenta),
+                    ),
+                ]));
+            }
+
+            if let Some(constraints) = &cert.name_constraints {
+                cert_info.extend(name_constraint_lines(constraints));
+            }
+
             if !cert.subject_alt_names.is_empty() {
                 cert_info.push(Line::from(vec![
                     Span::styled("Subject Alt Names: ", Style::default().fg(Color::Blue)),
@@ -188,73 +932,446 @@ pub fn display_tui(cert: &CertificateInfo) -> Result<(), Box<dyn std::error::Err
                 ]));
             }
 
-            let cert_paragraph = Paragraph::new(cert_info).wrap(Wrap { trim: true }).block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("Certificate Details"),
-            );
+            cert_info.extend(url_section_lines(
+                "CRL Distribution Points:",
+                &cert.crl_urls,
+            ));
+            cert_info.extend(url_section_lines("OCSP Responders:", &cert.ocsp_urls));
+            cert_info.extend(url_section_lines("CA Issuers:", &cert.ca_issuer_urls));
+            cert_info.extend(sct_lines(&cert.scts));
+
+            for violation in &cert.violations {
+                cert_info.push(Line::from(vec![
+                    Span::styled("Violation: ", Style::default().fg(Color::Blue)),
+                    Span::styled(violation, Style::default().fg(Color::Red)),
+                ]));
+            }
+
+            let cert_paragraph = Paragraph::new(cert_info)
+                .wrap(Wrap { trim: true })
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title("Certificate Details")
+                        .border_style(focus_style(!extensions_active)),
+                )
+                .scroll((details_scroll, 0));
             f.render_widget(cert_paragraph, chunks[1]);
 
+            let extension_items: Vec<ListItem> = cert
+                .extensions
+                .iter()
+                .map(|ext| {
+                    ListItem::new(Line::from(vec![
+                        Span::styled(
+                            ext.name.as_deref().unwrap_or(&ext.oid),
+                            Style::default().fg(Color::Cyan),
+                        ),
+                        Span::raw(" ("),
+                        criticality_span(ext.critical),
+                        Span::raw(")"),
+                    ]))
+                })
+                .collect();
+            let extension_list = List::new(extension_items)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(format!("Extensions ({})", cert.extensions.len()))
+                        .border_style(focus_style(extensions_active)),
+                )
+                .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+                .highlight_symbol(if extensions_active { ">> " } else { "   " });
+            f.render_stateful_widget(extension_list, chunks[2], &mut extension_state);
+
             // Footer with instructions
-            let footer = Paragraph::new("Press 'q' to quit")
+            let footer_text = if popup.is_some() {
+                "↑/↓ j/k, PgUp/PgDn, Ctrl-d/u, g/G: Scroll Value | Enter/Esc: Close"
+            } else if extensions_active {
+                "↑/↓ j/k, PgUp/PgDn, Ctrl-d/u, g/G: Select | Enter: Show Value | Tab: Details | 'q' Quit"
+            } else {
+                "↑/↓ j/k, PgUp/PgDn, Ctrl-d/u, g/G: Scroll | Tab: Extensions | 'q' Quit"
+            };
+            let footer = Paragraph::new(footer_text)
                 .style(Style::default().fg(Color::Gray))
                 .block(Block::default().borders(Borders::ALL));
-            f.render_widget(footer, chunks[2]);
+            f.render_widget(footer, chunks[3]);
+
+            if let Some(ext) = popup.and_then(|index| cert.extensions.get(index)) {
+                let area = centered_rect(size, POPUP_PERCENT);
+                let value = Paragraph::new(extension_popup_lines(ext))
+                    .wrap(Wrap { trim: false })
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .title("Extension Value")
+                            .border_style(focus_style(true)),
+                    )
+                    .scroll((popup_scroll, 0));
+                f.render_widget(ratatui::widgets::Clear, area);
+                f.render_widget(value, area);
+            }
         })?;
 
         // Handle input
+        // - Tab: Move focus between the details pane and the extension list
+        // - Enter: Open the selected extension's decoded value; Enter/Esc/'q' close it
+        // - Navigation keys scroll the details or popup, or move the extension selection
         if event::poll(Duration::from_millis(100))? {
             if let Event::Key(key) = event::read()? {
+                if popup.is_some() {
+                    match key.code {
+                        KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter => popup = None,
+                        _ => {
+                            if let Some(action) = nav_action(&key) {
+                                popup_scroll = scroll_offset(popup_scroll, action);
+                            }
+                        }
+                    }
+                    continue;
+                }
+
                 match key.code {
                     KeyCode::Char('q') | KeyCode::Esc => break,
-                    _ => {}
+                    KeyCode::Tab if !cert.extensions.is_empty() => {
+                        extensions_active = !extensions_active;
+                    }
+                    KeyCode::Enter if extensions_active => {
+                        popup = extension_state.selected();
+                        popup_scroll = 0;
+                    }
+                    _ => {
+                        if let Some(action) = nav_action(&key) {
+                            if extensions_active {
+                                let selected = extension_state.selected().unwrap_or(0);
+                                extension_state.select(Some(select_index(
+                                    selected,
+                                    cert.extensions.len(),
+                                    action,
+                                )));
+                            } else {
+                                details_scroll = scroll_offset(details_scroll, action);
+                            }
+                        }
+                    }
                 }
             }
         }
     }
 
     // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    guard.restore()?;
 
     Ok(())
 }
 
-pub fn display_certificate_tree_text(tree: &CertificateTree) {
+/// Puts the terminal back in cooked mode on the main screen when dropped
+///
+/// An early `?` return inside a TUI would otherwise leave raw mode and the
+/// alternate screen behind, and the shell unusable. Panics are covered by the
+/// hook [`TerminalGuard::enter`] installs, since release builds abort instead
+/// of unwinding and never run this guard.
+struct TerminalGuard<R: FnMut() -> io::Result<()> = fn() -> io::Result<()>> {
+    restore: Option<R>,
+}
+
+impl TerminalGuard {
+    /// Switch to raw mode and the alternate screen with mouse capture
+    fn enter() -> io::Result<Self> {
+        install_panic_restore();
+        enable_raw_mode()?;
+        TERMINAL_ACTIVE.store(true, Ordering::SeqCst);
+        // Armed before entering the alternate screen so a failure there is undone too
+        let guard = TerminalGuard {
+            restore: Some(restore_terminal as fn() -> io::Result<()>),
+        };
+        execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+        Ok(guard)
+    }
+}
+
+impl<R: FnMut() -> io::Result<()>> TerminalGuard<R> {
+    /// Restore now, reporting the errors that a drop has to ignore
+    fn restore(mut self) -> io::Result<()> {
+        self.restore.take().map_or(Ok(()), |mut restore| restore())
+    }
+}
+
+impl<R: FnMut() -> io::Result<()>> Drop for TerminalGuard<R> {
+    fn drop(&mut self) {
+        if let Some(mut restore) = self.restore.take() {
+            let _ = restore();
+        }
+    }
+}
+
+/// Whether a TUI currently holds the terminal in raw mode on the alternate screen
+static TERMINAL_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Restore the terminal before the panic message is printed, then run the previous hook
+///
+/// Installed once per process; panics outside a TUI pass straight through.
+fn install_panic_restore() {
+    static INSTALLED: Once = Once::new();
+    INSTALLED.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let _ = restore_terminal();
+            previous(info);
+        }));
+    });
+}
+
+/// Leave raw mode and the alternate screen, once per [`TerminalGuard::enter`]
+fn restore_terminal() -> io::Result<()> {
+    if !TERMINAL_ACTIVE.swap(false, Ordering::SeqCst) {
+        return Ok(());
+    }
+    disable_raw_mode()?;
+    execute!(
+        io::stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        crossterm::cursor::Show
+    )
+}
+
+/// Decide whether text output should contain ANSI color codes
+///
+/// Color is disabled by `--no-color`, by a non-empty `NO_COLOR` environment
+/// variable (<https://no-color.org>), when writing to a file, or when stdout
+/// is not a terminal.
+#[must_use]
+pub fn color_enabled(no_color: bool, writing_to_file: bool) -> bool {
+    let no_color_env = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    !no_color && !no_color_env && !writing_to_file && io::stdout().is_terminal()
+}
+
+/// Connector characters the text tree is drawn with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TreeChars {
+    /// Marks a top-level certificate
+    pub root: &'static str,
+    /// Links a certificate to its issuer on the line above
+    pub branch: &'static str,
+    /// Leads the count of certificates a depth limit cut off
+    pub ellipsis: &'static str,
+}
+
+impl TreeChars {
+    /// Box-drawing characters for UTF-8 terminals
+    pub const UNICODE: Self = Self {
+        root: "━",
+        branch: "└",
+        ellipsis: "…",
+    };
+
+    /// Plain characters for terminals without UTF-8
+    pub const ASCII: Self = Self {
+        root: "-",
+        branch: "`-",
+        ellipsis: "...",
+    };
+
+    /// Characters for the current terminal
+    ///
+    /// ASCII is used with `--ascii`, or when the locale (`LC_ALL`, then `LC_CTYPE`,
+    /// then `LANG`) is set but does not name UTF-8, e.g. `C` or `POSIX`.
+    #[must_use]
+    pub fn detect(ascii: bool) -> Self {
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .find_map(|name| env::var(name).ok().filter(|value| !value.is_empty()));
+        if ascii || !locale_is_utf8(locale.as_deref()) {
+            Self::ASCII
+        } else {
+            Self::UNICODE
+        }
+    }
+
+    /// Tree prefix of top-level certificates
+    fn root_prefix(self) -> String {
+        format!("{} ", self.root)
+    }
+
+    /// Tree prefix of a certificate whose parent sits at `depth`, cascading 4 spaces per level
+    fn child_prefix(self, depth: usize) -> String {
+        format!("{}{} ", " ".repeat(5 + (depth * 4)), self.branch)
+    }
+}
+
+/// Whether a locale value such as `en_US.UTF-8` selects UTF-8; an unset locale counts as UTF-8
+fn locale_is_utf8(locale: Option<&str>) -> bool {
+    locale.is_none_or(|value| {
+        let value = value.to_ascii_lowercase();
+        value.contains("utf-8") || value.contains("utf8")
+    })
+}
+
+/// How the text tree is drawn
+#[derive(Debug, Clone, Copy)]
+struct TextStyle {
+    color: bool,
+    chars: TreeChars,
+    zone: DisplayTimezone,
+}
+
+/// Write the certificate tree as indented text, one certificate per line
+///
+/// # Errors
+///
+/// Returns an error if writing to `out` fails.
+pub fn display_certificate_tree_text(
+    tree: &CertificateTree,
+    out: &mut impl Write,
+    color: bool,
+    chars: TreeChars,
+    zone: DisplayTimezone,
+) -> io::Result<()> {
+    let root_prefix = chars.root_prefix();
+    let mut name_ends = Vec::new();
+    for root in &tree.roots {
+        collect_name_ends(root, root_prefix.len(), 0, chars, &mut name_ends);
+    }
+    let date_column = date_column_start(name_ends, terminal_width());
+
+    let style = TextStyle { color, chars, zone };
     let mut sequence_num = 0;
-    for (i, root) in tree.roots.iter().enumerate() {
-        let prefix = "━ ";
+    for root in &tree.roots {
         display_tree_node_text(
             root,
-            prefix,
+            &root_prefix,
             0,
             &mut sequence_num,
-            i == tree.roots.len() - 1,
-        );
+            date_column,
+            out,
+            style,
+        )?;
+    }
+    Ok(())
+}
+
+/// Write each leaf-to-root path of the tree on one line, e.g.
+/// `example.com [VALID until 2025-01-01] <- R3 <- ISRG Root X1`
+///
+/// Issuers are marked only when their status is not VALID, so a problem anywhere
+/// along the path stays visible without colour.
+///
+/// # Errors
+///
+/// Returns an error if writing to `out` fails.
+pub fn display_compact(
+    tree: &CertificateTree,
+    out: &mut impl Write,
+    color: bool,
+    zone: DisplayTimezone,
+) -> io::Result<()> {
+    let mut nodes = Vec::new();
+    for root in &tree.roots {
+        collect_nodes(root, 0, &mut nodes);
+    }
+
+    // Nodes arrive depth-first, so the ancestors of each node are the path so far
+    let mut path: Vec<&CertificateNode> = Vec::new();
+    for (node, depth) in nodes {
+        path.truncate(depth);
+        path.push(node);
+        if node.children.is_empty() {
+            writeln!(out, "{}", compact_path(&path, color, zone))?;
+        }
+    }
+    Ok(())
+}
+
+/// One `--format compact` line for a root-to-leaf `path`, written leaf first
+fn compact_path(path: &[&CertificateNode], color: bool, zone: DisplayTimezone) -> String {
+    path.iter()
+        .rev()
+        .enumerate()
+        .map(|(index, node)| {
+            let cn = cert_tree::parser::extract_cn(&node.cert.subject);
+            let (status_text, color_code) = validity_text_and_color(&node.validity_status);
+            let status = if index == 0 {
+                let until = zone.format(node.cert.not_after_dt, "%Y-%m-%d");
+                format!(" [{status_text} until {until}]")
+            } else if node.validity_status == ValidityStatus::Valid {
+                String::new()
+            } else {
+                format!(" [{status_text}]")
+            };
+            if color {
+                format!("{color_code}{cn}{status}\x1b[0m")
+            } else {
+                format!("{cn}{status}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" <- ")
+}
+
+/// Label and terminal colour the text formats use for a validity status
+fn validity_text_and_color(status: &ValidityStatus) -> (&'static str, &'static str) {
+    match status {
+        ValidityStatus::Expired => ("EXPIRED", "\x1b[31m"), // Red
+        ValidityStatus::ExpiringSoon => ("EXPIRES SOON", "\x1b[33m"), // Yellow
+        ValidityStatus::Valid => ("VALID", "\x1b[32m"),     // Green
+        ValidityStatus::NotYetValid => ("NOT YET VALID", "\x1b[34m"), // Blue
+    }
+}
+
+/// Collect where each certificate's name ends in the text tree, before any truncation
+fn collect_name_ends(
+    node: &CertificateNode,
+    prefix_len: usize,
+    depth: usize,
+    chars: TreeChars,
+    name_ends: &mut Vec<usize>,
+) {
+    let cn = cert_tree::parser::extract_cn(&node.cert.subject);
+    name_ends.push(prefix_len + cn.len());
+    let child_prefix_len = chars.child_prefix(depth).len();
+    for child in &node.children {
+        collect_name_ends(child, child_prefix_len, depth + 1, chars, name_ends);
     }
 }
 
+/// Width of the terminal stdout is attached to, or 80 columns when it is not a terminal
+fn terminal_width() -> usize {
+    if io::stdout().is_terminal() {
+        if let Ok((columns, _)) = terminal::size() {
+            return usize::from(columns);
+        }
+    }
+    DEFAULT_TERMINAL_WIDTH
+}
+
+/// Column the text tree's status and date start at
+///
+/// Placed one space after the longest name so nothing is truncated, but no further right
+/// than leaves room for the status and date within `terminal_width`.
+fn date_column_start(name_ends: impl IntoIterator<Item = usize>, terminal_width: usize) -> usize {
+    let longest = name_ends.into_iter().max().unwrap_or(0);
+    let limit = terminal_width
+        .saturating_sub(DATE_COLUMN_RESERVED)
+        .max(MIN_DATE_COLUMN_START);
+    (longest + 1).min(limit)
+}
+
 fn display_tree_node_text(
     node: &CertificateNode,
     prefix: &str,
     depth: usize,
     sequence_num: &mut usize,
-    _is_last: bool,
-) {
+    date_column_start: usize,
+    out: &mut impl Write,
+    style: TextStyle,
+) -> io::Result<()> {
     // Increment sequence number for this certificate
     *sequence_num += 1;
 
-    // Fixed column positions - dates should align regardless of tree depth
-    let date_column_start: usize = DATE_COLUMN_START; // Fixed position for date column (adjusted for seconds in time format)
-
     // Get certificate name (without sequence number) - use only CN
-    let cn = crate::parser::extract_cn(&node.cert.subject);
-    let available_name_space = date_column_start.saturating_sub(prefix.len()) - 5; // Leave space for brackets and content
+    let cn = cert_tree::parser::extract_cn(&node.cert.subject);
+    let available_name_space = date_column_start.saturating_sub(prefix.len() + 1); // Leave one space before the date column
     let display_name = if cn.len() > available_name_space {
         let truncate_len = if available_name_space > 3 {
             available_name_space - 3
@@ -266,8 +1383,9 @@ fn display_tree_node_text(
         cn.clone()
     };
 
-    // Date is already in the correct format
-    let date_str = node.cert.not_after.clone();
+    // In the `--timezone` zone, with its abbreviation
+    let date_str = node.cert.not_after_text(style.zone);
+    let expiry_text = node.cert.expiry_text();
 
     // Calculate exact padding to align date column
     let name_end_pos = prefix.len() + display_name.len();
@@ -279,41 +1397,137 @@ fn display_tree_node_text(
     let padding = " ".repeat(padding_needed);
 
     // Color codes for terminal output
-    let (status_text, color_code) = match node.validity_status {
-        ValidityStatus::Expired => ("EXPIRED", "\x1b[31m"), // Red
-        ValidityStatus::ExpiringSoon => ("EXPIRES SOON", "\x1b[33m"), // Yellow
-        ValidityStatus::Valid => ("VALID", "\x1b[32m"),     // Green
+    let (status_text, color_code) = validity_text_and_color(&node.validity_status);
+
+    // Revocation is only shown once an OCSP check has run
+    let (revocation_text, revocation_color) = match node.revocation_status {
+        RevocationStatus::NotChecked => ("", ""),
+        RevocationStatus::Good => (" [OCSP: GOOD]", "\x1b[32m"),
+        RevocationStatus::Revoked => (" [OCSP: REVOKED]", "\x1b[31m"),
+        RevocationStatus::Unknown => (" [OCSP: UNKNOWN]", "\x1b[33m"),
     };
 
-    // Use white for certificate names, color only the status/date part
-    println!(
-        "\x1b[37m[{sequence_num}] {prefix}{display_name}{padding}\x1b[0m{color_code}[{status_text}] [until: {date_str}]\x1b[0m"
-    );
+    // Only roles that usually point at an incomplete or unusual chain are flagged
+    let role_text = match node.role {
+        CertificateRole::SelfSigned => " [SELF-SIGNED]",
+        CertificateRole::OrphanIntermediate => " [ISSUER MISSING]",
+        CertificateRole::RootCa | CertificateRole::Intermediate | CertificateRole::Leaf => "",
+    };
+
+    // Trust is only shown for chain tops once a CA bundle was supplied
+    let (trust_text, trust_color) = match node.trust_status {
+        TrustStatus::NotChecked => ("", ""),
+        TrustStatus::Anchored => (" [ANCHORED]", "\x1b[32m"),
+        TrustStatus::Untrusted => (" [UNTRUSTED]", "\x1b[31m"),
+    };
+
+    if style.color {
+        // Use white for certificate names, color only the status/date part
+        writeln!(
+            out,
+            "\x1b[37m[{sequence_num}] {prefix}{display_name}{padding}\x1b[0m{color_code}[{status_text}] [until: {date_str}] ({expiry_text})\x1b[0m{revocation_color}{revocation_text}\x1b[0m{trust_color}{trust_text}\x1b[0m\x1b[33m{role_text}\x1b[0m"
+        )?;
+    } else {
+        writeln!(
+            out,
+            "[{sequence_num}] {prefix}{display_name}{padding}[{status_text}] [until: {date_str}] ({expiry_text}){revocation_text}{trust_text}{role_text}"
+        )?;
+    }
 
     // Display children with cascading tree structure
-    for (i, child) in node.children.iter().enumerate() {
-        let is_last_child = i == node.children.len() - 1;
+    for child in &node.children {
+        display_tree_node_text(
+            child,
+            &style.chars.child_prefix(depth),
+            depth + 1,
+            sequence_num,
+            date_column_start,
+            out,
+            style,
+        )?;
+    }
+
+    // Stand-in for the descendants a depth limit cut off, aligned like a child
+    if node.truncated > 0 {
+        let number_width = format!("[{sequence_num}] ").len();
+        let plural = if node.truncated == 1 { "" } else { "s" };
+        writeln!(
+            out,
+            "{}{}{} {} more certificate{plural}",
+            " ".repeat(number_width),
+            style.chars.child_prefix(depth),
+            style.chars.ellipsis,
+            node.truncated
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Re-fetch hook for the chain TUI's `--watch` mode
+pub struct Watch<'a> {
+    /// Time between refreshes
+    pub interval: Duration,
+    /// Rebuild the tree from fresh certificates; runs on a worker thread
+    pub refresh: &'a (dyn Fn() -> Result<CertificateTree, Box<dyn std::error::Error>> + Sync),
+}
 
-        // Create cascading indentation for child level (4 spaces per level)
-        let child_indent = " ".repeat(5 + (depth * 4)); // 5 spaces base + 4 per depth level
-        let child_prefix = format!("{child_indent}└ ");
+/// Outcome of a `--watch` refresh, sent back from its worker thread
+type RefreshResult = Result<CertificateTree, String>;
 
-        display_tree_node_text(child, &child_prefix, depth + 1, sequence_num, is_last_child);
+/// Whether a `--watch` refresh is due after `elapsed` since the last one
+fn refresh_due(elapsed: Duration, interval: Duration) -> bool {
+    elapsed >= interval
+}
+
+/// Result of the `--watch` refresh behind `receiver`, or `None` while it is still running
+fn poll_refresh(receiver: &mpsc::Receiver<RefreshResult>) -> Option<RefreshResult> {
+    match receiver.try_recv() {
+        Ok(result) => Some(result),
+        Err(mpsc::TryRecvError::Empty) => None,
+        Err(mpsc::TryRecvError::Disconnected) => Some(Err("refresh worker stopped".to_string())),
     }
 }
 
+/// Browse the certificate tree in the interactive terminal UI
+///
+/// With `watch`, the tree is rebuilt every interval on a worker thread, so the
+/// UI stays responsive while a slow fetch runs; the footer shows when it was
+/// last updated and whether a refresh is in progress, and a failed refresh
+/// keeps the previous tree.
+///
+/// # Errors
+///
+/// Returns an error if the terminal cannot be set up, drawn to or restored.
 pub fn display_certificate_tree_tui(
     tree: &CertificateTree,
+    sort: SortOrder,
+    color: bool,
+    chars: TreeChars,
+    zone: DisplayTimezone,
+    watch: Option<&Watch>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
+    // Quitting mid-refresh restores the terminal first, then waits for the worker
+    thread::scope(|scope| certificate_tree_tui(scope, tree, sort, color, chars, zone, watch))
+}
+
+fn certificate_tree_tui<'scope, 'env>(
+    scope: &'scope thread::Scope<'scope, 'env>,
+    tree: &CertificateTree,
+    sort: SortOrder,
+    color: bool,
+    chars: TreeChars,
+    zone: DisplayTimezone,
+    watch: Option<&'env Watch<'env>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Setup terminal; the guard restores it if anything below panics or returns early
+    let guard = TerminalGuard::enter()?;
+    let backend = CrosstermBackend::new(io::stdout());
     let mut terminal = Terminal::new(backend)?;
 
     // Flatten the certificate tree into a list
-    let certificates = flatten_certificate_tree(tree);
+    let mut tree = tree.clone();
+    let mut certificates = flatten_certificate_tree(&tree, sort);
     let mut list_state = ratatui::widgets::ListState::default();
     list_state.select(Some(0));
 
@@ -325,11 +1539,63 @@ pub fn display_certificate_tree_tui(
     // Toggle with Tab key for better accessibility and usability
     let mut details_pane_active = false;
 
+    // `v` switches the details pane between the labeled view and the raw `-v` dump
+    let mut raw_details = false;
+
+    // Filter state: `/` starts typing a query that narrows the list by CN or issuer
+    let mut filter_query = String::new();
+    let mut filter_typing = false;
+
+    // Screen regions from the last draw, used to route mouse events
+    let mut list_region = Rect::default();
+    let mut details_region = Rect::default();
+
+    // --watch state: when the tree was last fetched, why the latest refresh failed,
+    // and the channel of the refresh running on a worker thread, if any
+    let mut last_refresh = Instant::now();
+    let mut updated_at = chrono::Utc::now();
+    let mut refresh_error: Option<String> = None;
+    let mut pending_refresh: Option<mpsc::Receiver<RefreshResult>> = None;
+
     // Force initial clear and small delay to ensure proper layout on startup
     terminal.clear()?;
     std::thread::sleep(Duration::from_millis(SLEEP_MS));
 
     loop {
+        if let Some(watch) = watch {
+            if let Some(receiver) = &pending_refresh {
+                if let Some(result) = poll_refresh(receiver) {
+                    match result {
+                        Ok(fresh) => {
+                            tree = fresh;
+                            certificates = flatten_certificate_tree(&tree, sort);
+                            updated_at = chrono::Utc::now();
+                            refresh_error = None;
+                        }
+                        Err(err) => refresh_error = Some(err),
+                    }
+                    pending_refresh = None;
+                    last_refresh = Instant::now();
+                }
+            } else if refresh_due(last_refresh.elapsed(), watch.interval) {
+                let (sender, receiver) = mpsc::channel();
+                let refresh = watch.refresh;
+                scope.spawn(move || {
+                    // The receiver is gone if the TUI has quit meanwhile
+                    let _ = sender.send(refresh().map_err(|err| err.to_string()));
+                });
+                pending_refresh = Some(receiver);
+            }
+        }
+
+        let visible: Vec<&CertificateDisplayItem> = certificates
+            .iter()
+            .filter(|item| matches_filter(item, &filter_query))
+            .collect();
+        if list_state.selected().unwrap_or(0) >= visible.len() {
+            list_state.select(Some(visible.len().saturating_sub(1)));
+        }
+
         terminal.draw(|f| {
             let size = f.size();
 
@@ -344,9 +1610,21 @@ pub fn display_certificate_tree_tui(
                 ])
                 .split(size);
 
-            // Title block with version
-            let title_text = format!("🔐 Certificate Chain Inspector{:>width$}", env!("CARGO_PKG_VERSION"), width = size.width as usize - 35);
-            let title = Paragraph::new(title_text)
+            // Title block with where the chains end, once checked against a trust store, and version
+            let anchor_text = tree
+                .anchor_status
+                .map(|status| format!("  {}", status.text()))
+                .unwrap_or_default();
+            let version_width = (size.width as usize).saturating_sub(35 + anchor_text.chars().count());
+            let title_line = Line::from(vec![
+                Span::raw("🔐 Certificate Chain Inspector"),
+                Span::styled(
+                    anchor_text,
+                    Style::default().fg(tree.anchor_status.map_or(Color::Cyan, |status| status.color())),
+                ),
+                Span::raw(format!("{:>version_width$}", env!("CARGO_PKG_VERSION"))),
+            ]);
+            let title = Paragraph::new(title_line)
                 .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
                 .block(Block::default().borders(Borders::ALL).title("cert-tree.rs"));
             f.render_widget(title, chunks[0]);
@@ -368,11 +1646,13 @@ pub fn display_certificate_tree_tui(
             let padding_after_date = 3;
 
             let list_area = chunks[1];
+            list_region = list_area;
+            details_region = chunks[2];
             let effective_width = (list_area.width as usize).saturating_sub(2); // Subtract border width (1 left + 1 right)
             let available_name_width = effective_width.saturating_sub(date_width + min_gap + padding_after_date + 4).max(min_name_width);
 
             // Create list items
-            let items: Vec<ListItem> = certificates
+            let items: Vec<ListItem> = visible
                 .iter()
                 .map(|item| {
                     // Truncate long names if necessary
@@ -386,12 +1666,8 @@ pub fn display_certificate_tree_tui(
                         item.display_name.clone()
                     };
 
-                    // Reformat date using adaptive format
-                    let formatted_date = if let Ok(dt) = DateTime::parse_from_str(&item.valid_until, "%Y-%m-%d %H:%M:%S") {
-                        dt.format(date_format).to_string()
-                    } else {
-                        item.valid_until.clone()
-                    };
+                    // Reformat date using adaptive format, in the `--timezone` zone
+                    let formatted_date = zone.format(item.valid_until, date_format);
 
                     // Create formatted strings for each column
                     let name_part = format!("{display_name:<available_name_width$}");
@@ -414,6 +1690,11 @@ pub fn display_certificate_tree_tui(
             } else {
                 "Certificates (Active - Use ↑/↓/PgUp/PgDn to navigate)"
             };
+            let list_title = if filter_typing || !filter_query.is_empty() {
+                format!("{list_title} [/{filter_query}] {}/{}", visible.len(), certificates.len())
+            } else {
+                list_title.to_string()
+            };
 
             let list_block = if details_pane_active {
                 Block::default()
@@ -435,90 +1716,21 @@ pub fn display_certificate_tree_tui(
 
             // Certificate details section
             let selected_index = list_state.selected().unwrap_or(0);
-            let selected_cert = &certificates[selected_index];
-            let cert = &selected_cert.certificate_info;
-            let sig_explanation = crate::parser::explain_signature_algorithm(&cert.signature_algorithm);
-
-            let mut details_lines = vec![
-                Line::from(vec![
-                    Span::styled("Subject: ", Style::default().fg(Color::Blue)),
-                    Span::styled(&cert.subject, Style::default().fg(Color::White)),
-                ]),
-                Line::from(vec![
-                    Span::styled("Issuer: ", Style::default().fg(Color::Blue)),
-                    Span::styled(&cert.issuer, Style::default().fg(Color::White)),
-                ]),
-                Line::from(vec![
-                    Span::styled("Serial Number: ", Style::default().fg(Color::Blue)),
-                    Span::styled(&cert.serial_number, Style::default().fg(Color::White)),
-                ]),
-                Line::from(vec![
-                    Span::styled("Validity Period: ", Style::default().fg(Color::Blue)),
-                    Span::styled(&cert.not_before, Style::default().fg(Color::White)),
-                    Span::raw(" → "),
-                    Span::styled(&cert.not_after, Style::default().fg(Color::White)),
-                ]),
-                Line::from(vec![
-                    Span::styled("Status: ", Style::default().fg(Color::Blue)),
-                    Span::styled(selected_cert.validity_status.text(), Style::default().fg(selected_cert.validity_status.color())),
-                ]),
-                Line::from(vec![
-                    Span::styled("Chain Validation: ", Style::default().fg(Color::Blue)),
-                    Span::styled(selected_cert.validation_status.text(), Style::default().fg(selected_cert.validation_status.color())),
-                ]),
-                Line::from(vec![
-                    Span::styled("Version: ", Style::default().fg(Color::Blue)),
-                    Span::styled(cert.version.to_string(), Style::default().fg(Color::White)),
-                ]),
-                Line::from(vec![
-                    Span::styled("Public Key Algorithm: ", Style::default().fg(Color::Blue)),
-                    Span::styled(&cert.public_key_algorithm, Style::default().fg(Color::Green)),
-                ]),
-                Line::from(vec![
-                    Span::styled("Signature Algorithm: ", Style::default().fg(Color::Blue)),
-                    Span::styled(sig_explanation.as_str(), Style::default().fg(Color::Green)),
-                ]),
-                Line::from(vec![
-                    Span::styled("Is CA: ", Style::default().fg(Color::Blue)),
-                    Span::styled(cert.is_ca.to_string(), Style::default().fg(if cert.is_ca { Color::Yellow } else { Color::White })),
-                ]),
-            ];
-
-            if let Some(ku) = &cert.key_usage {
-                details_lines.push(Line::from(vec![
-                    Span::styled("Key Usage: ", Style::default().fg(Color::Blue)),
-                    Span::styled(ku, Style::default().fg(Color::Magenta)),
-                ]));
-            }
-
-            if !cert.subject_alt_names.is_empty() {
-                details_lines.push(Line::from(vec![
-                    Span::styled("Subject Alternative Names: ", Style::default().fg(Color::Blue)),
-                    Span::styled(cert.subject_alt_names.join(", "), Style::default().fg(Color::Cyan)),
-                ]));
-            }
-
-            if !cert.extensions.is_empty() {
-                details_lines.push(Line::from(vec![
-                    Span::styled("Extensions:", Style::default().fg(Color::Blue)),
-                ]));
-                for ext in &cert.extensions {
-                    let ext_name = ext.name.as_deref().unwrap_or(&ext.oid);
-                    details_lines.push(Line::from(vec![
-                        Span::raw("  "),
-                        Span::styled(ext_name, Style::default().fg(Color::Cyan)),
-                        Span::raw(" ("),
-                        Span::styled(if ext.critical { "critical" } else { "non-critical" }, Style::default().fg(if ext.critical { Color::Red } else { Color::Green })),
-                        Span::raw(")"),
-                    ]));
-                }
-            }
+            let details_lines = if let Some(selected_cert) = visible.get(selected_index) {
+                detail_lines(selected_cert, raw_details, zone)
+            } else {
+                vec![Line::from(Span::styled(
+                    "No certificates match the filter",
+                    Style::default().fg(Color::Gray),
+                ))]
+            };
 
             // Create details paragraph with visual feedback for active state
-            let details_title = if details_pane_active {
-                "Certificate Details (Active - Use ↑/↓ to scroll)"
-            } else {
-                "Certificate Details (Press Tab to activate)"
+            let details_title = match (details_pane_active, raw_details) {
+                (true, false) => "Certificate Details (Active - Use ↑/↓ to scroll)",
+                (false, false) => "Certificate Details (Press Tab to activate)",
+                (true, true) => "Raw Certificate Details (Active - Use ↑/↓ to scroll)",
+                (false, true) => "Raw Certificate Details (Press Tab to activate)",
             };
 
             let details_block = if details_pane_active {
@@ -539,10 +1751,24 @@ pub fn display_certificate_tree_tui(
             f.render_widget(details_paragraph, chunks[2]);
 
             // Footer with instructions - dynamic based on details pane state
-            let footer_text = if details_pane_active {
-                "Tab: Deactivate Details | ↑/↓: Scroll Details | PgUp/PgDn: Navigate List | 'q' Quit | 't' Text Mode"
+            let footer_text = if filter_typing {
+                "Type to filter by CN or issuer | Backspace: Delete | Enter: Apply | Esc: Clear"
+            } else if details_pane_active {
+                "Tab: Deactivate Details | ↑/↓ j/k, Ctrl-d/u, g/G: Scroll Details | PgUp/PgDn: Navigate List | '/' Filter | 'v' Raw Details | 'q' Quit | 't' Text Mode"
             } else {
-                "↑/↓/PgUp/PgDn: Navigate List | Tab: Activate Details | 'q' Quit | 't' Text Mode"
+                "↑/↓ j/k, PgUp/PgDn, Ctrl-d/u, g/G: Navigate List | Tab: Activate Details | '/' Filter | 'v' Raw Details | 'q' Quit | 't' Text Mode"
+            };
+
+            let updated = zone.format(updated_at, "%H:%M:%S %Z");
+            let footer_text = match (watch, &refresh_error) {
+                (None, _) => footer_text.to_string(),
+                (Some(_), _) if pending_refresh.is_some() => {
+                    format!("{footer_text} | Last updated {updated} (refreshing…)")
+                }
+                (Some(_), None) => format!("{footer_text} | Last updated {updated}"),
+                (Some(_), Some(err)) => {
+                    format!("{footer_text} | Last updated {updated} (refresh failed: {err})")
+                }
             };
 
             let footer = Paragraph::new(footer_text)
@@ -555,11 +1781,74 @@ pub fn display_certificate_tree_tui(
         // - Tab: Toggle details pane activation/deactivation
         // - When details pane inactive: ↑/↓/PgUp/PgDn navigate certificate list
         // - When details pane active: ↑/↓ scroll certificate details, PgUp/PgDn disabled
-        // - 'q'/Esc: Quit application
+        // - Vim aliases: j/k for ↓/↑, Ctrl-d/Ctrl-u half a page, g/G to the top/bottom
+        // - '/': Type a filter query; Backspace edits, Enter applies, Esc clears
+        // - 'q'/Esc: Quit application (Esc clears an active filter first)
         // - 't': Switch to text mode
+        // - 'v': Toggle the details pane between labeled and raw views
+        // - Mouse: click a row to select it; the wheel scrolls the list or details under the cursor
         if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
+            let event = event::read()?;
+            if let Event::Mouse(mouse) = event {
+                let over_details = rect_contains(details_region, mouse.column, mouse.row);
+                let over_list = rect_contains(list_region, mouse.column, mouse.row);
+                let selected = list_state.selected().unwrap_or(0);
+                match mouse.kind {
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        if let Some(index) = list_index_at(
+                            list_region,
+                            mouse.column,
+                            mouse.row,
+                            list_state.offset(),
+                            visible.len(),
+                        ) {
+                            list_state.select(Some(index));
+                        }
+                    }
+                    MouseEventKind::ScrollUp if over_details => {
+                        details_scroll = details_scroll.saturating_sub(1);
+                    }
+                    MouseEventKind::ScrollDown if over_details => {
+                        details_scroll = (details_scroll + 1).min(MAX_SCROLL_LIMIT);
+                    }
+                    MouseEventKind::ScrollUp if over_list => {
+                        list_state.select(Some(selected.saturating_sub(1)));
+                    }
+                    MouseEventKind::ScrollDown if over_list && selected + 1 < visible.len() => {
+                        list_state.select(Some(selected + 1));
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+
+            if let Event::Key(key) = event {
+                if filter_typing {
+                    match key.code {
+                        KeyCode::Char(c) => filter_query.push(c),
+                        KeyCode::Backspace => {
+                            filter_query.pop();
+                        }
+                        KeyCode::Enter => filter_typing = false,
+                        KeyCode::Esc => {
+                            filter_query.clear();
+                            filter_typing = false;
+                        }
+                        _ => {}
+                    }
+                    // The details pane follows the first match of the new query
+                    list_state.select(Some(0));
+                    details_scroll = 0;
+                    continue;
+                }
+
                 match key.code {
+                    KeyCode::Char('/') => filter_typing = true,
+                    KeyCode::Esc if !filter_query.is_empty() => {
+                        filter_query.clear();
+                        list_state.select(Some(0));
+                        details_scroll = 0;
+                    }
                     KeyCode::Char('q') | KeyCode::Esc => break,
 
                     // Tab key toggles details pane activation
@@ -567,122 +1856,1265 @@ pub fn display_certificate_tree_tui(
                         details_pane_active = !details_pane_active;
                     }
 
-                    // Navigation keys - behavior depends on details pane state
-                    KeyCode::Up => {
-                        if details_pane_active {
-                            // Scroll details up when details pane is active
-                            if details_scroll > 0 {
-                                details_scroll = details_scroll.saturating_sub(1);
-                            }
-                        } else {
-                            // Navigate list up when details pane is inactive
-                            let i = list_state.selected().unwrap_or(0);
-                            if i > 0 {
-                                list_state.select(Some(i - 1));
-                            }
-                        }
-                    }
-                    KeyCode::Down => {
-                        if details_pane_active {
-                            // Scroll details down when details pane is active
-                            if details_scroll < MAX_SCROLL_LIMIT {
-                                details_scroll += 1;
-                            }
-                        } else {
-                            // Navigate list down when details pane is inactive
-                            let i = list_state.selected().unwrap_or(0);
-                            if i < certificates.len() - 1 {
-                                list_state.select(Some(i + 1));
-                            }
-                        }
-                    }
-
-                    // Page Up/Page Down for fast list navigation (only when details pane inactive)
-                    KeyCode::PageUp => {
-                        if !details_pane_active {
-                            let i = list_state.selected().unwrap_or(0);
-                            let new_index = i.saturating_sub(PAGE_SIZE);
-                            list_state.select(Some(new_index));
-                        }
-                    }
-                    KeyCode::PageDown => {
-                        if !details_pane_active {
-                            let i = list_state.selected().unwrap_or(0);
-                            let new_index = (i + PAGE_SIZE).min(certificates.len() - 1);
-                            list_state.select(Some(new_index));
-                        }
+                    KeyCode::Char('v') => {
+                        raw_details = !raw_details;
+                        details_scroll = 0;
                     }
 
                     // Text mode switch
                     KeyCode::Char('t') => {
                         // Switch to text mode
-                        disable_raw_mode()?;
-                        execute!(
-                            terminal.backend_mut(),
-                            LeaveAlternateScreen,
-                            DisableMouseCapture
+                        guard.restore()?;
+                        display_certificate_tree_text(
+                            &tree,
+                            &mut io::stdout(),
+                            color,
+                            chars,
+                            zone,
                         )?;
-                        terminal.show_cursor()?;
-                        display_certificate_tree_text(tree);
                         return Ok(());
                     }
-                    _ => {}
+
+                    // Navigation keys - behavior depends on details pane state
+                    _ => match nav_action(&key) {
+                        // Page keys only move the list
+                        Some(NavAction::PageUp | NavAction::PageDown) if details_pane_active => {}
+                        Some(action) if details_pane_active => {
+                            details_scroll = scroll_offset(details_scroll, action);
+                        }
+                        Some(action) => {
+                            let i = list_state.selected().unwrap_or(0);
+                            list_state.select(Some(select_index(i, visible.len(), action)));
+                        }
+                        None => {}
+                    },
                 }
             }
         }
     }
 
     // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    guard.restore()?;
 
     Ok(())
 }
 
-fn flatten_certificate_tree(tree: &CertificateTree) -> Vec<CertificateDisplayItem> {
-    let mut certificates = Vec::new();
-    let mut line_number = 1;
+/// Lines for the details pane of the tree TUI
+///
+/// The labeled view picks out the statuses and the most useful fields; `raw`
+/// instead shows the `-v` text dump, with every extension's value.
+fn detail_lines(
+    selected_cert: &CertificateDisplayItem,
+    raw: bool,
+    zone: DisplayTimezone,
+) -> Vec<Line<'_>> {
+    let cert = &selected_cert.certificate_info;
+    if raw {
+        let mut dump = Vec::new();
+        // Writing to a Vec cannot fail
+        let _ = display_verbose(cert, Verbosity::Verbose, zone, &mut dump);
+        return String::from_utf8_lossy(&dump)
+            .lines()
+            .map(|line| Line::from(line.to_string()))
+            .collect();
+    }
+
+    let sig_explanation = explain_signature_algorithm(&cert.signature_algorithm);
+
+    let mut details_lines = dn_lines("Subject", &cert.subject);
+    details_lines.extend(dn_lines("Issuer", &cert.issuer));
+    details_lines.extend(vec![
+        Line::from(vec![
+            Span::styled("Serial Number: ", Style::default().fg(Color::Blue)),
+            Span::styled(&cert.serial_number, Style::default().fg(Color::White)),
+        ]),
+        Line::from(vec![
+            Span::styled("Validity Period: ", Style::default().fg(Color::Blue)),
+            Span::styled(
+                cert.not_before_text(zone),
+                Style::default().fg(Color::White),
+            ),
+            Span::raw(" → "),
+            Span::styled(cert.not_after_text(zone), Style::default().fg(Color::White)),
+            Span::styled(
+                format!(" ({})", cert.expiry_text()),
+                Style::default().fg(selected_cert.validity_status.color()),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Status: ", Style::default().fg(Color::Blue)),
+            Span::styled(
+                selected_cert.validity_status.text(),
+                Style::default().fg(selected_cert.validity_status.color()),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Role: ", Style::default().fg(Color::Blue)),
+            Span::styled(
+                selected_cert.role.text(),
+                Style::default().fg(selected_cert.role.color()),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Chain Validation: ", Style::default().fg(Color::Blue)),
+            Span::styled(
+                selected_cert.validation_status.text(),
+                Style::default().fg(selected_cert.validation_status.color()),
+            ),
+            Span::styled(
+                selected_cert
+                    .validation_status
+                    .reason()
+                    .map(|reason| format!(" ({reason})"))
+                    .unwrap_or_default(),
+                Style::default().fg(selected_cert.validation_status.color()),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Revocation (OCSP): ", Style::default().fg(Color::Blue)),
+            Span::styled(
+                selected_cert.revocation_status.text(),
+                Style::default().fg(selected_cert.revocation_status.color()),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Trust Anchor: ", Style::default().fg(Color::Blue)),
+            Span::styled(
+                selected_cert.trust_status.text(),
+                Style::default().fg(selected_cert.trust_status.color()),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Version: ", Style::default().fg(Color::Blue)),
+            Span::styled(cert.version_display(), Style::default().fg(Color::White)),
+        ]),
+        Line::from(vec![
+            Span::styled("Size: ", Style::default().fg(Color::Blue)),
+            Span::styled(cert.size_text(), Style::default().fg(Color::White)),
+        ]),
+        Line::from(vec![
+            Span::styled("Public Key Algorithm: ", Style::default().fg(Color::Blue)),
+            Span::styled(
+                &cert.public_key_algorithm,
+                Style::default().fg(Color::Green),
+            ),
+            weak_span(cert.is_weak_key()),
+        ]),
+        Line::from(vec![
+            Span::styled("Signature Algorithm: ", Style::default().fg(Color::Blue)),
+            Span::styled(sig_explanation, Style::default().fg(Color::Green)),
+            weak_span(cert.is_weak_signature()),
+        ]),
+        Line::from(vec![
+            Span::styled("Is CA: ", Style::default().fg(Color::Blue)),
+            Span::styled(
+                cert.is_ca_display(),
+                Style::default().fg(if cert.is_ca {
+                    Color::Yellow
+                } else {
+                    Color::White
+                }),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("SHA-256 Fingerprint: ", Style::default().fg(Color::Blue)),
+            Span::styled(&cert.sha256_fingerprint, Style::default().fg(Color::White)),
+        ]),
+        Line::from(vec![
+            Span::styled("SHA-1 Fingerprint: ", Style::default().fg(Color::Blue)),
+            Span::styled(&cert.sha1_fingerprint, Style::default().fg(Color::White)),
+        ]),
+    ]);
+
+    if let Some(ku) = &cert.key_usage {
+        details_lines.push(Line::from(vec![
+            Span::styled("Key Usage: ", Style::default().fg(Color::Blue)),
+            Span::styled(ku, Style::default().fg(Color::Magenta)),
+        ]));
+    }
+
+    if !cert.extended_key_usage.is_empty() {
+        details_lines.push(Line::from(vec![
+            Span::styled("Extended Key Usage: ", Style::default().fg(Color::Blue)),
+            Span::styled(
+                cert.extended_key_usage.join(", "),
+                Style::default().fg(Color::Magenta),
+            ),
+        ]));
+    }
+
+    if let Some(check) = &cert.hostname_check {
+        details_lines.push(Line::from(vec![
+            Span::styled("Hostname: ", Style::default().fg(Color::Blue)),
+            Span::styled(check.text(), Style::default().fg(check.color())),
+        ]));
+    }
+
+    if let Some(connection) = &cert.tls_connection {
+        details_lines.push(Line::from(vec![
+            Span::styled("TLS Connection: ", Style::default().fg(Color::Blue)),
+            Span::styled(connection.text(), Style::default().fg(Color::Green)),
+        ]));
+        let verification_color = if connection.verification_error.is_none() {
+            Color::Green
+        } else {
+            Color::Red
+        };
+        details_lines.push(Line::from(vec![
+            Span::styled("TLS Verification: ", Style::default().fg(Color::Blue)),
+            Span::styled(
+                connection.verification_text(),
+                Style::default().fg(verification_color),
+            ),
+        ]));
+    }
+
+    if !cert.policies.is_empty() {
+        details_lines.push(Line::from(vec![
+            Span::styled("Certificate Policies: ", Style::default().fg(Color::Blue)),
+            Span::styled(
+                cert.policies.join(", "),
+                Style::default().fg(Color::Magenta),
+            ),
+        ]));
+    }
+
+    if let Some(constraints) = &cert.name_constraints {
+        details_lines.extend(name_constraint_lines(constraints));
+    }
+
+    if !cert.subject_alt_names.is_empty() {
+        details_lines.push(Line::from(vec![
+            Span::styled(
+                "Subject Alternative Names: ",
+                Style::default().fg(Color::Blue),
+            ),
+            Span::styled(
+                cert.subject_alt_names.join(", "),
+                Style::default().fg(Color::Cyan),
+            ),
+        ]));
+    }
+
+    details_lines.extend(url_section_lines(
+        "CRL Distribution Points:",
+        &cert.crl_urls,
+    ));
+    details_lines.extend(url_section_lines("OCSP Responders:", &cert.ocsp_urls));
+    details_lines.extend(url_section_lines("CA Issuers:", &cert.ca_issuer_urls));
+    details_lines.extend(sct_lines(&cert.scts));
+
+    for violation in &cert.violations {
+        details_lines.push(Line::from(vec![
+            Span::styled("Violation: ", Style::default().fg(Color::Blue)),
+            Span::styled(violation, Style::default().fg(Color::Red)),
+        ]));
+    }
+
+    if !cert.extensions.is_empty() {
+        details_lines.push(Line::from(vec![Span::styled(
+            "Extensions:",
+            Style::default().fg(Color::Blue),
+        )]));
+        for ext in &cert.extensions {
+            let ext_name = ext.name.as_deref().unwrap_or(&ext.oid);
+            details_lines.push(Line::from(vec![
+                Span::raw("  "),
+                Span::styled(ext_name, Style::default().fg(Color::Cyan)),
+                Span::raw(" ("),
+                criticality_span(ext.critical),
+                Span::raw(")"),
+            ]));
+        }
+    }
+
+    details_lines
+}
+
+/// Navigation requested by a key press in either TUI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NavAction {
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    HalfPageUp,
+    HalfPageDown,
+    Top,
+    Bottom,
+}
+
+/// Map arrow and page keys and their vim-style aliases to a navigation action
+///
+/// `j`/`k` move down/up, `Ctrl-d`/`Ctrl-u` half a page, and `g`/`G` jump to the top/bottom.
+fn nav_action(key: &KeyEvent) -> Option<NavAction> {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    match key.code {
+        KeyCode::Char('d') if ctrl => Some(NavAction::HalfPageDown),
+        KeyCode::Char('u') if ctrl => Some(NavAction::HalfPageUp),
+        _ if ctrl => None,
+        KeyCode::Up | KeyCode::Char('k') => Some(NavAction::Up),
+        KeyCode::Down | KeyCode::Char('j') => Some(NavAction::Down),
+        KeyCode::PageUp => Some(NavAction::PageUp),
+        KeyCode::PageDown => Some(NavAction::PageDown),
+        KeyCode::Char('g') => Some(NavAction::Top),
+        KeyCode::Char('G') => Some(NavAction::Bottom),
+        _ => None,
+    }
+}
+
+/// List selection after `action`, kept within a list of `len` items
+fn select_index(current: usize, len: usize, action: NavAction) -> usize {
+    let last = len.saturating_sub(1);
+    match action {
+        NavAction::Up => current.saturating_sub(1),
+        NavAction::Down => current + 1,
+        NavAction::PageUp => current.saturating_sub(PAGE_SIZE),
+        NavAction::PageDown => current + PAGE_SIZE,
+        NavAction::HalfPageUp => current.saturating_sub(PAGE_SIZE / 2),
+        NavAction::HalfPageDown => current + PAGE_SIZE / 2,
+        NavAction::Top => 0,
+        NavAction::Bottom => last,
+    }
+    .min(last)
+}
+
+/// Details pane scroll offset after `action`, capped at `MAX_SCROLL_LIMIT`
+fn scroll_offset(current: u16, action: NavAction) -> u16 {
+    let page = u16::try_from(PAGE_SIZE).unwrap_or(MAX_SCROLL_LIMIT);
+    match action {
+        NavAction::Up => current.saturating_sub(1),
+        NavAction::Down => current + 1,
+        NavAction::PageUp => current.saturating_sub(page),
+        NavAction::PageDown => current + page,
+        NavAction::HalfPageUp => current.saturating_sub(page / 2),
+        NavAction::HalfPageDown => current + page / 2,
+        NavAction::Top => 0,
+        NavAction::Bottom => MAX_SCROLL_LIMIT,
+    }
+    .min(MAX_SCROLL_LIMIT)
+}
+
+/// Whether a terminal cell lies within `area`
+fn rect_contains(area: Rect, column: u16, row: u16) -> bool {
+    column >= area.x
+        && column < area.x.saturating_add(area.width)
+        && row >= area.y
+        && row < area.y.saturating_add(area.height)
+}
+
+/// Map a click inside a bordered list to the index of the item under it
+///
+/// `offset` is the list's current scroll offset; clicks on the border or
+/// below the last item yield `None`.
+fn list_index_at(area: Rect, column: u16, row: u16, offset: usize, len: usize) -> Option<usize> {
+    let inner = Rect {
+        x: area.x + 1,
+        y: area.y + 1,
+        width: area.width.saturating_sub(2),
+        height: area.height.saturating_sub(2),
+    };
+    if !rect_contains(inner, column, row) {
+        return None;
+    }
+    let index = offset + usize::from(row - inner.y);
+    (index < len).then_some(index)
+}
+
+/// Whether a list entry matches the TUI filter query (case-insensitive CN or issuer)
+fn matches_filter(item: &CertificateDisplayItem, query: &str) -> bool {
+    if query.is_empty() {
+        return true;
+    }
+    let query = query.to_lowercase();
+    let cert = &item.certificate_info;
+    cert_tree::parser::extract_cn(&cert.subject)
+        .to_lowercase()
+        .contains(&query)
+        || cert.issuer.to_lowercase().contains(&query)
+}
+
+/// Flatten the tree into list entries, depth-first unless a sort order is given
+///
+/// A sorted list no longer follows the chain, so its entries are not indented.
+fn flatten_certificate_tree(
+    tree: &CertificateTree,
+    sort: SortOrder,
+) -> Vec<CertificateDisplayItem> {
+    let mut nodes = Vec::new();
     for root in &tree.roots {
-        flatten_node(root, &mut certificates, 0, &mut line_number);
+        collect_nodes(root, 0, &mut nodes);
+    }
+    if sort != SortOrder::None {
+        // Stable, so ties keep their tree order
+        nodes.sort_by(|(a, _), (b, _)| sort.compare(&a.cert, &b.cert));
     }
-    certificates
+
+    nodes
+        .into_iter()
+        .enumerate()
+        .map(|(index, (node, depth))| {
+            // Get certificate name (CN only)
+            let cn = cert_tree::parser::extract_cn(&node.cert.subject);
+
+            // Create indentation based on depth
+            let indentation = if sort == SortOrder::None {
+                "  ".repeat(depth)
+            } else {
+                String::new()
+            };
+
+            // Format display name with bracketed sequence number, indentation, and certificate name
+            let display_name = format!("[{}] {indentation}{cn}", index + 1);
+
+            CertificateDisplayItem {
+                display_name,
+                valid_until: node.cert.not_after_dt,
+                role: node.role,
+                validity_status: node.validity_status.clone(),
+                validation_status: node.validation_status.clone(),
+                revocation_status: node.revocation_status.clone(),
+                trust_status: node.trust_status.clone(),
+                certificate_info: node.cert.clone(),
+            }
+        })
+        .collect()
 }
 
-fn flatten_node(
-    node: &CertificateNode,
-    certificates: &mut Vec<CertificateDisplayItem>,
+fn collect_nodes<'a>(
+    node: &'a CertificateNode,
     depth: usize,
-    line_number: &mut usize,
+    nodes: &mut Vec<(&'a CertificateNode, usize)>,
 ) {
-    // Get certificate name (CN only)
-    let cn = crate::parser::extract_cn(&node.cert.subject);
+    nodes.push((node, depth));
 
-    // Create indentation based on depth
-    let indentation = "  ".repeat(depth);
+    // Add children
+    for child in &node.children {
+        collect_nodes(child, depth + 1, nodes);
+    }
+}
 
-    // Format display name with bracketed sequence number, indentation, and certificate name
-    let display_name = format!("[{line_number}] {indentation}{cn}");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cert_tree::parser::parse_certificate_chain;
+    use cert_tree::tree::build_certificate_tree;
+    use std::fs;
+
+    const CHAIN_PEM: &[u8] = include_bytes!("../test/chain.pem");
+
+    #[test]
+    fn test_status_colors() {
+        assert_eq!(ValidityStatus::NotYetValid.color(), Color::Blue);
+        assert_eq!(ValidityStatus::Expired.color(), Color::Red);
+        assert_eq!(CertificateRole::OrphanIntermediate.color(), Color::Yellow);
+        assert_eq!(AnchorStatus::TrustedRoot.color(), Color::Green);
+    }
 
-    // Date is already in the correct format (YYYY-MM-DD HH:MM:SS)
-    let valid_until = node.cert.not_after.clone();
+    #[test]
+    fn test_explain_signature_algorithm_eddsa() {
+        assert!(explain_signature_algorithm("Ed25519").contains("EdDSA"));
+        assert!(explain_signature_algorithm("Ed448").contains("EdDSA"));
+        assert!(explain_signature_algorithm("RSA-PSS").contains("RSA"));
+    }
 
-    certificates.push(CertificateDisplayItem {
-        display_name,
-        valid_until,
-        validity_status: node.validity_status.clone(),
-        validation_status: node.validation_status.clone(),
-        certificate_info: node.cert.clone(),
-    });
+    #[test]
+    fn test_terminal_guard_restores_on_drop_and_panic() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let restores = Arc::new(AtomicUsize::new(0));
+        let guard = || {
+            let restores = Arc::clone(&restores);
+            TerminalGuard {
+                restore: Some(move || {
+                    restores.fetch_add(1, Ordering::SeqCst);
+                    Ok(())
+                }),
+            }
+        };
 
-    *line_number += 1;
+        drop(guard());
+        assert_eq!(restores.load(Ordering::SeqCst), 1);
 
-    // Add children
-    for child in &node.children {
-        flatten_node(child, certificates, depth + 1, line_number);
+        let armed = guard();
+        let result = std::panic::catch_unwind(move || {
+            let _armed = armed;
+            panic!("draw failed");
+        });
+        assert!(result.is_err());
+        assert_eq!(restores.load(Ordering::SeqCst), 2);
+
+        // An explicit restore disarms the guard, so dropping it does not restore twice
+        guard().restore().unwrap();
+        assert_eq!(restores.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_tree_text_written_to_file() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+        let tree = build_certificate_tree(&certs);
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        display_certificate_tree_text(
+            &tree,
+            &mut file,
+            false,
+            TreeChars::UNICODE,
+            DisplayTimezone::Utc,
+        )
+        .unwrap();
+
+        let contents = fs::read_to_string(file.path()).unwrap();
+        assert!(contents.contains("cert-tree Test Root CA"));
+        assert!(contents.contains("www.example.com"));
+    }
+
+    #[test]
+    fn test_verbose_written_to_buffer() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+
+        let mut buffer = Vec::new();
+        display_verbose(
+            &certs[0],
+            Verbosity::Normal,
+            DisplayTimezone::Utc,
+            &mut buffer,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains("CN: www.example.com"));
+        assert!(output.contains("Extensions:"));
+        assert!(!output.contains("SHA-256 Fingerprint"));
+    }
+
+    #[test]
+    fn test_quiet_omits_extensions() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+
+        let mut buffer = Vec::new();
+        display_verbose(
+            &certs[0],
+            Verbosity::Quiet,
+            DisplayTimezone::Utc,
+            &mut buffer,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.starts_with("www.example.com: ✓ Valid (expires in "));
+        assert_eq!(output.lines().count(), 1);
+        assert!(!output.contains("Extensions"));
+    }
+
+    #[test]
+    fn test_verbose_adds_fingerprints_and_raw_values() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+
+        let mut buffer = Vec::new();
+        display_verbose(
+            &certs[0],
+            Verbosity::Verbose,
+            DisplayTimezone::Utc,
+            &mut buffer,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains(&format!(
+            "SHA-256 Fingerprint: {}",
+            certs[0].sha256_fingerprint
+        )));
+        let ext = &certs[0].extensions[0];
+        assert!(output.contains(&format!(" - {}", ext.value)));
+    }
+
+    #[test]
+    fn test_verbose_lists_every_cn() {
+        let mut certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+        certs[0].subject = "CN=www.example.com, O=Example, CN=example.com".to_string();
+
+        let mut buffer = Vec::new();
+        display_verbose(
+            &certs[0],
+            Verbosity::Normal,
+            DisplayTimezone::Utc,
+            &mut buffer,
+        )
+        .unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains("CN: www.example.com\n"));
+        assert!(!output.contains("All CNs"));
+
+        let mut buffer = Vec::new();
+        display_verbose(
+            &certs[0],
+            Verbosity::Verbose,
+            DisplayTimezone::Utc,
+            &mut buffer,
+        )
+        .unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains("All CNs: www.example.com; example.com\n"));
+    }
+
+    #[test]
+    fn test_tree_text_flags_orphan_intermediate() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+        let tree = build_certificate_tree(&certs[..2]);
+
+        let mut buffer = Vec::new();
+        display_certificate_tree_text(
+            &tree,
+            &mut buffer,
+            false,
+            TreeChars::UNICODE,
+            DisplayTimezone::Utc,
+        )
+        .unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines[0].ends_with("[ISSUER MISSING]"));
+        assert!(!lines[1].contains("[ISSUER MISSING]"));
+    }
+
+    #[test]
+    fn test_tree_text_marks_not_yet_valid() {
+        let mut certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+        certs[0].not_before_dt = chrono::Utc::now() + chrono::Duration::days(1);
+        let tree = build_certificate_tree(&certs);
+
+        let mut buffer = Vec::new();
+        display_certificate_tree_text(
+            &tree,
+            &mut buffer,
+            false,
+            TreeChars::UNICODE,
+            DisplayTimezone::Utc,
+        )
+        .unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        let leaf = output
+            .lines()
+            .find(|line| line.contains("www.example.com"))
+            .unwrap();
+        assert!(leaf.contains("NOT YET VALID"));
+
+        let mut buffer = Vec::new();
+        display_verbose(
+            &certs[0],
+            Verbosity::Quiet,
+            DisplayTimezone::Utc,
+            &mut buffer,
+        )
+        .unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.starts_with("www.example.com: ⧗ Not Yet Valid"));
+    }
+
+    #[test]
+    fn test_tree_text_shows_truncated_descendants() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+        let mut tree = build_certificate_tree(&certs);
+        cert_tree::tree::limit_depth(&mut tree, 1);
+
+        let mut buffer = Vec::new();
+        display_certificate_tree_text(
+            &tree,
+            &mut buffer,
+            false,
+            TreeChars::UNICODE,
+            DisplayTimezone::Utc,
+        )
+        .unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("cert-tree Test Root CA"));
+        assert!(lines[1].ends_with("└ … 2 more certificates"));
+    }
+
+    #[test]
+    fn test_tree_text_unicode_and_ascii_connectors() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+        let tree = build_certificate_tree(&certs[..2]);
+        let render = |chars| {
+            let mut buffer = Vec::new();
+            display_certificate_tree_text(&tree, &mut buffer, false, chars, DisplayTimezone::Utc)
+                .unwrap();
+            String::from_utf8(buffer).unwrap()
+        };
+
+        let unicode = render(TreeChars::UNICODE);
+        let lines: Vec<&str> = unicode.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("[1] ━ "));
+        assert!(lines[1].starts_with("[2]      └ www.example.com"));
+
+        let ascii = render(TreeChars::ASCII);
+        assert!(ascii.is_ascii());
+        let lines: Vec<&str> = ascii.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("[1] - "));
+        assert!(lines[1].starts_with("[2]      `- www.example.com"));
+    }
+
+    #[test]
+    fn test_compact_writes_leaf_to_root_path() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+        let tree = build_certificate_tree(&certs);
+        let mut buffer = Vec::new();
+        display_compact(&tree, &mut buffer, false, DisplayTimezone::Utc).unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "www.example.com [VALID until 2099-12-31] <- cert-tree Test Intermediate CA <- cert-tree Test Root CA\n"
+        );
+
+        let mut buffer = Vec::new();
+        display_compact(&tree, &mut buffer, true, DisplayTimezone::Utc).unwrap();
+        let colored = String::from_utf8(buffer).unwrap();
+        assert!(colored.starts_with("\x1b[32mwww.example.com [VALID until 2099-12-31]\x1b[0m <- "));
+    }
+
+    #[test]
+    fn test_locale_selects_utf8() {
+        assert!(locale_is_utf8(None));
+        assert!(locale_is_utf8(Some("en_US.UTF-8")));
+        assert!(locale_is_utf8(Some("de_DE.utf8")));
+        assert!(!locale_is_utf8(Some("C")));
+        assert!(!locale_is_utf8(Some("POSIX")));
+    }
+
+    #[test]
+    fn test_tree_text_without_color_has_no_escape_codes() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+        let tree = build_certificate_tree(&certs);
+
+        let mut plain = Vec::new();
+        display_certificate_tree_text(
+            &tree,
+            &mut plain,
+            false,
+            TreeChars::UNICODE,
+            DisplayTimezone::Utc,
+        )
+        .unwrap();
+        assert!(!plain.contains(&0x1b));
+
+        let mut colored = Vec::new();
+        display_certificate_tree_text(
+            &tree,
+            &mut colored,
+            true,
+            TreeChars::UNICODE,
+            DisplayTimezone::Utc,
+        )
+        .unwrap();
+        assert!(colored.contains(&0x1b));
+    }
+
+    #[test]
+    fn test_date_column_follows_longest_name() {
+        // Short names pull the column left to one space past the longest
+        assert_eq!(date_column_start([10, 30, 20], 200), 31);
+        assert_eq!(date_column_start([4, 8], 200), 9);
+        assert_eq!(date_column_start([], 200), 1);
+
+        // Long names are capped so the status and date still fit the terminal
+        assert_eq!(
+            date_column_start([150, 20], 120),
+            120 - DATE_COLUMN_RESERVED
+        );
+        assert_eq!(date_column_start([150], 50), MIN_DATE_COLUMN_START);
+    }
+
+    #[test]
+    fn test_nav_action_vim_bindings() {
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+
+        assert_eq!(nav_action(&key(KeyCode::Char('j'))), Some(NavAction::Down));
+        assert_eq!(nav_action(&key(KeyCode::Char('k'))), Some(NavAction::Up));
+        assert_eq!(nav_action(&key(KeyCode::Char('g'))), Some(NavAction::Top));
+        assert_eq!(
+            nav_action(&KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT)),
+            Some(NavAction::Bottom)
+        );
+        assert_eq!(nav_action(&ctrl('d')), Some(NavAction::HalfPageDown));
+        assert_eq!(nav_action(&ctrl('u')), Some(NavAction::HalfPageUp));
+        assert_eq!(nav_action(&key(KeyCode::Down)), Some(NavAction::Down));
+        assert_eq!(nav_action(&key(KeyCode::PageUp)), Some(NavAction::PageUp));
+
+        // Plain d/u and other keys are not navigation
+        assert_eq!(nav_action(&key(KeyCode::Char('d'))), None);
+        assert_eq!(nav_action(&ctrl('j')), None);
+        assert_eq!(nav_action(&key(KeyCode::Char('t'))), None);
+    }
+
+    #[test]
+    fn test_select_index_stays_in_list() {
+        assert_eq!(select_index(3, 20, NavAction::Top), 0);
+        assert_eq!(select_index(3, 20, NavAction::Bottom), 19);
+        assert_eq!(
+            select_index(3, 20, NavAction::HalfPageDown),
+            3 + PAGE_SIZE / 2
+        );
+        assert_eq!(select_index(2, 20, NavAction::HalfPageUp), 0);
+        assert_eq!(select_index(19, 20, NavAction::Down), 19);
+        assert_eq!(select_index(0, 0, NavAction::Bottom), 0);
+        assert_eq!(scroll_offset(1, NavAction::Bottom), MAX_SCROLL_LIMIT);
+        assert_eq!(
+            scroll_offset(MAX_SCROLL_LIMIT, NavAction::Down),
+            MAX_SCROLL_LIMIT
+        );
+    }
+
+    #[test]
+    fn test_poll_refresh_waits_for_worker() {
+        let (sender, receiver) = mpsc::channel();
+        assert!(poll_refresh(&receiver).is_none());
+
+        thread::spawn(move || sender.send(Ok(CertificateTree::default())).unwrap())
+            .join()
+            .unwrap();
+        assert!(matches!(poll_refresh(&receiver), Some(Ok(_))));
+        // A worker that ended without sending is reported as a failed refresh
+        assert!(matches!(poll_refresh(&receiver), Some(Err(_))));
+    }
+
+    #[test]
+    fn test_refresh_due_once_interval_elapsed() {
+        let interval = Duration::from_secs(30);
+        assert!(!refresh_due(Duration::ZERO, interval));
+        assert!(!refresh_due(Duration::from_millis(29_900), interval));
+        assert!(refresh_due(interval, interval));
+        assert!(refresh_due(Duration::from_secs(95), interval));
+    }
+
+    #[test]
+    fn test_extension_popup_lists_decoded_value_items() {
+        let ext = ExtensionInfo {
+            oid: "2.5.29.30".to_string(),
+            name: Some("Name Constraints".to_string()),
+            critical: true,
+            value:
+                "Permitted: DNS:example.com, IP Address:10.0.0.0/8; Excluded: DNS:bad.example.com"
+                    .to_string(),
+        };
+        let text: Vec<String> = extension_popup_lines(&ext)
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            text,
+            [
+                "Name: Name Constraints",
+                "OID: 2.5.29.30",
+                "Criticality: critical",
+                "",
+                "Value:",
+                "  Permitted: DNS:example.com",
+                "  IP Address:10.0.0.0/8",
+                "  Excluded: DNS:bad.example.com",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dn_lines_one_component_per_line() {
+        let lines = dn_lines("Subject", "C=US, O=Example Corp, CN=www.example.com");
+        let text: Vec<String> = lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            text,
+            [
+                "Subject:",
+                "  C: US",
+                "  O: Example Corp",
+                "  CN: www.example.com"
+            ]
+        );
+
+        assert_eq!(dn_lines("Subject", "").len(), 1);
+    }
+
+    fn display_item(subject: &str, issuer: &str) -> CertificateDisplayItem {
+        CertificateDisplayItem {
+            display_name: subject.to_string(),
+            valid_until: chrono::DateTime::default(),
+            role: CertificateRole::Leaf,
+            validity_status: ValidityStatus::Valid,
+            validation_status: cert_tree::models::ValidationStatus::Valid,
+            revocation_status: RevocationStatus::NotChecked,
+            trust_status: TrustStatus::NotChecked,
+            certificate_info: CertificateInfo {
+                subject: subject.to_string(),
+                issuer: issuer.to_string(),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_matches_filter_by_cn_or_issuer() {
+        let items = [
+            display_item(
+                "CN=DigiCert Global Root G2, O=DigiCert Inc",
+                "CN=DigiCert Global Root G2",
+            ),
+            display_item("CN=www.example.com, O=Example Org", "CN=Example Issuing CA"),
+            display_item(
+                "CN=ISRG Root X1, O=Internet Security Research Group",
+                "CN=ISRG Root X1",
+            ),
+        ];
+
+        let matching = |query: &str| -> Vec<String> {
+            items
+                .iter()
+                .filter(|item| matches_filter(item, query))
+                .map(|item| cert_tree::parser::extract_cn(&item.certificate_info.subject))
+                .collect()
+        };
+
+        assert_eq!(matching("").len(), 3);
+        assert_eq!(matching("digicert"), vec!["DigiCert Global Root G2"]);
+        // Issuer matches count even when the CN does not
+        assert_eq!(matching("issuing ca"), vec!["www.example.com"]);
+        // Subject attributes other than CN are not searched
+        assert!(matching("internet security").is_empty());
+        assert!(matching("nothing").is_empty());
+    }
+
+    #[test]
+    fn test_yaml_tree_round_trip() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+        let tree = build_certificate_tree(&certs);
+
+        let mut buffer = Vec::new();
+        display_yaml(&tree, &mut buffer).unwrap();
+        let yaml = String::from_utf8(buffer.clone()).unwrap();
+        assert!(yaml.contains("validity_status: valid"));
+        assert!(yaml.contains("revocation_status: not_checked"));
+
+        let parsed: CertificateTree = serde_yaml::from_slice(&buffer).unwrap();
+        let root = &parsed.roots[0];
+        assert!(root.cert.subject.contains("cert-tree Test Root CA"));
+        assert!(matches!(root.validity_status, ValidityStatus::Valid));
+
+        let leaf = &root.children[0].children[0];
+        assert!(leaf.cert.subject.contains("www.example.com"));
+        assert_eq!(leaf.cert.serial_number, certs[0].serial_number);
+        assert_eq!(leaf.cert.subject_alt_names, certs[0].subject_alt_names);
+    }
+
+    #[test]
+    fn test_json_certificate_round_trip() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+
+        let mut buffer = Vec::new();
+        display_json(&certs[0], &mut buffer).unwrap();
+
+        let parsed: CertificateInfo = serde_json::from_slice(&buffer).unwrap();
+        assert_eq!(parsed.subject, certs[0].subject);
+        assert_eq!(parsed.sha256_fingerprint, certs[0].sha256_fingerprint);
+        assert!(parsed.der.is_empty());
+    }
+
+    #[test]
+    fn test_ndjson_one_object_per_line() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+
+        let mut buffer = Vec::new();
+        display_ndjson(&certs, &mut buffer).unwrap();
+
+        let output = String::from_utf8(buffer).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), certs.len());
+        for (line, cert) in lines.iter().zip(&certs) {
+            let parsed: CertificateInfo = serde_json::from_str(line).unwrap();
+            assert_eq!(parsed.subject, cert.subject);
+        }
+    }
+
+    /// Split one CSV line into fields, honoring RFC 4180 quoting
+    fn parse_csv_line(line: &str) -> Vec<String> {
+        let mut fields = vec![String::new()];
+        let mut quoted = false;
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if quoted && chars.peek() == Some(&'"') => {
+                    fields.last_mut().unwrap().push('"');
+                    chars.next();
+                }
+                '"' => quoted = !quoted,
+                ',' if !quoted => fields.push(String::new()),
+                _ => fields.last_mut().unwrap().push(c),
+            }
+        }
+        fields
+    }
+
+    #[test]
+    fn test_pem_out_reparses_to_same_serials() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+
+        let mut buffer = Vec::new();
+        display_pem(&certs, &mut buffer).unwrap();
+
+        let reparsed = parse_certificate_chain(&buffer).unwrap();
+        let serials = |certs: &[CertificateInfo]| {
+            certs
+                .iter()
+                .map(|cert| cert.serial_number.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(serials(&reparsed), serials(&certs));
+        assert_eq!(
+            String::from_utf8(buffer)
+                .unwrap()
+                .matches("-----BEGIN CERTIFICATE-----")
+                .count(),
+            certs.len()
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_output_is_bare_hex() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+
+        let mut buffer = Vec::new();
+        display_fingerprints(&certs[..1], FingerprintAlgorithm::Sha256, &mut buffer).unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "4B:C8:4B:D0:32:53:15:19:1F:5B:4A:AC:A0:BA:31:37:A3:C5:E8:AE:DF:5F:A0:94:D9:B7:CC:50:31:E5:F2:3B\n"
+        );
+
+        let mut buffer = Vec::new();
+        display_fingerprints(&certs, FingerprintAlgorithm::Sha1, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(output.lines().count(), certs.len());
+        assert!(output.starts_with("5F:8C:95:38:6D:C6:93:2C:0D:D3:9C:26:02:02:FB:D2:F8:A1:44:B2\n"));
+    }
+
+    #[test]
+    fn test_fields_print_only_requested_lines() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+
+        let fields = [Field::Cn, Field::NotAfter];
+        let mut buffer = Vec::new();
+        display_fields(&certs[0], &fields, DisplayTimezone::Utc, &mut buffer).unwrap();
+
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(
+            output,
+            format!(
+                "CN: www.example.com\nNot After: {} UTC\n",
+                certs[0].not_after
+            )
+        );
+
+        // Text lines follow --timezone; CSV and JSON keep the UTC value
+        let mut buffer = Vec::new();
+        display_fields(&certs[0], &fields, DisplayTimezone::Local, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.ends_with(&format!(
+            "Not After: {}\n",
+            certs[0].not_after_text(DisplayTimezone::Local)
+        )));
+    }
+
+    #[test]
+    fn test_fields_csv_header_and_rows() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+
+        let mut buffer = Vec::new();
+        display_fields_csv(&certs, &[Field::Cn, Field::SubjectAltNames], &mut buffer).unwrap();
+
+        let output = String::from_utf8(buffer).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "cn,subject_alt_names");
+        assert!(lines[1].starts_with("www.example.com,\"www.example.com, example.com, "));
+        assert_eq!(lines.len(), certs.len() + 1);
+    }
+
+    #[test]
+    fn test_csv_rows_round_trip() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+        let tree = build_certificate_tree(&certs);
+
+        let mut buffer = Vec::new();
+        display_csv(&tree, SortOrder::None, &mut buffer).unwrap();
+        let csv = String::from_utf8(buffer).unwrap();
+        let rows: Vec<Vec<String>> = csv.lines().map(parse_csv_line).collect();
+
+        assert_eq!(
+            rows[0],
+            vec![
+                "cn",
+                "issuer_cn",
+                "serial",
+                "not_before",
+                "not_after",
+                "days_until_expiry",
+                "validity_status",
+                "is_ca"
+            ]
+        );
+        assert_eq!(rows.len(), 4);
+
+        // Rows follow the tree order: root, intermediate, leaf
+        let leaf = &rows[3];
+        assert_eq!(leaf[0], "www.example.com");
+        assert_eq!(leaf[1], "cert-tree Test Intermediate CA");
+        assert_eq!(leaf[2], certs[0].serial_number);
+        assert_eq!(leaf[4], "2099-12-31 23:59:59");
+        assert!(leaf[5].parse::<i64>().unwrap() > 0);
+        assert_eq!(leaf[6], "valid");
+        assert_eq!(leaf[7], "false");
+    }
+
+    #[test]
+    fn test_expiry_sort_is_ascending() {
+        let certs = parse_certificate_chain(include_bytes!("../test/cacert.pem")).unwrap();
+        let tree = build_certificate_tree(&certs);
+
+        let items = flatten_certificate_tree(&tree, SortOrder::Expiry);
+        assert_eq!(items.len(), certs.len());
+        let dates: Vec<_> = items.iter().map(|item| item.valid_until).collect();
+        assert!(dates.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(items[0].display_name.starts_with("[1] "));
+    }
+
+    #[test]
+    fn test_detail_lines_labeled_and_raw() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+        let tree = build_certificate_tree(&certs);
+        let items = flatten_certificate_tree(&tree, SortOrder::default());
+        let leaf = items
+            .iter()
+            .find(|item| item.display_name.contains("www.example.com"))
+            .unwrap();
+        let text = |raw| -> Vec<String> {
+            detail_lines(leaf, raw, DisplayTimezone::Utc)
+                .iter()
+                .map(|line| {
+                    line.spans
+                        .iter()
+                        .map(|span| span.content.as_ref())
+                        .collect()
+                })
+                .collect()
+        };
+
+        let labeled = text(false);
+        assert!(labeled.contains(&"Chain Validation: ✓ Valid Chain".to_string()));
+        assert!(labeled.contains(&"  Basic Constraints (critical)".to_string()));
+
+        // The raw view is the -v dump, extension values included
+        let raw = text(true);
+        let mut verbose = Vec::new();
+        display_verbose(
+            &leaf.certificate_info,
+            Verbosity::Verbose,
+            DisplayTimezone::Utc,
+            &mut verbose,
+        )
+        .unwrap();
+        assert_eq!(raw.join("\n") + "\n", String::from_utf8(verbose).unwrap());
+        assert!(raw
+            .iter()
+            .any(|line| line.starts_with("  Basic Constraints (critical) - ")));
+    }
+
+    #[test]
+    fn test_table_has_header_and_separators() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+        let tree = build_certificate_tree(&certs);
+
+        let mut buffer = Vec::new();
+        display_table(&tree, SortOrder::None, DisplayTimezone::Utc, &mut buffer).unwrap();
+        let table = String::from_utf8(buffer).unwrap();
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert!(lines[0].starts_with('┌') && lines[0].ends_with('┐'));
+        let header: Vec<&str> = lines[1].split('│').map(str::trim).collect();
+        assert_eq!(header, vec!["", "CN", "Issuer", "Expiry", "Status", ""]);
+        assert!(lines[2].starts_with('├') && lines[2].contains('┼'));
+        assert!(lines[5].contains("www.example.com") && lines[5].contains("2099-12-31 23:59:59"));
+        assert!(lines[6].starts_with('└'));
+        assert_eq!(lines.len(), 7);
+
+        // Every line has the same width
+        let widths: Vec<usize> = lines.iter().map(|line| line.chars().count()).collect();
+        assert!(widths.iter().all(|&w| w == widths[0]));
+    }
+
+    #[test]
+    fn test_table_truncates_names_to_width() {
+        let rows = [[
+            "a-very-long-common-name.example.com".to_string(),
+            "Some Intermediate Certificate Authority".to_string(),
+            "2099-12-31 23:59:59".to_string(),
+            "valid".to_string(),
+        ]];
+
+        let mut buffer = Vec::new();
+        write_table(
+            &["CN", "Issuer", "Expiry", "Status"],
+            &rows,
+            60,
+            &mut buffer,
+        )
+        .unwrap();
+        let table = String::from_utf8(buffer).unwrap();
+
+        assert!(table.lines().all(|line| line.chars().count() == 60));
+        assert!(table.contains("│ a-very-lon… │ Some Inter… │"));
+        assert_eq!(truncate_cell("short", 8), "short");
+        assert_eq!(truncate_cell("longer-name", 5), "long…");
+    }
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("Smith, John"), "\"Smith, John\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn test_list_index_at_maps_click_rows() {
+        // List block at rows 3..13 with a one-cell border: items start on row 4
+        let area = Rect {
+            x: 0,
+            y: 3,
+            width: 40,
+            height: 10,
+        };
+
+        assert_eq!(list_index_at(area, 5, 4, 0, 20), Some(0));
+        assert_eq!(list_index_at(area, 5, 7, 0, 20), Some(3));
+        assert_eq!(list_index_at(area, 5, 7, 10, 20), Some(13));
+
+        // Borders, outside the block and past the last item
+        assert_eq!(list_index_at(area, 5, 3, 0, 20), None);
+        assert_eq!(list_index_at(area, 5, 12, 0, 20), None);
+        assert_eq!(list_index_at(area, 0, 5, 0, 20), None);
+        assert_eq!(list_index_at(area, 5, 20, 0, 20), None);
+        assert_eq!(list_index_at(area, 5, 8, 0, 3), None);
     }
 }
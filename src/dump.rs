@@ -0,0 +1,244 @@
+use cert_tree::parser::{curve_oid_to_name, oid_to_name, signature_alg_to_name};
+use std::borrow::Cow;
+use std::io::{self, Write};
+use x509_parser::der_parser::oid::Oid;
+
+/// Bytes per line of the hex dump
+const BYTES_PER_LINE: usize = 16;
+
+/// Content bytes previewed for binary ASN.1 values before eliding the rest
+const VALUE_PREVIEW_BYTES: usize = 16;
+
+/// Nesting beyond which the ASN.1 outline stops descending into constructed values
+const MAX_OUTLINE_DEPTH: usize = 32;
+
+/// Bit marking a constructed (nested) ASN.1 encoding in the identifier octet
+const CONSTRUCTED: u8 = 0x20;
+
+/// Write `bytes` as an `xxd`-style dump: offset, 16 bytes in 2-byte hex groups and an ASCII gutter
+///
+/// With `color`, offsets are cyan and printable ASCII green.
+///
+/// # Errors
+///
+/// Returns an error if writing to `out` fails.
+pub fn hex_dump(bytes: &[u8], out: &mut impl Write, color: bool) -> io::Result<()> {
+    // 8 groups of 4 hex digits separated by single spaces
+    let hex_width = BYTES_PER_LINE / 2 * 5 - 1;
+
+    for (line, chunk) in bytes.chunks(BYTES_PER_LINE).enumerate() {
+        let hex: Vec<String> = chunk
+            .chunks(2)
+            .map(|pair| {
+                pair.iter()
+                    .map(|byte| format!("{byte:02x}"))
+                    .collect::<Vec<_>>()
+                    .concat()
+            })
+            .collect();
+        let ascii: String = chunk
+            .iter()
+            .map(|&byte| {
+                if byte.is_ascii_graphic() || byte == b' ' {
+                    char::from(byte)
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        let offset = format!("{:08x}", line * BYTES_PER_LINE);
+        let hex = format!("{:<hex_width$}", hex.join(" "));
+
+        if color {
+            writeln!(
+                out,
+                "\x1b[36m{offset}\x1b[0m: {hex}  \x1b[32m{ascii}\x1b[0m"
+            )?;
+        } else {
+            writeln!(out, "{offset}: {hex}  {ascii}")?;
+        }
+    }
+    Ok(())
+}
+
+/// Write an indented outline of the DER structure, one line per TLV with its offset and length
+///
+/// OIDs are shown dotted with their name when known, strings and times as
+/// text, and other primitive values as a hex preview. Malformed or
+/// indefinite-length encodings end the outline with a marker line.
+///
+/// # Errors
+///
+/// Returns an error if writing to `out` fails.
+pub fn asn1_outline(der: &[u8], out: &mut impl Write, color: bool) -> io::Result<()> {
+    outline(der, 0, 0, out, color)
+}
+
+fn outline(
+    data: &[u8],
+    base_offset: usize,
+    depth: usize,
+    out: &mut impl Write,
+    color: bool,
+) -> io::Result<()> {
+    let indent = "  ".repeat(depth);
+    let mut pos = 0;
+    while pos < data.len() {
+        let offset = base_offset + pos;
+        let Some((tag, header_len, len)) = read_header(&data[pos..]) else {
+            return writeln!(out, "{offset:>6}: {indent}<malformed encoding>");
+        };
+        let content = &data[pos + header_len..pos + header_len + len];
+        let name = tag_name(tag);
+        let value = value_preview(tag, content);
+
+        if color {
+            writeln!(
+                out,
+                "{offset:>6}: {indent}\x1b[34m{name}\x1b[0m ({len} bytes)\x1b[32m{value}\x1b[0m"
+            )?;
+        } else {
+            writeln!(out, "{offset:>6}: {indent}{name} ({len} bytes){value}")?;
+        }
+
+        if tag & CONSTRUCTED != 0 && depth < MAX_OUTLINE_DEPTH {
+            outline(content, offset + header_len, depth + 1, out, color)?;
+        }
+        pos += header_len + len;
+    }
+    Ok(())
+}
+
+/// Decode a TLV header into (identifier octet, header length, content length)
+///
+/// Only low tag numbers and definite lengths of up to four octets occur in
+/// certificates; anything else, or a length past the data, yields `None`.
+fn read_header(data: &[u8]) -> Option<(u8, usize, usize)> {
+    let (&tag, rest) = data.split_first()?;
+    if tag & 0x1F == 0x1F {
+        return None;
+    }
+    let (&first, rest) = rest.split_first()?;
+    let (header_len, len) = if first < 0x80 {
+        (2, usize::from(first))
+    } else {
+        let count = usize::from(first & 0x7F);
+        if count == 0 || count > 4 || rest.len() < count {
+            return None;
+        }
+        let len = rest[..count]
+            .iter()
+            .fold(0usize, |len, &byte| (len << 8) | usize::from(byte));
+        (2 + count, len)
+    };
+    (data.len() - header_len >= len).then_some((tag, header_len, len))
+}
+
+/// Human-readable name of an identifier octet, e.g. `SEQUENCE` or `[3]`
+fn tag_name(tag: u8) -> String {
+    let number = tag & 0x1F;
+    match tag >> 6 {
+        0 => match number {
+            1 => "BOOLEAN".to_string(),
+            2 => "INTEGER".to_string(),
+            3 => "BIT STRING".to_string(),
+            4 => "OCTET STRING".to_string(),
+            5 => "NULL".to_string(),
+            6 => "OBJECT IDENTIFIER".to_string(),
+            12 => "UTF8String".to_string(),
+            16 => "SEQUENCE".to_string(),
+            17 => "SET".to_string(),
+            19 => "PrintableString".to_string(),
+            20 => "T61String".to_string(),
+            22 => "IA5String".to_string(),
+            23 => "UTCTime".to_string(),
+            24 => "GeneralizedTime".to_string(),
+            30 => "BMPString".to_string(),
+            _ => format!("UNIVERSAL {number}"),
+        },
+        1 => format!("[APPLICATION {number}]"),
+        2 => format!("[{number}]"),
+        _ => format!("[PRIVATE {number}]"),
+    }
+}
+
+/// Short rendering of a primitive value for the outline, prefixed with `: `; empty for constructed ones
+fn value_preview(tag: u8, content: &[u8]) -> String {
+    if tag & CONSTRUCTED != 0 || content.is_empty() {
+        return String::new();
+    }
+    match tag {
+        0x06 => {
+            let oid = Oid::new(Cow::Borrowed(content)).to_id_string();
+            let name = oid_to_name(&oid)
+                .or_else(|| signature_alg_to_name(&oid))
+                .or_else(|| curve_oid_to_name(&oid));
+            match name {
+                Some(name) => format!(": {oid} ({name})"),
+                None => format!(": {oid}"),
+            }
+        }
+        0x0C | 0x13 | 0x14 | 0x16 | 0x17 | 0x18 => {
+            format!(": {}", String::from_utf8_lossy(content))
+        }
+        _ => {
+            let shown = &content[..content.len().min(VALUE_PREVIEW_BYTES)];
+            let hex: Vec<String> = shown.iter().map(|byte| format!("{byte:02x}")).collect();
+            let more = if content.len() > shown.len() {
+                " …"
+            } else {
+                ""
+            };
+            format!(": {}{more}", hex.join(" "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_dump_matches_xxd_layout() {
+        let mut buffer = Vec::new();
+        hex_dump(b"0123456789abcdef\x00\x01\xff", &mut buffer, false).unwrap();
+
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "00000000: 3031 3233 3435 3637 3839 6162 6364 6566  0123456789abcdef\n\
+             00000010: 0001 ff                                  ...\n"
+        );
+    }
+
+    #[test]
+    fn test_asn1_outline_nests_constructed_values() {
+        // SEQUENCE { OID 2.5.29.19, BOOLEAN TRUE, OCTET STRING 30 00 }
+        let der = [
+            0x30, 0x0C, 0x06, 0x03, 0x55, 0x1D, 0x13, 0x01, 0x01, 0xFF, 0x04, 0x02, 0x30, 0x00,
+        ];
+
+        let mut buffer = Vec::new();
+        asn1_outline(&der, &mut buffer, false).unwrap();
+
+        let output = String::from_utf8(buffer).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "     0: SEQUENCE (12 bytes)");
+        assert_eq!(
+            lines[1],
+            "     2:   OBJECT IDENTIFIER (3 bytes): 2.5.29.19 (Basic Constraints)"
+        );
+        assert_eq!(lines[2], "     7:   BOOLEAN (1 bytes): ff");
+        assert_eq!(lines[3], "    10:   OCTET STRING (2 bytes): 30 00");
+    }
+
+    #[test]
+    fn test_asn1_outline_flags_truncated_input() {
+        let mut buffer = Vec::new();
+        asn1_outline(&[0x30, 0x05, 0x02, 0x01], &mut buffer, false).unwrap();
+
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "     0: <malformed encoding>\n"
+        );
+    }
+}
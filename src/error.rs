@@ -6,16 +6,69 @@ pub enum CertError {
     Io(#[from] std::io::Error),
     #[error("HTTP error: {0}")]
     Http(#[from] reqwest::Error),
+    #[error("DNS resolution failed: {0}")]
+    DnsResolution(String),
     #[error("TLS error: {0}")]
     Tls(String),
+    #[error("STARTTLS negotiation failed: {0}")]
+    StartTls(String),
+    #[error("Client certificate error: {0}")]
+    ClientAuth(String),
+    #[error("Proxy error: {0}")]
+    Proxy(String),
+    #[error("PEM parsing error: {0}")]
+    PemParse(String),
     #[error("X.509 parsing error: {0}")]
     X509Parse(String),
+    #[error("No certificates found: {0}")]
+    NoCertificates(String),
+    #[error("Invalid hex input: {0}")]
+    Hex(String),
+    #[error("Invalid OID map: {0}")]
+    OidMap(String),
     #[error("Invalid certificate format")]
     InvalidFormat,
     #[error("Certificate not found")]
     NotFound,
 }
 
+impl CertError {
+    /// Whether the failure may go away on its own, such as a timeout or a reset connection
+    ///
+    /// Malformed URLs, unparseable certificates and TLS or STARTTLS protocol
+    /// errors are permanent and not worth retrying. HTTP errors are classified by
+    /// the I/O error underneath them, so a refused connection is permanent on
+    /// both the download and the handshake path.
+    #[must_use]
+    pub fn is_transient(&self) -> bool {
+        match self {
+            CertError::Io(err) => is_transient_io(err),
+            CertError::Http(err) => {
+                err.is_timeout()
+                    || std::iter::successors(std::error::Error::source(err), |source| {
+                        source.source()
+                    })
+                    .find_map(|source| source.downcast_ref::<std::io::Error>())
+                    .is_some_and(is_transient_io)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn is_transient_io(err: &std::io::Error) -> bool {
+    matches!(
+        err.kind(),
+        std::io::ErrorKind::TimedOut
+            | std::io::ErrorKind::WouldBlock
+            | std::io::ErrorKind::ConnectionReset
+            | std::io::ErrorKind::ConnectionAborted
+            | std::io::ErrorKind::BrokenPipe
+            | std::io::ErrorKind::UnexpectedEof
+            | std::io::ErrorKind::Interrupted
+    )
+}
+
 impl From<rustls::Error> for CertError {
     fn from(err: rustls::Error) -> Self {
         CertError::Tls(err.to_string())
@@ -0,0 +1,208 @@
+use cert_tree::models::{CertificateInfo, DisplayTimezone, ValidityStatus};
+use cert_tree::parser::extract_cn;
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use serde_json::Value;
+
+/// A certificate field selectable with `--fields`
+///
+/// Names follow the CSV columns, or the JSON keys for fields the CSV lacks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[value(rename_all = "snake_case")]
+pub enum Field {
+    /// Subject common name
+    Cn,
+    /// Full subject DN
+    Subject,
+    /// Full issuer DN
+    Issuer,
+    /// Issuer common name
+    IssuerCn,
+    /// Serial number
+    Serial,
+    /// Start of the validity period
+    NotBefore,
+    /// End of the validity period
+    NotAfter,
+    /// Signed whole days until expiry
+    DaysUntilExpiry,
+    /// Validity status label, e.g. `expiring_soon`
+    ValidityStatus,
+    /// Public key algorithm
+    PublicKeyAlgorithm,
+    /// Signature algorithm
+    SignatureAlgorithm,
+    /// X.509 version, e.g. v3
+    Version,
+    /// Whether the certificate is a CA
+    IsCa,
+    /// Key Usage
+    KeyUsage,
+    /// Extended Key Usage purposes
+    ExtendedKeyUsage,
+    /// Certificate policies
+    Policies,
+    /// Subject Alternative Names
+    SubjectAltNames,
+    /// CRL distribution point URLs
+    CrlUrls,
+    /// OCSP responder URLs
+    OcspUrls,
+    /// CA Issuers URLs
+    CaIssuerUrls,
+    /// SHA-256 fingerprint
+    Sha256Fingerprint,
+    /// SHA-1 fingerprint
+    Sha1Fingerprint,
+}
+
+impl Field {
+    /// Name as given to `--fields` and used for CSV headers and JSON keys
+    #[must_use]
+    pub fn name(self) -> String {
+        clap::ValueEnum::to_possible_value(&self)
+            .map(|value| value.get_name().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Label for text output, matching the full certificate display
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Field::Cn => "CN",
+            Field::Subject => "Subject",
+            Field::Issuer => "Issuer",
+            Field::IssuerCn => "Issuer CN",
+            Field::Serial => "Serial Number",
+            Field::NotBefore => "Not Before",
+            Field::NotAfter => "Not After",
+            Field::DaysUntilExpiry => "Days Until Expiry",
+            Field::ValidityStatus => "Status",
+            Field::PublicKeyAlgorithm => "Public Key Algorithm",
+            Field::SignatureAlgorithm => "Signature Algorithm",
+            Field::Version => "Version",
+            Field::IsCa => "Is CA",
+            Field::KeyUsage => "Key Usage",
+            Field::ExtendedKeyUsage => "Extended Key Usage",
+            Field::Policies => "Certificate Policies",
+            Field::SubjectAltNames => "Subject Alternative Names",
+            Field::CrlUrls => "CRL Distribution Points",
+            Field::OcspUrls => "OCSP Responders",
+            Field::CaIssuerUrls => "CA Issuers",
+            Field::Sha256Fingerprint => "SHA-256 Fingerprint",
+            Field::Sha1Fingerprint => "SHA-1 Fingerprint",
+        }
+    }
+
+    /// The field's value as JSON: strings, numbers, booleans, lists, or null when absent
+    #[must_use]
+    pub fn value(self, cert: &CertificateInfo) -> Value {
+        match self {
+            Field::Cn => extract_cn(&cert.subject).into(),
+            Field::Subject => cert.subject.clone().into(),
+            Field::Issuer => cert.issuer.clone().into(),
+            Field::IssuerCn => extract_cn(&cert.issuer).into(),
+            Field::Serial => cert.serial_number.clone().into(),
+            Field::NotBefore => cert.not_before.clone().into(),
+            Field::NotAfter => cert.not_after.clone().into(),
+            Field::DaysUntilExpiry => cert.days_until_expiry().into(),
+            Field::ValidityStatus => {
+                ValidityStatus::from_dates(cert.not_before_dt, cert.not_after_dt)
+                    .label()
+                    .into()
+            }
+            Field::PublicKeyAlgorithm => cert.public_key_algorithm.clone().into(),
+            Field::SignatureAlgorithm => cert.signature_algorithm.clone().into(),
+            Field::Version => format!("v{}", cert.version + 1).into(),
+            Field::IsCa => cert.is_ca.into(),
+            Field::KeyUsage => cert.key_usage.clone().into(),
+            Field::ExtendedKeyUsage => cert.extended_key_usage.clone().into(),
+            Field::Policies => cert.policies.clone().into(),
+            Field::SubjectAltNames => cert.subject_alt_names.clone().into(),
+            Field::CrlUrls => cert.crl_urls.clone().into(),
+            Field::OcspUrls => cert.ocsp_urls.clone().into(),
+            Field::CaIssuerUrls => cert.ca_issuer_urls.clone().into(),
+            Field::Sha256Fingerprint => cert.sha256_fingerprint.clone().into(),
+            Field::Sha1Fingerprint => cert.sha1_fingerprint.clone().into(),
+        }
+    }
+
+    /// The field's value as one line of text; lists are comma-separated, absent values empty
+    #[must_use]
+    pub fn text(self, cert: &CertificateInfo) -> String {
+        match self.value(cert) {
+            Value::Null => String::new(),
+            Value::String(text) => text,
+            Value::Array(items) => items
+                .iter()
+                .map(|item| {
+                    item.as_str()
+                        .map_or_else(|| item.to_string(), str::to_string)
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+            other => other.to_string(),
+        }
+    }
+
+    /// [`Field::text`] for human-readable output, with validity dates in `zone`
+    #[must_use]
+    pub fn zoned_text(self, cert: &CertificateInfo, zone: DisplayTimezone) -> String {
+        match self {
+            Field::NotBefore => cert.not_before_text(zone),
+            Field::NotAfter => cert.not_after_text(zone),
+            _ => self.text(cert),
+        }
+    }
+}
+
+/// A certificate reduced to the chosen fields, serialized as a map in their order
+pub struct SelectedFields<'a> {
+    pub cert: &'a CertificateInfo,
+    pub fields: &'a [Field],
+}
+
+impl Serialize for SelectedFields<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.fields.len()))?;
+        for field in self.fields {
+            map.serialize_entry(&field.name(), &field.value(self.cert))?;
+        }
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cert_tree::parser::parse_certificate_chain;
+
+    const CHAIN_PEM: &[u8] = include_bytes!("../test/chain.pem");
+
+    #[test]
+    fn test_selected_fields_keep_requested_order() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+        let selected = SelectedFields {
+            cert: &certs[0],
+            fields: &[Field::Serial, Field::Cn, Field::IsCa],
+        };
+
+        let json = serde_json::to_string(&selected).unwrap();
+        assert_eq!(
+            json,
+            format!(
+                r#"{{"serial":"{}","cn":"www.example.com","is_ca":false}}"#,
+                certs[0].serial_number
+            )
+        );
+    }
+
+    #[test]
+    fn test_field_names_are_snake_case() {
+        assert_eq!(Field::NotAfter.name(), "not_after");
+        assert_eq!(Field::Sha256Fingerprint.name(), "sha256_fingerprint");
+        assert_eq!(
+            <Field as clap::ValueEnum>::from_str("issuer_cn", false),
+            Ok(Field::IssuerCn)
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! Hostname verification
+//!
+//! Checks a connected hostname against a certificate's Subject Alternative Names
+//! following RFC 6125: DNS names compare case-insensitively, a leftmost `*` label
+//! matches exactly one label, IP hosts only match IP SANs, and the subject CN is
+//! consulted only when the certificate has no DNS or IP SANs.
+
+use crate::models::{CertificateInfo, HostnameCheck, HostnameMatch};
+use std::net::IpAddr;
+use x509_parser::extensions::GeneralName;
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+/// Check whether `cert` is valid for `hostname`
+#[must_use]
+pub fn verify_hostname(cert: &CertificateInfo, hostname: &str) -> HostnameCheck {
+    let host = hostname
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .trim_end_matches('.')
+        .to_ascii_lowercase();
+
+    let matched = X509Certificate::from_der(&cert.der)
+        .is_ok_and(|(_, parsed)| matches_certificate(&parsed, &host));
+
+    HostnameCheck {
+        hostname: hostname.to_string(),
+        status: if matched {
+            HostnameMatch::Match
+        } else {
+            HostnameMatch::Mismatch
+        },
+    }
+}
+
+fn matches_certificate(cert: &X509Certificate, host: &str) -> bool {
+    let host_ip: Option<IpAddr> = host.parse().ok();
+    let mut has_identifiers = false;
+
+    if let Ok(Some(san)) = cert.subject_alternative_name() {
+        for name in &san.value.general_names {
+            match name {
+                GeneralName::DNSName(pattern) => {
+                    has_identifiers = true;
+                    if host_ip.is_none() && dns_name_matches(pattern, host) {
+                        return true;
+                    }
+                }
+                GeneralName::IPAddress(bytes) => {
+                    has_identifiers = true;
+                    if host_ip.is_some() && ip_from_bytes(bytes) == host_ip {
+                        return true;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Legacy fallback: only certificates without DNS/IP SANs are matched on CN
+    !has_identifiers
+        && host_ip.is_none()
+        && cert
+            .subject()
+            .iter_common_name()
+            .filter_map(|cn| cn.as_str().ok())
+            .any(|cn| dns_name_matches(cn, host))
+}
+
+fn ip_from_bytes(bytes: &[u8]) -> Option<IpAddr> {
+    match bytes.len() {
+        4 => <[u8; 4]>::try_from(bytes).ok().map(IpAddr::from),
+        16 => <[u8; 16]>::try_from(bytes).ok().map(IpAddr::from),
+        _ => None,
+    }
+}
+
+/// Match a DNS name or wildcard pattern against an already-lowercased host
+///
+/// A wildcard is only honored as the entire leftmost label and must leave at
+/// least two labels (so `*.com` matches nothing).
+fn dns_name_matches(pattern: &str, host: &str) -> bool {
+    let pattern = pattern.trim_end_matches('.').to_ascii_lowercase();
+
+    match pattern.strip_prefix("*.") {
+        Some(suffix) => {
+            suffix.contains('.')
+                && host
+                    .split_once('.')
+                    .is_some_and(|(label, rest)| !label.is_empty() && rest == suffix)
+        }
+        None => pattern == host,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_certificate_chain;
+
+    const LEAF_PEM: &[u8] = include_bytes!("../test/leaf.pem");
+
+    fn leaf() -> CertificateInfo {
+        parse_certificate_chain(LEAF_PEM).unwrap().remove(0)
+    }
+
+    #[test]
+    fn test_exact_match_is_case_insensitive() {
+        let cert = leaf();
+        for host in [
+            "www.example.com",
+            "WWW.Example.COM",
+            "example.com.",
+            "localhost",
+        ] {
+            assert!(
+                matches!(verify_hostname(&cert, host).status, HostnameMatch::Match),
+                "{host}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_ip_sans() {
+        let cert = leaf();
+        assert!(matches!(
+            verify_hostname(&cert, "127.0.0.1").status,
+            HostnameMatch::Match
+        ));
+        assert!(matches!(
+            verify_hostname(&cert, "[::1]").status,
+            HostnameMatch::Match
+        ));
+        assert!(matches!(
+            verify_hostname(&cert, "10.0.0.1").status,
+            HostnameMatch::Mismatch
+        ));
+    }
+
+    #[test]
+    fn test_wildcard_matching() {
+        assert!(dns_name_matches("*.example.com", "www.example.com"));
+        assert!(dns_name_matches("*.Example.com", "api.example.com"));
+        assert!(!dns_name_matches("*.example.com", "example.com"));
+        assert!(!dns_name_matches("*.example.com", "a.b.example.com"));
+        assert!(!dns_name_matches("*.com", "example.com"));
+        assert!(!dns_name_matches("www.*.com", "www.example.com"));
+    }
+
+    #[test]
+    fn test_mismatch() {
+        let check = verify_hostname(&leaf(), "mail.example.com");
+        assert!(matches!(check.status, HostnameMatch::Mismatch));
+        assert_eq!(check.hostname, "mail.example.com");
+    }
+}
@@ -1,24 +1,66 @@
 use crate::error::CertError;
-use crate::models::CertificateInfo;
-use crate::parser::extract_cert_info;
+use crate::hostname::verify_hostname;
+use crate::models::{CertificateInfo, TlsConnectionInfo, TrustAnchor};
+use crate::parser::{extract_cert_info, InputFormat};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use flate2::read::GzDecoder;
+use ipnet::IpNet;
+use std::collections::HashSet;
 use std::fs;
 use std::io::{Read, Write};
-use std::net::TcpStream;
-use std::path::Path;
-use std::sync::Arc;
-use std::time::Duration;
+use std::net::{IpAddr, Ipv6Addr, SocketAddr, TcpStream, ToSocketAddrs};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
 use url::Url;
 use x509_parser::prelude::{FromDer, X509Certificate};
 
+pub use crate::ocsp::check_ocsp_status;
+
 /// Buffer size for reading certificate data from network
 const BUFFER_SIZE: usize = 1024;
 
-/// Standard HTTPS port number
+/// Default port when the URL does not specify one
 const HTTPS_PORT: u16 = 443;
 
+/// Port assumed for a proxy URL without one
+const HTTP_PROXY_PORT: u16 = 80;
+
+/// Message signed with a client key to check that it belongs to the client certificate
+const KEY_MATCH_CHALLENGE: &[u8] = b"cert-tree client key check";
+
 /// Connection timeout in seconds for network operations
 const CONNECTION_TIMEOUT_SECS: u64 = 10;
 
+/// Delay before the first retry of a transient network failure, doubled for each further retry
+const RETRY_BASE_DELAY_MS: u64 = 500;
+
+/// Longest wait between retries, however many have been made
+const MAX_RETRY_DELAY_MS: u64 = 8_000;
+
+/// Magic bytes at the start of a gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1F, 0x8B];
+
+/// DER tag a certificate (SEQUENCE) starts with
+const DER_SEQUENCE_TAG: u8 = 0x30;
+
+/// File extensions picked up when scanning a directory
+const CERTIFICATE_EXTENSIONS: [&str; 3] = ["pem", "crt", "der"];
+
+/// Upper bound on AIA downloads, so a misconfigured or looping chain cannot hang a run
+const MAX_AIA_FETCHES: usize = 8;
+
+/// Read a certificate file into memory
+///
+/// # Errors
+///
+/// Returns [`CertError::NotFound`] if the path does not exist, or an I/O error
+/// if it cannot be read.
 pub fn load_certificate_from_file(path: &str) -> Result<Vec<u8>, CertError> {
     let path = Path::new(path);
     if !path.exists() {
@@ -29,84 +71,2169 @@ pub fn load_certificate_from_file(path: &str) -> Result<Vec<u8>, CertError> {
     Ok(data)
 }
 
-pub fn fetch_certificate_chain_from_url(url: &str) -> Result<Vec<CertificateInfo>, CertError> {
-    // Parse the URL to extract hostname
-    let url_parsed = Url::parse(url).map_err(|_| CertError::InvalidFormat)?;
-    let hostname = url_parsed.host_str().ok_or(CertError::InvalidFormat)?;
+/// Certificates found by scanning a directory, and the files that failed to parse
+#[derive(Debug, Default)]
+pub struct DirectoryScan {
+    pub certificates: Vec<CertificateInfo>,
+    pub failures: Vec<(PathBuf, CertError)>,
+}
 
-    // First, try to fetch as direct certificate data (for URLs like cacert.pem)
-    let client = reqwest::blocking::Client::new();
-    if let Ok(response) = client.get(url).send() {
-        let data = response.bytes()?;
-        let content = String::from_utf8_lossy(&data);
+/// Read every `.pem`, `.crt` and `.der` file in a directory, optionally recursing
+///
+/// Files are visited in sorted order. A file that cannot be read or parsed is
+/// recorded in [`DirectoryScan::failures`] instead of aborting the scan.
+/// Symlinked directories are followed, but each real directory is read once,
+/// so a link back to a parent does not recurse forever.
+///
+/// # Errors
+///
+/// Returns [`CertError::NotFound`] if `path` does not exist, or an I/O error if
+/// the top-level directory cannot be listed.
+pub fn scan_directory(
+    path: &str,
+    recursive: bool,
+    format: InputFormat,
+) -> Result<DirectoryScan, CertError> {
+    let root = Path::new(path);
+    if !root.exists() {
+        return Err(CertError::NotFound);
+    }
+
+    let mut scan = DirectoryScan::default();
+    let mut files = Vec::new();
+    let mut visited: HashSet<PathBuf> = HashSet::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        if !visited.insert(fs::canonicalize(&dir).unwrap_or_else(|_| dir.clone())) {
+            debug!(dir = %dir.display(), "directory already scanned; skipping");
+            continue;
+        }
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if dir == root => return Err(e.into()),
+            Err(e) => {
+                scan.failures.push((dir, e.into()));
+                continue;
+            }
+        };
+        for entry in entries.flatten() {
+            let entry_path = entry.path();
+            if entry_path.is_dir() {
+                if recursive {
+                    pending.push(entry_path);
+                }
+            } else if has_certificate_extension(&entry_path) {
+                files.push(entry_path);
+            }
+        }
+    }
+    files.sort();
+
+    for file in files {
+        let parsed = fs::read(&file)
+            .map_err(CertError::from)
+            .and_then(|data| crate::parser::parse_certificate_chain_as(&data, format));
+        match parsed {
+            Ok(certificates) => scan.certificates.extend(certificates),
+            Err(e) => scan.failures.push((file, e)),
+        }
+    }
+    Ok(scan)
+}
+
+fn has_certificate_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            CERTIFICATE_EXTENSIONS
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known))
+        })
+}
+
+/// Parse a `--url` argument, accepting bare `host` or `host:port` inputs without a scheme
+///
+/// # Errors
+///
+/// Returns an error if the input is not a valid URL even with `https://` prepended.
+pub fn parse_target_url(input: &str) -> Result<Url, CertError> {
+    let with_scheme = if input.contains("://") {
+        input.to_string()
+    } else {
+        format!("https://{input}")
+    };
+    Url::parse(&with_scheme).map_err(|_| CertError::InvalidFormat)
+}
+
+/// Plaintext protocols that can upgrade to TLS with a STARTTLS-style command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartTls {
+    Smtp,
+    Imap,
+    Pop3,
+    Ftp,
+}
+
+impl StartTls {
+    /// Well-known plaintext port of the protocol, used when no port is given
+    #[must_use]
+    pub fn default_port(self) -> u16 {
+        match self {
+            StartTls::Smtp => 25,
+            StartTls::Imap => 143,
+            StartTls::Pop3 => 110,
+            StartTls::Ftp => 21,
+        }
+    }
+}
+
+/// Options controlling how certificates are fetched from the network
+#[derive(Debug, Clone, Default)]
+pub struct FetchOptions {
+    /// Port override; takes precedence over the port in the URL
+    pub port: Option<u16>,
+    /// Protocol to negotiate STARTTLS with before the TLS handshake
+    pub starttls: Option<StartTls>,
+    /// Name to present as SNI and HTTP Host instead of the connect host
+    pub servername: Option<String>,
+    /// DER trust anchors from `--ca-bundle`, accepted alongside the webpki roots
+    pub ca_bundle_roots: Vec<Vec<u8>>,
+    /// Extra attempts after a transient failure such as a timeout or reset connection
+    pub retries: u32,
+    /// HTTP proxy for downloads and, via CONNECT tunnels, for TLS handshakes
+    pub proxy: Option<Url>,
+    /// Certificate and key presented to servers that request client authentication
+    pub client_identity: Option<ClientIdentity>,
+}
+
+/// Client certificate chain and matching private key for mutual TLS
+#[derive(Debug, Clone)]
+pub struct ClientIdentity {
+    /// DER certificates, the client's own first
+    pub cert_chain: Vec<Vec<u8>>,
+    /// DER private key (PKCS#8, PKCS#1 RSA or SEC1 EC)
+    pub key: Vec<u8>,
+}
+
+/// Load a client certificate chain and its private key from PEM files for mutual TLS
+///
+/// # Errors
+///
+/// Returns [`CertError::ClientAuth`] if a file cannot be read, has no
+/// certificate or private key block, holds an unsupported key type, or the
+/// key does not belong to the first certificate.
+pub fn load_client_identity(cert_path: &str, key_path: &str) -> Result<ClientIdentity, CertError> {
+    let read = |path: &str| {
+        fs::read(path).map_err(|e| CertError::ClientAuth(format!("cannot read {path}: {e}")))
+    };
+    client_identity_from_pem(&read(cert_path)?, &read(key_path)?)
+}
+
+/// Build a [`ClientIdentity`] from PEM data, checking the key matches the certificate
+pub(crate) fn client_identity_from_pem(
+    cert_pem: &[u8],
+    key_pem: &[u8],
+) -> Result<ClientIdentity, CertError> {
+    let blocks = |data: &[u8]| {
+        pem::parse_many(data).map_err(|e| CertError::ClientAuth(format!("invalid PEM: {e}")))
+    };
+    let cert_chain: Vec<Vec<u8>> = blocks(cert_pem)?
+        .into_iter()
+        .filter(|block| block.tag() == "CERTIFICATE")
+        .map(pem::Pem::into_contents)
+        .collect();
+    let leaf = cert_chain.first().ok_or_else(|| {
+        CertError::ClientAuth("no CERTIFICATE block in the client certificate file".to_string())
+    })?;
+    let key = blocks(key_pem)?
+        .into_iter()
+        .find(|block| block.tag().ends_with("PRIVATE KEY"))
+        .ok_or_else(|| {
+            CertError::ClientAuth("no PRIVATE KEY block in the client key file".to_string())
+        })?
+        .into_contents();
+
+    if !key_matches_certificate(leaf, &key)? {
+        return Err(CertError::ClientAuth(
+            "the private key does not match the client certificate".to_string(),
+        ));
+    }
+    Ok(ClientIdentity { cert_chain, key })
+}
+
+/// Whether `key_der` is the private half of the certificate's public key
+///
+/// A fixed challenge is signed with the key and verified against the
+/// certificate's subject public key.
+fn key_matches_certificate(cert_der: &[u8], key_der: &[u8]) -> Result<bool, CertError> {
+    use ring::signature::{self, UnparsedPublicKey, VerificationAlgorithm};
+    use rustls::SignatureScheme;
+
+    let signing_key = rustls::sign::any_supported_type(&rustls::PrivateKey(key_der.to_vec()))
+        .map_err(|_| CertError::ClientAuth("unsupported private key type".to_string()))?;
+    let signer = signing_key
+        .choose_scheme(&[
+            SignatureScheme::RSA_PKCS1_SHA256,
+            SignatureScheme::ECDSA_NISTP256_SHA256,
+            SignatureScheme::ECDSA_NISTP384_SHA384,
+            SignatureScheme::ED25519,
+        ])
+        .ok_or_else(|| CertError::ClientAuth("unsupported private key type".to_string()))?;
+    let challenge_signature = signer.sign(KEY_MATCH_CHALLENGE)?;
+    let algorithm: &'static dyn VerificationAlgorithm = match signer.scheme() {
+        SignatureScheme::RSA_PKCS1_SHA256 => {
+            &signature::RSA_PKCS1_1024_8192_SHA256_FOR_LEGACY_USE_ONLY
+        }
+        SignatureScheme::ECDSA_NISTP256_SHA256 => &signature::ECDSA_P256_SHA256_ASN1,
+        SignatureScheme::ECDSA_NISTP384_SHA384 => &signature::ECDSA_P384_SHA384_ASN1,
+        _ => &signature::ED25519,
+    };
+
+    let (_, cert) = X509Certificate::from_der(cert_der)
+        .map_err(|e| CertError::X509Parse(format!("Failed to parse certificate: {e}")))?;
+    let public_key = UnparsedPublicKey::new(algorithm, &cert.public_key().subject_public_key.data);
+    Ok(public_key
+        .verify(KEY_MATCH_CHALLENGE, &challenge_signature)
+        .is_ok())
+}
+
+/// Proxy URL from `HTTPS_PROXY` or `ALL_PROXY` (either case), if one is set
+#[must_use]
+pub fn proxy_from_env() -> Option<String> {
+    ["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+}
+
+/// Host list from `NO_PROXY` (or `no_proxy`) of hosts reached directly despite a proxy
+fn no_proxy_from_env() -> Option<String> {
+    ["NO_PROXY", "no_proxy"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+}
+
+/// Whether `host` is on the comma-separated `no_proxy` list and so bypasses the proxy
+///
+/// Matches the way reqwest reads `NO_PROXY` for downloads: `*` matches every
+/// host, an IP address or CIDR block matches addresses in it, and a domain,
+/// with or without a leading dot, matches itself and its subdomains.
+fn bypasses_proxy(no_proxy: &str, host: &str) -> bool {
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let ip = host.parse::<IpAddr>().ok();
+    no_proxy
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .any(|entry| {
+            if entry == "*" {
+                return true;
+            }
+            if let Some(ip) = ip {
+                return entry.parse::<IpNet>().map_or_else(
+                    |_| entry.parse::<IpAddr>() == Ok(ip),
+                    |net| net.contains(&ip),
+                );
+            }
+            let domain = entry.trim_start_matches('.');
+            host == domain
+                || host
+                    .strip_suffix(domain)
+                    .is_some_and(|subdomain| subdomain.ends_with('.'))
+        })
+}
+
+/// Parse a proxy given as `http://[user:pass@]host[:port]` or a bare `host:port`
+///
+/// # Errors
+///
+/// Returns [`CertError::Proxy`] if the URL is malformed, has no host, or uses
+/// a scheme other than `http`.
+pub fn parse_proxy_url(input: &str) -> Result<Url, CertError> {
+    let with_scheme = if input.contains("://") {
+        input.to_string()
+    } else {
+        format!("http://{input}")
+    };
+    let url = Url::parse(&with_scheme)
+        .map_err(|e| CertError::Proxy(format!("invalid proxy URL '{input}': {e}")))?;
+    if url.scheme() != "http" {
+        return Err(CertError::Proxy(format!(
+            "unsupported proxy scheme '{}' (only http:// proxies can tunnel)",
+            url.scheme()
+        )));
+    }
+    if url.host_str().is_none() {
+        return Err(CertError::Proxy(format!("proxy URL '{input}' has no host")));
+    }
+    Ok(url)
+}
+
+/// HTTP client for downloads, routed through `options.proxy` when one is set
+///
+/// Hosts on the `NO_PROXY` list are still fetched directly.
+pub(crate) fn http_client(options: &FetchOptions) -> Result<reqwest::blocking::Client, CertError> {
+    Ok(http_client_builder(options)?.build()?)
+}
+
+fn http_client_builder(
+    options: &FetchOptions,
+) -> Result<reqwest::blocking::ClientBuilder, CertError> {
+    let mut builder = reqwest::blocking::Client::builder();
+    if let Some(proxy) = &options.proxy {
+        builder = builder
+            .proxy(reqwest::Proxy::all(proxy.as_str())?.no_proxy(reqwest::NoProxy::from_env()));
+    }
+    Ok(builder)
+}
+
+/// Client and URL for the download probe of `url`
+///
+/// With `--servername` the request is addressed to that name, so it is sent as
+/// SNI and Host like the handshake, while the connection still goes to the
+/// addresses of the URL's own host.
+fn probe_request(
+    url: &Url,
+    port: u16,
+    options: &FetchOptions,
+) -> Result<(reqwest::blocking::Client, Url), CertError> {
+    let builder = http_client_builder(options)?;
+    let (Some(servername), Some(host)) = (options.servername.as_deref(), url.host_str()) else {
+        return Ok((builder.build()?, url.clone()));
+    };
+    let connect_host = host
+        .strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+        .unwrap_or(host);
+    let addrs = resolve(connect_host, port)?;
+    let mut probe_url = url.clone();
+    probe_url
+        .set_host(Some(servername))
+        .map_err(|_| CertError::InvalidFormat)?;
+    Ok((
+        builder.resolve_to_addrs(servername, &addrs).build()?,
+        probe_url,
+    ))
+}
+
+/// Open a TCP tunnel to `host:port` through an HTTP proxy with `CONNECT`
+///
+/// The target name is passed to the proxy unresolved. Credentials in the proxy
+/// URL are sent as `Proxy-Authorization: Basic`.
+fn connect_via_proxy(proxy: &Url, host: &str, port: u16) -> Result<TcpStream, CertError> {
+    let proxy_host = proxy.host_str().ok_or(CertError::InvalidFormat)?;
+    let proxy_host = proxy_host
+        .strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+        .unwrap_or(proxy_host);
+    let proxy_port = proxy.port_or_known_default().unwrap_or(HTTP_PROXY_PORT);
+
+    let addrs = resolve(proxy_host, proxy_port)?;
+    debug!(proxy = %proxy, target = host, port, "opening CONNECT tunnel");
+    let mut socket = TcpStream::connect(&addrs[..])?;
+    socket.set_read_timeout(Some(Duration::from_secs(CONNECTION_TIMEOUT_SECS)))?;
+    socket.set_write_timeout(Some(Duration::from_secs(CONNECTION_TIMEOUT_SECS)))?;
+
+    let authority = if host.parse::<Ipv6Addr>().is_ok() {
+        format!("[{host}]:{port}")
+    } else {
+        format!("{host}:{port}")
+    };
+    let authorization = if proxy.username().is_empty() {
+        String::new()
+    } else {
+        let credentials = format!(
+            "{}:{}",
+            proxy.username(),
+            proxy.password().unwrap_or_default()
+        );
+        format!(
+            "Proxy-Authorization: Basic {}\r\n",
+            STANDARD.encode(credentials)
+        )
+    };
+    let request =
+        format!("CONNECT {authority} HTTP/1.1\r\nHost: {authority}\r\n{authorization}\r\n");
+    socket.write_all(request.as_bytes())?;
+
+    let read_reply = |socket: &mut TcpStream| {
+        read_line(socket).map_err(|e| {
+            line_error(e, || {
+                CertError::Proxy("proxy closed the connection".to_string())
+            })
+        })
+    };
+    let status = read_reply(&mut socket)?;
+    let accepted = status
+        .split_whitespace()
+        .nth(1)
+        .is_some_and(|code| code.starts_with('2'));
+    if !status.starts_with("HTTP/") || !accepted {
+        return Err(CertError::Proxy(format!(
+            "CONNECT to {authority} refused: {status}"
+        )));
+    }
+    // Skip the response headers up to the blank line; the tunnel starts after it
+    while !read_reply(&mut socket)?.is_empty() {}
+    Ok(socket)
+}
+
+/// Read a single CRLF/LF-terminated line without buffering past it
+///
+/// Reading byte by byte guarantees no TLS handshake bytes are consumed
+/// once the server switches protocols. A connection closed before any byte
+/// of the line is an [`std::io::ErrorKind::UnexpectedEof`] error.
+fn read_line(stream: &mut impl Read) -> std::io::Result<String> {
+    let mut line = Vec::new();
+    let mut byte = [0u8; 1];
+    let mut terminated = false;
+    loop {
+        if stream.read(&mut byte)? == 0 {
+            break;
+        }
+        if byte[0] == b'\n' {
+            terminated = true;
+            break;
+        }
+        line.push(byte[0]);
+    }
+    // A bare LF is a blank line; only running out of input is a closed connection
+    if line.is_empty() && !terminated {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            "connection closed",
+        ));
+    }
+    Ok(String::from_utf8_lossy(&line).trim_end().to_string())
+}
+
+/// Classify a [`read_line`] failure: a closed connection becomes `closed()`,
+/// anything else (such as a timeout) stays an I/O error
+fn line_error(err: std::io::Error, closed: impl FnOnce() -> CertError) -> CertError {
+    if err.kind() == std::io::ErrorKind::UnexpectedEof {
+        closed()
+    } else {
+        CertError::Io(err)
+    }
+}
+
+/// Read one line of a STARTTLS negotiation
+fn read_negotiation_line(stream: &mut impl Read) -> Result<String, CertError> {
+    read_line(stream).map_err(|e| {
+        line_error(e, || {
+            CertError::StartTls("connection closed during negotiation".to_string())
+        })
+    })
+}
+
+/// Read a (possibly multi-line) reply and check it carries the expected prefix
+///
+/// SMTP and FTP continuation lines use `<code>-`; the final line uses `<code> `.
+fn expect_reply(stream: &mut impl Read, prefix: &str) -> Result<(), CertError> {
+    loop {
+        let line = read_negotiation_line(stream)?;
+        if !line.starts_with(prefix) {
+            return Err(CertError::StartTls(format!(
+                "unexpected server reply: {line}"
+            )));
+        }
+        if line.as_bytes().get(prefix.len()) != Some(&b'-') {
+            return Ok(());
+        }
+    }
+}
 
-        // Check if the URL contains certificate data
-        if content.contains("-----BEGIN CERTIFICATE-----") {
-            return crate::parser::parse_certificate_chain(&data);
+/// Perform the plaintext greeting and STARTTLS upgrade for the given protocol
+pub(crate) fn negotiate_starttls(
+    stream: &mut (impl Read + Write),
+    protocol: StartTls,
+) -> Result<(), CertError> {
+    match protocol {
+        StartTls::Smtp => {
+            expect_reply(stream, "220")?;
+            stream.write_all(b"EHLO cert-tree\r\n")?;
+            expect_reply(stream, "250")?;
+            stream.write_all(b"STARTTLS\r\n")?;
+            expect_reply(stream, "220")?;
+        }
+        StartTls::Imap => {
+            expect_reply(stream, "* OK")?;
+            stream.write_all(b"a001 STARTTLS\r\n")?;
+            // Untagged responses may precede the tagged completion
+            loop {
+                let line = read_negotiation_line(stream)?;
+                if line.starts_with("a001 OK") {
+                    break;
+                }
+                if line.starts_with("a001 ") {
+                    return Err(CertError::StartTls(format!(
+                        "unexpected server reply: {line}"
+                    )));
+                }
+            }
+        }
+        StartTls::Pop3 => {
+            expect_reply(stream, "+OK")?;
+            stream.write_all(b"STLS\r\n")?;
+            expect_reply(stream, "+OK")?;
         }
+        StartTls::Ftp => {
+            expect_reply(stream, "220")?;
+            stream.write_all(b"AUTH TLS\r\n")?;
+            expect_reply(stream, "234")?;
+        }
+    }
+    Ok(())
+}
+
+/// Normalize a downloaded certificate bundle into PEM or DER bytes
+///
+/// Gzip payloads (detected by magic bytes) are decompressed first. Bare base64
+/// without PEM armor is decoded to DER; other content is returned unchanged.
+pub(crate) fn decode_bundle(data: &[u8]) -> Result<Vec<u8>, CertError> {
+    let data = if data.starts_with(&GZIP_MAGIC) {
+        let mut decompressed = Vec::new();
+        GzDecoder::new(data).read_to_end(&mut decompressed)?;
+        decompressed
     } else {
+        data.to_vec()
+    };
+
+    let is_bare_base64 = !data.is_empty()
+        && data.iter().any(|b| !b.is_ascii_whitespace())
+        && data.iter().all(|b| {
+            b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'=') || b.is_ascii_whitespace()
+        });
+    if is_bare_base64 {
+        let compact: Vec<u8> = data
+            .iter()
+            .copied()
+            .filter(|b| !b.is_ascii_whitespace())
+            .collect();
+        if let Ok(der) = STANDARD.decode(compact) {
+            return Ok(der);
+        }
+    }
+
+    Ok(data)
+}
+
+/// Fetch certificates from a URL: a downloadable bundle, or the chain presented in a TLS handshake
+///
+/// Transient failures are retried up to `options.retries` times, waiting
+/// [`RETRY_BASE_DELAY_MS`] before the first retry and twice as long before each next one,
+/// up to [`MAX_RETRY_DELAY_MS`].
+///
+/// # Errors
+///
+/// Returns an error if the URL is invalid, the connection or STARTTLS
+/// negotiation fails, or the TLS handshake yields no parseable certificates.
+/// After exhausting the retries, the last attempt's error is returned.
+pub fn fetch_certificate_chain_from_url(
+    url: &str,
+    options: &FetchOptions,
+) -> Result<Vec<CertificateInfo>, CertError> {
+    with_retries(url, options.retries, || {
+        fetch_certificate_chain_once(url, options)
+    })
+}
+
+/// Parse a `--connect` argument of the form `host:port`, with IPv6 hosts bracketed
+///
+/// # Errors
+///
+/// Returns [`CertError::InvalidFormat`] if the host or port is missing or the port is not a number.
+pub fn parse_connect_target(input: &str) -> Result<(String, u16), CertError> {
+    let (host, port) = input.rsplit_once(':').ok_or(CertError::InvalidFormat)?;
+    let port = port.parse().map_err(|_| CertError::InvalidFormat)?;
+    if host.is_empty() {
+        return Err(CertError::InvalidFormat);
+    }
+    Ok((host.to_string(), port))
+}
+
+/// Fetch the chain a TLS endpoint presents, without first probing it as an HTTP download
+///
+/// Meant for endpoints that are not web servers; `options.port` still overrides
+/// the port given in `target`, and transient failures are retried as for URLs.
+///
+/// # Errors
+///
+/// Returns an error if `target` is not `host:port`, the connection or STARTTLS
+/// negotiation fails, or the TLS handshake yields no parseable certificates.
+pub fn fetch_certificate_chain_from_connect(
+    target: &str,
+    options: &FetchOptions,
+) -> Result<Vec<CertificateInfo>, CertError> {
+    let (host, port) = parse_connect_target(target)?;
+    let port = options.port.unwrap_or(port);
+    with_retries(target, options.retries, || {
+        fetch_certificate_chain_via_tls(&host, port, options, false)
+            .map(TlsChain::into_certificates)
+    })
+}
+
+/// Run `fetch`, retrying transient failures with exponential backoff
+fn with_retries<T>(
+    target: &str,
+    retries: u32,
+    mut fetch: impl FnMut() -> Result<T, CertError>,
+) -> Result<T, CertError> {
+    let mut delay = Duration::from_millis(RETRY_BASE_DELAY_MS);
+    let mut attempt = 0;
+    loop {
+        match fetch() {
+            Err(e) if e.is_transient() && attempt < retries => {
+                attempt += 1;
+                warn!(target, error = %e, attempt, ?delay, "transient failure; retrying");
+                thread::sleep(delay);
+                delay = (delay * 2).min(Duration::from_millis(MAX_RETRY_DELAY_MS));
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Port to connect to for `url`: `--port`, else the URL's own, else the STARTTLS
+/// protocol's, else the default of the URL's scheme
+fn target_port(url: &Url, options: &FetchOptions) -> u16 {
+    options
+        .port
+        .or_else(|| url.port())
+        .or_else(|| options.starttls.map(StartTls::default_port))
+        .or_else(|| url.port_or_known_default())
+        .unwrap_or(HTTPS_PORT)
+}
+
+fn fetch_certificate_chain_once(
+    url: &str,
+    options: &FetchOptions,
+) -> Result<Vec<CertificateInfo>, CertError> {
+    // Parse the URL to extract hostname and port (explicit --port wins over the URL)
+    let mut url_parsed = parse_target_url(url)?;
+    let port = target_port(&url_parsed, options);
+    if let Some(port) = options.port {
+        url_parsed
+            .set_port(Some(port))
+            .map_err(|()| CertError::InvalidFormat)?;
+    }
+    let hostname = url_parsed
+        .host_str()
+        .ok_or(CertError::InvalidFormat)?
+        .to_string();
+
+    // STARTTLS endpoints are not HTTP servers, so skip the direct download probe
+    if options.starttls.is_some() {
+        return fetch_certificate_chain_via_tls(&hostname, port, options, false)
+            .map(TlsChain::into_certificates);
+    }
+
+    // First, try to fetch as direct certificate data (for URLs like cacert.pem)
+    let (client, probe_url) = probe_request(&url_parsed, port, options)?;
+    match client.get(probe_url.as_str()).send() {
+        Ok(response) => {
+            debug!(status = %response.status(), url = %probe_url, "direct download");
+            let data = decode_bundle(&response.bytes()?)?;
+            let content = String::from_utf8_lossy(&data);
+
+            // Check if the URL contains certificate data
+            if content.contains("-----BEGIN CERTIFICATE-----") {
+                return crate::parser::parse_certificate_chain(&data);
+            }
+
+            // A JWK Set (e.g. /.well-known/jwks.json) with x5c certificate chains
+            if crate::parser::is_json_object(&data) {
+                match crate::parser::parse_certificate_chain(&data) {
+                    Ok(certificates) => return Ok(certificates),
+                    Err(e) => debug!(error = %e, "downloaded JSON has no x5c certificates"),
+                }
+            }
+
+            // Bare base64 decoded to DER; anything else (e.g. an HTML page) falls through
+            if data.first() == Some(&DER_SEQUENCE_TAG) {
+                match crate::parser::parse_certificate_chain(&data) {
+                    Ok(certificates) => return Ok(certificates),
+                    Err(e) => debug!(error = %e, "downloaded DER is not a certificate"),
+                }
+            }
+            debug!("response is not a certificate bundle; inspecting the TLS handshake");
+        }
         // If direct fetch fails, try to get certificate chain from HTTPS connection
+        Err(e) => debug!(error = %e, "direct download failed; inspecting the TLS handshake"),
     }
 
     // For HTTPS URLs, establish a TLS connection and capture the certificate chain
-    fetch_certificate_chain_via_tls(hostname)
+    fetch_certificate_chain_via_tls(&hostname, port, options, true).map(TlsChain::into_certificates)
 }
 
-fn fetch_certificate_chain_via_tls(hostname: &str) -> Result<Vec<CertificateInfo>, CertError> {
-    use rustls::client::ClientConnection;
-    use rustls::{ClientConfig, RootCertStore};
-    use webpki_roots::TLS_SERVER_ROOTS;
+/// Download missing issuer certificates via their CA Issuers (AIA) URLs
+///
+/// Servers often omit the root, and some send only their leaf, so for every
+/// certificate whose issuer is not in the set, its first untried CA Issuers URL
+/// is fetched (DER, PEM or PKCS#7) and the result appended, until each chain
+/// reaches a self-issued root or runs out of URLs. A URL is tried once and a
+/// certificate already held is not added again, so issuers that point back down
+/// the chain end the walk; it also stops after `MAX_AIA_FETCHES` downloads. One
+/// HTTP client, using `options.proxy` if set, serves all requests; failures are
+/// logged and skipped.
+pub fn fetch_missing_issuers(certificates: &mut Vec<CertificateInfo>, options: &FetchOptions) {
+    let client = match http_client(options) {
+        Ok(client) => client,
+        Err(e) => {
+            warn!(error = %e, "could not set up the HTTP client for issuer downloads");
+            return;
+        }
+    };
+    fetch_missing_issuers_with(certificates, |url| download_certificates(&client, url));
+}
+
+fn fetch_missing_issuers_with(
+    certificates: &mut Vec<CertificateInfo>,
+    mut download: impl FnMut(&str) -> Result<Vec<CertificateInfo>, CertError>,
+) {
+    let mut seen: HashSet<Vec<u8>> = certificates.iter().map(|cert| cert.der.clone()).collect();
+    let mut attempted: HashSet<String> = HashSet::new();
+
+    loop {
+        let next_url = certificates
+            .iter()
+            .filter(|cert| {
+                cert.subject != cert.issuer
+                    && !certificates
+                        .iter()
+                        .any(|issuer| issuer.subject == cert.issuer)
+            })
+            .flat_map(|cert| &cert.ca_issuer_urls)
+            .find(|url| !attempted.contains(*url))
+            .cloned();
+        let Some(url) = next_url else {
+            break;
+        };
+        if attempted.len() == MAX_AIA_FETCHES {
+            warn!("stopped fetching issuers after {MAX_AIA_FETCHES} downloads; the chain may be incomplete");
+            break;
+        }
+        attempted.insert(url.clone());
+
+        match download(&url) {
+            Ok(found) => {
+                debug!(url, count = found.len(), "fetched issuer via AIA");
+                for cert in found {
+                    if seen.insert(cert.der.clone()) {
+                        certificates.push(cert);
+                    }
+                }
+            }
+            Err(e) => warn!(url, error = %e, "could not fetch issuer certificate"),
+        }
+    }
+}
+
+fn download_certificates(
+    client: &reqwest::blocking::Client,
+    url: &str,
+) -> Result<Vec<CertificateInfo>, CertError> {
+    let response = client.get(url).send()?.error_for_status()?;
+    let data = decode_bundle(&response.bytes()?)?;
+    crate::parser::parse_certificate_chain(&data)
+}
+
+/// Resolve a host before connecting so lookup failures are told apart from refused connections
+///
+/// The standard library reports failed lookups with an uncategorized
+/// `io::ErrorKind`, so resolution is done as its own step instead.
+fn resolve(host: &str, port: u16) -> Result<Vec<SocketAddr>, CertError> {
+    let addrs: Vec<SocketAddr> = (host, port)
+        .to_socket_addrs()
+        .map_err(|e| CertError::DnsResolution(format!("could not resolve host '{host}': {e}")))?
+        .collect();
+    if addrs.is_empty() {
+        return Err(CertError::DnsResolution(format!(
+            "host '{host}' has no addresses"
+        )));
+    }
+    Ok(addrs)
+}
+
+/// A chain read from a TLS handshake, with the parameters that were negotiated
+struct TlsChain {
+    certificates: Vec<CertificateInfo>,
+    connection: TlsConnectionInfo,
+}
+
+impl TlsChain {
+    /// The certificates, with the connection parameters recorded on the leaf
+    fn into_certificates(mut self) -> Vec<CertificateInfo> {
+        if let Some(leaf) = self.certificates.first_mut() {
+            leaf.tls_connection = Some(self.connection);
+        }
+        self.certificates
+    }
+}
+
+/// Readable name of a negotiated protocol version, e.g. "TLS 1.3"
+fn protocol_name(version: rustls::ProtocolVersion) -> String {
+    match version {
+        rustls::ProtocolVersion::TLSv1_2 => "TLS 1.2".to_string(),
+        rustls::ProtocolVersion::TLSv1_3 => "TLS 1.3".to_string(),
+        other => format!("{other:?}"),
+    }
+}
+
+/// Connect to `hostname:port` and read the chain from the TLS handshake
+///
+/// With `http_request` a minimal HTTP request follows the handshake; `--connect`
+/// and STARTTLS targets are not HTTP servers and get only the handshake.
+fn fetch_certificate_chain_via_tls(
+    hostname: &str,
+    port: u16,
+    options: &FetchOptions,
+    http_request: bool,
+) -> Result<TlsChain, CertError> {
+    // URLs keep IPv6 literals bracketed ("[::1]"); sockets and ServerName need them bare
+    let connect_host = hostname
+        .strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+        .unwrap_or(hostname);
+
+    // Create a TCP connection, tunnelled through the proxy unless NO_PROXY exempts the host
+    let proxy = options.proxy.as_ref().filter(|_| {
+        let bypass =
+            no_proxy_from_env().is_some_and(|no_proxy| bypasses_proxy(&no_proxy, connect_host));
+        if bypass {
+            debug!(
+                host = connect_host,
+                "host is in NO_PROXY; connecting directly"
+            );
+        }
+        !bypass
+    });
+    let mut socket = if let Some(proxy) = proxy {
+        connect_via_proxy(proxy, connect_host, port)?
+    } else {
+        let addrs = resolve(connect_host, port)?;
+        debug!(host = connect_host, port, ?addrs, "connecting");
+        TcpStream::connect(&addrs[..]).inspect_err(|e| {
+            debug!(host = connect_host, port, error = %e, "TCP connect failed");
+        })?
+    };
+    socket.set_read_timeout(Some(Duration::from_secs(CONNECTION_TIMEOUT_SECS)))?;
+    socket.set_write_timeout(Some(Duration::from_secs(CONNECTION_TIMEOUT_SECS)))?;
+
+    if let Some(protocol) = options.starttls {
+        debug!(?protocol, "negotiating STARTTLS");
+        negotiate_starttls(&mut socket, protocol).inspect_err(|e| {
+            debug!(?protocol, error = %e, "STARTTLS negotiation failed");
+        })?;
+    }
+
+    // SNI and Host follow --servername when given; IP literals parse to
+    // ServerName::IpAddress, for which rustls sends no SNI extension
+    let sni_host = options.servername.as_deref().unwrap_or(connect_host);
+    tls_handshake_chain(&mut socket, sni_host, options, http_request)
+}
+
+/// Fetch the chain a TLS server presents on a Unix domain socket
+///
+/// A socket path has no host name, so `options.servername` supplies the SNI,
+/// the HTTP Host header and the name the leaf is checked against.
+///
+/// # Errors
+///
+/// Returns an error if no server name is set, the connection or STARTTLS
+/// negotiation fails, or the TLS handshake yields no parseable certificates.
+#[cfg(unix)]
+pub fn fetch_certificate_chain_from_unix_socket(
+    path: &str,
+    options: &FetchOptions,
+) -> Result<Vec<CertificateInfo>, CertError> {
+    let servername = options.servername.as_deref().ok_or_else(|| {
+        CertError::Tls("a Unix socket needs --servername for the TLS handshake".to_string())
+    })?;
+    with_retries(path, options.retries, || {
+        debug!(path, "connecting to Unix socket");
+        let mut socket = UnixStream::connect(path)?;
+        socket.set_read_timeout(Some(Duration::from_secs(CONNECTION_TIMEOUT_SECS)))?;
+        socket.set_write_timeout(Some(Duration::from_secs(CONNECTION_TIMEOUT_SECS)))?;
+        if let Some(protocol) = options.starttls {
+            negotiate_starttls(&mut socket, protocol)?;
+        }
+        tls_handshake_chain(&mut socket, servername, options, true).map(TlsChain::into_certificates)
+    })
+}
+
+/// Unix domain sockets are not available on this platform
+///
+/// # Errors
+///
+/// Always returns [`CertError::Tls`].
+#[cfg(not(unix))]
+pub fn fetch_certificate_chain_from_unix_socket(
+    path: &str,
+    _options: &FetchOptions,
+) -> Result<Vec<CertificateInfo>, CertError> {
+    Err(CertError::Tls(format!(
+        "{path}: Unix sockets are not supported on this platform"
+    )))
+}
+
+/// The trust anchors a TLS handshake accepts: the webpki roots, then the `--ca-bundle` roots
+///
+/// Chains are validated against these so that their trust status agrees with
+/// the handshake's verdict. Bundle certificates that do not parse are skipped.
+#[must_use]
+pub fn trust_anchors(options: &FetchOptions) -> Vec<TrustAnchor> {
+    webpki_roots::TLS_SERVER_ROOTS
+        .iter()
+        .map(|ta| TrustAnchor {
+            subject_der: der_sequence(ta.subject),
+            spki_der: der_sequence(ta.spki),
+        })
+        .chain(
+            options
+                .ca_bundle_roots
+                .iter()
+                .filter_map(|der| TrustAnchor::from_certificate(der)),
+        )
+        .collect()
+}
 
-    // Set up TLS configuration
-    let mut root_store = RootCertStore::empty();
-    root_store.add_trust_anchors(TLS_SERVER_ROOTS.iter().map(|ta| {
+/// Wrap `contents` in a DER SEQUENCE header; webpki stores anchor fields without one
+fn der_sequence(contents: &[u8]) -> Vec<u8> {
+    let length = contents.len().to_be_bytes();
+    let significant = length.iter().skip_while(|&&byte| byte == 0).count();
+    let mut der = vec![DER_SEQUENCE_TAG];
+    match u8::try_from(contents.len()) {
+        Ok(short) if short < 0x80 => der.push(short),
+        _ => {
+            der.push(0x80 | u8::try_from(significant).unwrap_or(0));
+            der.extend_from_slice(&length[length.len() - significant..]);
+        }
+    }
+    der.extend_from_slice(contents);
+    der
+}
+
+/// The handshake's trust store, holding the same roots as [`trust_anchors`]
+///
+/// # Errors
+///
+/// Returns [`CertError::Tls`] if a bundle certificate cannot be used as a trust anchor.
+fn trust_store(options: &FetchOptions) -> Result<rustls::RootCertStore, CertError> {
+    let mut root_store = rustls::RootCertStore::empty();
+    root_store.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|ta| {
         rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(
             ta.subject,
             ta.spki,
             ta.name_constraints,
         )
     }));
+    for der in &options.ca_bundle_roots {
+        root_store
+            .add(&rustls::Certificate(der.clone()))
+            .map_err(|e| CertError::Tls(e.to_string()))?;
+    }
+    Ok(root_store)
+}
 
-    let config = ClientConfig::builder()
-        .with_safe_defaults()
-        .with_root_certificates(root_store)
-        .with_no_client_auth();
+/// Server certificate verifier that records the standard verdict and accepts every chain
+///
+/// The handshake then always completes, and the chain is returned with the
+/// reason it would have been rejected (untrusted issuer, expiry, wrong name).
+/// Handshake signatures are still checked.
+struct RecordingVerifier {
+    inner: rustls::client::WebPkiVerifier,
+    verdict: Mutex<Option<String>>,
+}
 
-    // Create a TCP connection
-    let mut socket = TcpStream::connect((hostname, HTTPS_PORT))?;
-    socket.set_read_timeout(Some(Duration::from_secs(CONNECTION_TIMEOUT_SECS)))?;
-    socket.set_write_timeout(Some(Duration::from_secs(CONNECTION_TIMEOUT_SECS)))?;
+impl RecordingVerifier {
+    fn new(roots: rustls::RootCertStore) -> Self {
+        Self {
+            inner: rustls::client::WebPkiVerifier::new(roots, None),
+            verdict: Mutex::new(None),
+        }
+    }
+
+    /// Why the standard verifier rejected the chain, or `None` if it accepted it
+    fn verification_error(&self) -> Option<String> {
+        self.verdict.lock().ok().and_then(|verdict| verdict.clone())
+    }
+}
+
+impl rustls::client::ServerCertVerifier for RecordingVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &rustls::Certificate,
+        intermediates: &[rustls::Certificate],
+        server_name: &rustls::ServerName,
+        scts: &mut dyn Iterator<Item = &[u8]>,
+        ocsp_response: &[u8],
+        now: std::time::SystemTime,
+    ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
+        let result = self.inner.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            scts,
+            ocsp_response,
+            now,
+        );
+        if let (Err(e), Ok(mut verdict)) = (result, self.verdict.lock()) {
+            *verdict = Some(e.to_string());
+        }
+        Ok(rustls::client::ServerCertVerified::assertion())
+    }
+}
+
+/// Run the TLS handshake over a connected `socket` and read the chain the server presents
+///
+/// With `http_request` and without STARTTLS a minimal HTTP request follows the
+/// handshake, with `sni_host` as its Host header.
+fn tls_handshake_chain(
+    socket: &mut (impl Read + Write),
+    sni_host: &str,
+    options: &FetchOptions,
+    http_request: bool,
+) -> Result<TlsChain, CertError> {
+    use rustls::client::ClientConnection;
+    use rustls::ClientConfig;
+
+    // Set up TLS configuration; the verifier records its verdict instead of
+    // aborting, so untrusted or mismatched chains can still be inspected
+    let verifier = Arc::new(RecordingVerifier::new(trust_store(options)?));
+    let builder = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_custom_certificate_verifier(verifier.clone());
+    let config = match &options.client_identity {
+        Some(identity) => builder.with_client_auth_cert(
+            identity
+                .cert_chain
+                .iter()
+                .cloned()
+                .map(rustls::Certificate)
+                .collect(),
+            rustls::PrivateKey(identity.key.clone()),
+        )?,
+        None => builder.with_no_client_auth(),
+    };
 
     let server_name =
-        rustls::ServerName::try_from(hostname).map_err(|_| CertError::InvalidFormat)?;
+        rustls::ServerName::try_from(sni_host).map_err(|_| CertError::InvalidFormat)?;
+
+    // The Host header needs IPv6 literals bracketed again (RFC 7230 section 5.4)
+    let host_header = if sni_host.parse::<Ipv6Addr>().is_ok() {
+        format!("[{sni_host}]")
+    } else {
+        sni_host.to_string()
+    };
 
+    let started = Instant::now();
     let mut conn = ClientConnection::new(Arc::new(config), server_name)?;
+    // Keep reading until the handshake is done, however many records the chain spans;
+    // EOF mid-handshake is an error and the socket's read timeout bounds a stalled server
+    while conn.is_handshaking() {
+        conn.complete_io(socket)?;
+    }
+    let handshake = started.elapsed();
 
-    // Perform TLS handshake
-    let mut tls_stream = rustls::Stream::new(&mut conn, &mut socket);
+    // The upgraded STARTTLS protocol and --connect services are not HTTP; only the
+    // handshake is needed
+    if http_request && options.starttls.is_none() {
+        let mut tls_stream = rustls::Stream::new(&mut conn, socket);
 
-    // Send a minimal HTTP request to trigger the handshake
-    let request = format!("GET / HTTP/1.0\r\nHost: {hostname}\r\n\r\n");
-    tls_stream.write_all(request.as_bytes())?;
+        // Send a minimal HTTP request so the server sees a complete exchange
+        let request = format!("GET / HTTP/1.0\r\nHost: {host_header}\r\n\r\n");
+        tls_stream.write_all(request.as_bytes())?;
 
-    // Read response to complete handshake
-    let mut buffer = [0u8; BUFFER_SIZE];
-    let _ = tls_stream.read(&mut buffer);
+        // The handshake, and so the whole chain, is complete by now; the response is not needed
+        let mut buffer = [0u8; BUFFER_SIZE];
+        if let Err(e) = tls_stream.read(&mut buffer) {
+            debug!(error = %e, "reading HTTP response failed");
+        }
+    }
+    let peer_certificates = conn.peer_certificates().unwrap_or_default();
+    let connection = TlsConnectionInfo {
+        protocol: conn
+            .protocol_version()
+            .map_or_else(|| "unknown".to_string(), protocol_name),
+        cipher_suite: conn.negotiated_cipher_suite().map_or_else(
+            || "unknown".to_string(),
+            |suite| {
+                let suite = suite.suite();
+                suite
+                    .as_str()
+                    .map_or_else(|| format!("{suite:?}"), str::to_string)
+            },
+        ),
+        handshake_ms: handshake.as_secs_f64() * 1000.0,
+        certificate_count: peer_certificates.len(),
+        certificate_bytes: peer_certificates.iter().map(|cert| cert.0.len()).sum(),
+        verification_error: verifier.verification_error(),
+    };
+    debug!(
+        version = %connection.protocol,
+        cipher_suite = %connection.cipher_suite,
+        handshake_ms = connection.handshake_ms,
+        certificates = connection.certificate_count,
+        bytes = connection.certificate_bytes,
+        sni = sni_host,
+        verification_error = connection.verification_error.as_deref(),
+        "TLS handshake finished"
+    );
 
     // Extract certificate chain from the connection
-    if let Some(certs) = conn.peer_certificates() {
+    if peer_certificates.is_empty() {
+        Err(CertError::X509Parse(
+            "No certificates found in TLS handshake".to_string(),
+        ))
+    } else {
         let mut certificates = Vec::new();
-        for cert_der in certs {
+        for cert_der in peer_certificates {
             let (_, cert) = X509Certificate::from_der(cert_der.as_ref())
                 .map_err(|e| CertError::X509Parse(format!("Failed to parse certificate: {e}")))?;
 
-            let cert_info = extract_cert_info(&cert);
+            let cert_info = extract_cert_info(&cert, cert_der.as_ref());
             certificates.push(cert_info);
         }
-        Ok(certificates)
-    } else {
-        Err(CertError::X509Parse(
-            "No certificates found in TLS handshake".to_string(),
-        ))
+
+        // The first certificate presented is the server's leaf
+        if let Some(leaf) = certificates.first_mut() {
+            leaf.hostname_check = Some(verify_hostname(leaf, sni_host));
+        }
+        Ok(TlsChain {
+            certificates,
+            connection,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustls::{ServerConfig, ServerConnection};
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    /// Accept and immediately drop connections, counting how many arrived
+    fn counting_listener() -> (u16, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let count = Arc::new(AtomicUsize::new(0));
+
+        let counter = Arc::clone(&count);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                drop(stream);
+                counter.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        (port, count)
+    }
+
+    #[test]
+    fn test_parse_target_url_bare_host_port() {
+        let url = parse_target_url("mail.example.com:465").unwrap();
+        assert_eq!(url.host_str(), Some("mail.example.com"));
+        assert_eq!(url.port(), Some(465));
+
+        let url = parse_target_url("https://ldap.example.com:636/").unwrap();
+        assert_eq!(url.port(), Some(636));
+    }
+
+    #[test]
+    fn test_fetch_connects_to_explicit_port() {
+        let (port, count) = counting_listener();
+
+        let result = fetch_certificate_chain_from_url(
+            &format!("127.0.0.1:{port}"),
+            &FetchOptions::default(),
+        );
+
+        assert!(result.is_err());
+        assert!(count.load(Ordering::SeqCst) > 0);
+    }
+
+    #[test]
+    fn test_fetch_port_override() {
+        let (port, count) = counting_listener();
+
+        let options = FetchOptions {
+            port: Some(port),
+            ..FetchOptions::default()
+        };
+        let result = fetch_certificate_chain_from_url("https://127.0.0.1", &options);
+
+        assert!(result.is_err());
+        assert!(count.load(Ordering::SeqCst) > 0);
+    }
+
+    #[test]
+    fn test_starttls_defaults_to_protocol_port() {
+        let bare = parse_target_url("mail.example.com").unwrap();
+        let smtp = FetchOptions {
+            starttls: Some(StartTls::Smtp),
+            ..FetchOptions::default()
+        };
+        assert_eq!(target_port(&bare, &smtp), 25);
+        assert_eq!(target_port(&bare, &FetchOptions::default()), HTTPS_PORT);
+
+        let explicit = parse_target_url("mail.example.com:587").unwrap();
+        assert_eq!(target_port(&explicit, &smtp), 587);
+
+        let overridden = FetchOptions {
+            port: Some(465),
+            ..smtp
+        };
+        assert_eq!(target_port(&bare, &overridden), 465);
+    }
+
+    #[test]
+    fn test_target_port_follows_url_scheme() {
+        let options = FetchOptions::default();
+        let http = parse_target_url("http://crl.example.com/ca.crt").unwrap();
+        assert_eq!(target_port(&http, &options), 80);
+        let https = parse_target_url("https://www.example.com/").unwrap();
+        assert_eq!(target_port(&https, &options), HTTPS_PORT);
+    }
+
+    /// Run a scripted plaintext server: send each reply after reading one command line
+    ///
+    /// The first reply is the greeting sent before any command. Returns the
+    /// client connection and the commands the server received.
+    fn scripted_server(replies: &'static [&'static str]) -> (TcpStream, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let commands = Arc::new(Mutex::new(Vec::new()));
+
+        let received = Arc::clone(&commands);
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut writer = stream;
+
+            writer.write_all(replies[0].as_bytes()).unwrap();
+            for reply in &replies[1..] {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                received.lock().unwrap().push(line.trim_end().to_string());
+                writer.write_all(reply.as_bytes()).unwrap();
+            }
+        });
+
+        (TcpStream::connect(addr).unwrap(), commands)
+    }
+
+    #[test]
+    fn test_starttls_smtp_command_sequence() {
+        let (mut stream, commands) = scripted_server(&[
+            "220 mail.example.com ESMTP ready\r\n",
+            "250-mail.example.com\r\n250-PIPELINING\r\n250 STARTTLS\r\n",
+            "220 2.0.0 Ready to start TLS\r\n",
+        ]);
+
+        negotiate_starttls(&mut stream, StartTls::Smtp).unwrap();
+
+        assert_eq!(
+            *commands.lock().unwrap(),
+            vec!["EHLO cert-tree", "STARTTLS"]
+        );
+    }
+
+    #[test]
+    fn test_starttls_smtp_rejected() {
+        let (mut stream, _) = scripted_server(&[
+            "220 mail.example.com ESMTP ready\r\n",
+            "250 mail.example.com\r\n",
+            "454 4.7.0 TLS not available\r\n",
+        ]);
+
+        let result = negotiate_starttls(&mut stream, StartTls::Smtp);
+
+        assert!(matches!(result, Err(CertError::StartTls(_))));
+    }
+
+    const LEAF_PEM: &[u8] = include_bytes!("../test/leaf.pem");
+    // intermediate.pem without the rfc822Name constraint, which webpki rejects
+    const INTERMEDIATE_PEM: &[u8] = include_bytes!("../test/tls_intermediate.pem");
+    const ROOT_PEM: &[u8] = include_bytes!("../test/root.pem");
+    const LEAF_KEY: &[u8] = include_bytes!("../test/leaf.key");
+    const CHAIN_PEM: &[u8] = include_bytes!("../test/chain.pem");
+
+    fn pem_der(data: &[u8]) -> Vec<u8> {
+        pem::parse(data).unwrap().into_contents()
+    }
+
+    /// What a test TLS server observed from the client
+    #[derive(Debug)]
+    struct Observed {
+        sni: Option<String>,
+        host: Option<String>,
+    }
+
+    /// Serve the test leaf chain over TLS once on IPv4 loopback
+    fn tls_server() -> (u16, thread::JoinHandle<Observed>) {
+        tls_server_on(TcpListener::bind("127.0.0.1:0").unwrap())
+    }
+
+    /// Serve the test leaf chain over TLS once, recording SNI and the Host header
+    ///
+    /// Join the returned handle after the client finishes to read the observations.
+    fn tls_server_on(listener: TcpListener) -> (u16, thread::JoinHandle<Observed>) {
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || serve_chain(listener.accept().unwrap().0));
+
+        (port, server)
+    }
+
+    /// Present the test leaf chain over TLS on an accepted connection and answer one request
+    fn serve_chain(socket: impl Read + Write) -> Observed {
+        serve_certificates(socket, vec![pem_der(LEAF_PEM), pem_der(INTERMEDIATE_PEM)])
+    }
+
+    /// Present `chain`, which must start with the test leaf, and answer one request
+    fn serve_certificates(mut socket: impl Read + Write, chain: Vec<Vec<u8>>) -> Observed {
+        let config = ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(
+                chain.into_iter().map(rustls::Certificate).collect(),
+                rustls::PrivateKey(pem_der(LEAF_KEY)),
+            )
+            .unwrap();
+
+        let mut conn = ServerConnection::new(Arc::new(config)).unwrap();
+        let mut tls = rustls::Stream::new(&mut conn, &mut socket);
+
+        let mut request = Vec::new();
+        let mut buffer = [0u8; BUFFER_SIZE];
+        while !request.ends_with(b"\r\n\r\n") {
+            match tls.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => request.extend_from_slice(&buffer[..n]),
+            }
+        }
+        let _ = tls.write_all(b"HTTP/1.0 200 OK\r\n\r\n");
+        tls.conn.send_close_notify();
+        let _ = tls.flush();
+
+        Observed {
+            sni: tls.conn.server_name().map(str::to_string),
+            host: String::from_utf8_lossy(&request)
+                .lines()
+                .find_map(|line| line.strip_prefix("Host: ").map(str::to_string)),
+        }
+    }
+
+    #[test]
+    fn test_servername_overrides_sni_and_host() {
+        let (port, server) = tls_server();
+        let options = FetchOptions {
+            servername: Some("www.example.com".to_string()),
+            ca_bundle_roots: vec![pem_der(ROOT_PEM)],
+            ..FetchOptions::default()
+        };
+
+        let certs = fetch_certificate_chain_via_tls("127.0.0.1", port, &options, true)
+            .unwrap()
+            .into_certificates();
+        assert_eq!(certs.len(), 2);
+        let check = certs[0].hostname_check.as_ref().unwrap();
+        assert_eq!(check.hostname, "www.example.com");
+        assert!(certs[1].hostname_check.is_none());
+        let connection = certs[0].tls_connection.as_ref().unwrap();
+        assert_eq!(connection.verification_error, None);
+
+        let observed = server.join().unwrap();
+        assert_eq!(observed.sni.as_deref(), Some("www.example.com"));
+        assert_eq!(observed.host.as_deref(), Some("www.example.com"));
+    }
+
+    #[test]
+    fn test_servername_outside_sans_reports_mismatch() {
+        let (port, server) = tls_server();
+        let options = FetchOptions {
+            servername: Some("mismatch.example.net".to_string()),
+            ca_bundle_roots: vec![pem_der(ROOT_PEM)],
+            ..FetchOptions::default()
+        };
+
+        let certs = fetch_certificate_chain_via_tls("127.0.0.1", port, &options, true)
+            .unwrap()
+            .into_certificates();
+        assert_eq!(certs.len(), 2);
+        let check = certs[0].hostname_check.as_ref().unwrap();
+        assert_eq!(check.hostname, "mismatch.example.net");
+        assert!(matches!(
+            check.status,
+            crate::models::HostnameMatch::Mismatch
+        ));
+        let connection = certs[0].tls_connection.as_ref().unwrap();
+        assert!(connection.verification_error.is_some());
+
+        let observed = server.join().unwrap();
+        assert_eq!(observed.sni.as_deref(), Some("mismatch.example.net"));
+    }
+
+    #[test]
+    fn test_untrusted_chain_is_returned_with_verification_error() {
+        let (port, server) = tls_server();
+        let options = FetchOptions {
+            servername: Some("www.example.com".to_string()),
+            ..FetchOptions::default()
+        };
+
+        let certs = fetch_certificate_chain_via_tls("127.0.0.1", port, &options, true)
+            .unwrap()
+            .into_certificates();
+        assert_eq!(certs.len(), 2);
+        let error = certs[0]
+            .tls_connection
+            .as_ref()
+            .unwrap()
+            .verification_error
+            .as_deref();
+        assert!(error.unwrap().contains("UnknownIssuer"), "{error:?}");
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_chain_spanning_several_records_is_read_in_full() {
+        // Well past one 16 KiB TLS record and many times BUFFER_SIZE
+        let mut chain = vec![pem_der(LEAF_PEM)];
+        chain.extend(std::iter::repeat_n(pem_der(INTERMEDIATE_PEM), 40));
+        let size: usize = chain.iter().map(Vec::len).sum();
+        assert!(size > 2 * 16 * 1024);
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || serve_certificates(listener.accept().unwrap().0, chain));
+        let options = FetchOptions {
+            servername: Some("www.example.com".to_string()),
+            ca_bundle_roots: vec![pem_der(ROOT_PEM)],
+            ..FetchOptions::default()
+        };
+
+        let fetched = fetch_certificate_chain_via_tls("127.0.0.1", port, &options, true).unwrap();
+        assert_eq!(fetched.certificates.len(), 41);
+        assert_eq!(fetched.connection.certificate_bytes, size);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_connect_sends_no_http_request() {
+        let (port, server) = tls_server();
+        let options = FetchOptions {
+            servername: Some("www.example.com".to_string()),
+            ca_bundle_roots: vec![pem_der(ROOT_PEM)],
+            ..FetchOptions::default()
+        };
+
+        let certs =
+            fetch_certificate_chain_from_connect(&format!("127.0.0.1:{port}"), &options).unwrap();
+        assert_eq!(certs.len(), 2);
+
+        let observed = server.join().unwrap();
+        assert_eq!(observed.sni.as_deref(), Some("www.example.com"));
+        assert_eq!(observed.host, None);
+    }
+
+    #[test]
+    fn test_ip_literal_sends_no_sni() {
+        let (port, server) = tls_server();
+        let options = FetchOptions {
+            ca_bundle_roots: vec![pem_der(ROOT_PEM)],
+            ..FetchOptions::default()
+        };
+
+        fetch_certificate_chain_via_tls("127.0.0.1", port, &options, true).unwrap();
+
+        let observed = server.join().unwrap();
+        assert_eq!(observed.sni, None);
+        assert_eq!(observed.host.as_deref(), Some("127.0.0.1"));
+    }
+
+    #[test]
+    fn test_ipv6_literal_connects_without_sni() {
+        // Hosts without an IPv6 loopback, such as some containers, cannot run this test
+        let Ok(listener) = TcpListener::bind("[::1]:0") else {
+            eprintln!("skipping: cannot bind [::1]");
+            return;
+        };
+        let (port, server) = tls_server_on(listener);
+        let options = FetchOptions {
+            ca_bundle_roots: vec![pem_der(ROOT_PEM)],
+            ..FetchOptions::default()
+        };
+
+        // The host as the url crate reports it for https://[::1]:port
+        let url = parse_target_url(&format!("[::1]:{port}")).unwrap();
+        assert_eq!(url.host_str(), Some("[::1]"));
+
+        let certs = fetch_certificate_chain_via_tls("[::1]", port, &options, true)
+            .unwrap()
+            .certificates;
+        assert_eq!(certs.len(), 2);
+        assert!(matches!(
+            certs[0].hostname_check.as_ref().unwrap().status,
+            crate::models::HostnameMatch::Match
+        ));
+
+        let observed = server.join().unwrap();
+        assert_eq!(observed.sni, None);
+        assert_eq!(observed.host.as_deref(), Some("[::1]"));
+    }
+
+    /// Serve a single plain HTTP response with the given body; returns its URL
+    fn http_server(body: Vec<u8>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buffer = [0u8; BUFFER_SIZE];
+            let _ = stream.read(&mut buffer);
+            let header = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            stream.write_all(header.as_bytes()).unwrap();
+            stream.write_all(&body).unwrap();
+        });
+
+        format!("http://{addr}/bundle")
+    }
+
+    /// Drop the first `dropped` connections, then serve the body over plain HTTP
+    ///
+    /// Returns the URL and the number of connections accepted so far.
+    fn flaky_http_server(body: Vec<u8>, dropped: usize) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let count = Arc::new(AtomicUsize::new(0));
+
+        let counter = Arc::clone(&count);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                if counter.fetch_add(1, Ordering::SeqCst) < dropped {
+                    drop(stream);
+                    continue;
+                }
+                let mut buffer = [0u8; BUFFER_SIZE];
+                let _ = stream.read(&mut buffer);
+                let header = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                stream.write_all(header.as_bytes()).unwrap();
+                stream.write_all(&body).unwrap();
+                break;
+            }
+        });
+
+        (format!("http://{addr}/bundle"), count)
+    }
+
+    #[test]
+    fn test_retries_transient_failures_until_success() {
+        // Each failed attempt costs two connections: the download probe and the handshake
+        let (url, count) = flaky_http_server(CHAIN_PEM.to_vec(), 4);
+        let options = FetchOptions {
+            retries: 2,
+            ..FetchOptions::default()
+        };
+
+        let certs = fetch_certificate_chain_from_url(&url, &options).unwrap();
+
+        assert_eq!(certs.len(), 3);
+        assert_eq!(count.load(Ordering::SeqCst), 5);
+    }
+
+    #[test]
+    fn test_gives_up_after_retries_with_last_error() {
+        let (url, count) = flaky_http_server(CHAIN_PEM.to_vec(), 4);
+        let options = FetchOptions {
+            retries: 1,
+            ..FetchOptions::default()
+        };
+
+        let result = fetch_certificate_chain_from_url(&url, &options);
+
+        assert!(result.is_err_and(|e| e.is_transient()));
+        assert_eq!(count.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn test_refused_connection_is_permanent_on_both_paths() {
+        let port = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().port()
+        };
+
+        let download = http_client(&FetchOptions::default())
+            .unwrap()
+            .get(format!("http://127.0.0.1:{port}/"))
+            .send()
+            .map_err(CertError::from)
+            .unwrap_err();
+        assert!(matches!(download, CertError::Http(_)));
+        assert!(!download.is_transient());
+
+        let handshake =
+            fetch_certificate_chain_via_tls("127.0.0.1", port, &FetchOptions::default(), true)
+                .err()
+                .unwrap();
+        assert!(matches!(handshake, CertError::Io(_)));
+        assert!(!handshake.is_transient());
+    }
+
+    /// Record the first bytes each connection sends, then drop it
+    fn recording_listener() -> (u16, Arc<Mutex<Vec<Vec<u8>>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let received = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = Arc::clone(&received);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut buffer = [0u8; BUFFER_SIZE];
+                let n = stream.read(&mut buffer).unwrap_or(0);
+                recorded.lock().unwrap().push(buffer[..n].to_vec());
+            }
+        });
+
+        (port, received)
+    }
+
+    #[test]
+    fn test_parse_connect_target() {
+        assert_eq!(
+            parse_connect_target("mail.example.com:465").unwrap(),
+            ("mail.example.com".to_string(), 465)
+        );
+        assert_eq!(
+            parse_connect_target("[::1]:8443").unwrap(),
+            ("[::1]".to_string(), 8443)
+        );
+        assert!(parse_connect_target("mail.example.com").is_err());
+        assert!(parse_connect_target(":443").is_err());
+        assert!(parse_connect_target("host:https").is_err());
+    }
+
+    #[test]
+    fn test_connect_skips_http_probe() {
+        // A URL is probed with a plain HTTP GET before the handshake
+        let (port, received) = recording_listener();
+        let _ = fetch_certificate_chain_from_url(
+            &format!("http://127.0.0.1:{port}"),
+            &FetchOptions::default(),
+        );
+        assert!(received.lock().unwrap()[0].starts_with(b"GET "));
+
+        // --connect goes straight to the TLS handshake
+        let (port, received) = recording_listener();
+        let result = fetch_certificate_chain_from_connect(
+            &format!("127.0.0.1:{port}"),
+            &FetchOptions::default(),
+        );
+        assert!(result.is_err());
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(
+            received[0].first(),
+            Some(&0x16),
+            "expected a TLS handshake record"
+        );
+    }
+
+    #[test]
+    fn test_servername_sets_probe_host() {
+        let (port, received) = recording_listener();
+        let options = FetchOptions {
+            servername: Some("www.example.com".to_string()),
+            ..FetchOptions::default()
+        };
+        let _ = fetch_certificate_chain_from_url(&format!("http://127.0.0.1:{port}"), &options);
+
+        let request = String::from_utf8_lossy(&received.lock().unwrap()[0]).to_lowercase();
+        assert!(request.starts_with("get "));
+        assert!(request.contains(&format!("host: www.example.com:{port}\r\n")));
+    }
+
+    #[test]
+    fn test_connect_fetches_chain() {
+        let (port, _server) = tls_server();
+        let options = FetchOptions {
+            ca_bundle_roots: vec![pem_der(ROOT_PEM)],
+            ..FetchOptions::default()
+        };
+
+        let certs =
+            fetch_certificate_chain_from_connect(&format!("127.0.0.1:{port}"), &options).unwrap();
+
+        assert_eq!(certs.len(), 2);
+    }
+
+    #[test]
+    fn test_handshake_reports_negotiated_tls_13() {
+        let (port, _server) = tls_server();
+        let options = FetchOptions {
+            ca_bundle_roots: vec![pem_der(ROOT_PEM)],
+            ..FetchOptions::default()
+        };
+
+        let certs =
+            fetch_certificate_chain_from_connect(&format!("127.0.0.1:{port}"), &options).unwrap();
+
+        let connection = certs[0].tls_connection.as_ref().unwrap();
+        assert_eq!(connection.protocol, "TLS 1.3");
+        assert!(connection.cipher_suite.starts_with("TLS13_"));
+        assert!(certs[1].tls_connection.is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix_socket_chain_uses_servername() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tls.sock");
+        let listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+        let server = thread::spawn(move || serve_chain(listener.accept().unwrap().0));
+        let options = FetchOptions {
+            ca_bundle_roots: vec![pem_der(ROOT_PEM)],
+            servername: Some("www.example.com".to_string()),
+            ..FetchOptions::default()
+        };
+
+        let certs =
+            fetch_certificate_chain_from_unix_socket(path.to_str().unwrap(), &options).unwrap();
+
+        assert_eq!(certs.len(), 2);
+        assert!(certs[0].subject.contains("www.example.com"));
+        assert!(matches!(
+            certs[0].hostname_check.as_ref().unwrap().status,
+            crate::models::HostnameMatch::Match
+        ));
+        let observed = server.join().unwrap();
+        assert_eq!(observed.sni.as_deref(), Some("www.example.com"));
+        assert_eq!(observed.host.as_deref(), Some("www.example.com"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix_socket_requires_servername() {
+        let result = fetch_certificate_chain_from_unix_socket(
+            "/nonexistent/tls.sock",
+            &FetchOptions::default(),
+        );
+        assert!(matches!(result, Err(CertError::Tls(message)) if message.contains("--servername")));
+    }
+
+    #[test]
+    fn test_handshake_records_duration_and_sizes() {
+        let (port, _server) = tls_server();
+        let options = FetchOptions {
+            ca_bundle_roots: vec![pem_der(ROOT_PEM)],
+            ..FetchOptions::default()
+        };
+
+        let certs =
+            fetch_certificate_chain_from_connect(&format!("127.0.0.1:{port}"), &options).unwrap();
+
+        let connection = certs[0].tls_connection.as_ref().unwrap();
+        assert!(connection.handshake_ms > 0.0);
+        assert_eq!(connection.certificate_count, certs.len());
+        assert_eq!(
+            connection.certificate_bytes,
+            certs.iter().map(|cert| cert.der.len()).sum::<usize>()
+        );
+    }
+
+    /// Accept one CONNECT request, reply with `status` and, if it is 200, relay to `upstream`
+    ///
+    /// Returns the proxy's port and the request head it received.
+    fn connect_proxy(status: &'static str, upstream: u16) -> (u16, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let proxy = thread::spawn(move || {
+            let (client, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(client.try_clone().unwrap());
+            let mut head = String::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim_end().is_empty() {
+                    break;
+                }
+                head.push_str(&line);
+            }
+
+            let mut client = client;
+            client.write_all(status.as_bytes()).unwrap();
+            if status.starts_with("HTTP/1.1 200") {
+                let server = TcpStream::connect(("127.0.0.1", upstream)).unwrap();
+                let (mut to_server, mut from_server) = (server.try_clone().unwrap(), server);
+                let mut from_client = client.try_clone().unwrap();
+                let relay = thread::spawn(move || {
+                    let _ = std::io::copy(&mut from_client, &mut to_server);
+                    let _ = to_server.shutdown(std::net::Shutdown::Write);
+                });
+                let _ = std::io::copy(&mut from_server, &mut client);
+                let _ = client.shutdown(std::net::Shutdown::Write);
+                let _ = relay.join();
+            }
+            head
+        });
+        (port, proxy)
+    }
+
+    #[test]
+    fn test_tls_fetch_tunnels_through_connect_proxy() {
+        let (upstream, _server) = tls_server();
+        let (proxy_port, proxy) =
+            connect_proxy("HTTP/1.1 200 Connection established\r\n\r\n", upstream);
+        let options = FetchOptions {
+            ca_bundle_roots: vec![pem_der(ROOT_PEM)],
+            proxy: Some(parse_proxy_url(&format!("user:secret@127.0.0.1:{proxy_port}")).unwrap()),
+            ..FetchOptions::default()
+        };
+
+        // The proxy, not the client, resolves the target name
+        let certs = fetch_certificate_chain_from_connect("www.example.com:443", &options).unwrap();
+        assert_eq!(certs.len(), 2);
+
+        let head = proxy.join().unwrap();
+        assert!(head.starts_with("CONNECT www.example.com:443 HTTP/1.1\r\n"));
+        let credentials = STANDARD.encode("user:secret");
+        assert!(head.contains(&format!("Proxy-Authorization: Basic {credentials}")));
+    }
+
+    #[test]
+    fn test_refused_connect_is_proxy_error() {
+        let (proxy_port, _proxy) = connect_proxy("HTTP/1.1 403 Forbidden\r\n\r\n", 0);
+        let options = FetchOptions {
+            proxy: Some(parse_proxy_url(&format!("http://127.0.0.1:{proxy_port}")).unwrap()),
+            ..FetchOptions::default()
+        };
+
+        let err =
+            fetch_certificate_chain_from_connect("www.example.com:443", &options).unwrap_err();
+        assert!(matches!(err, CertError::Proxy(message) if message.contains("403")));
+    }
+
+    #[test]
+    fn test_proxy_closing_connection_is_proxy_error() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let proxy_port = listener.local_addr().unwrap().port();
+        let proxy = thread::spawn(move || {
+            // Read the CONNECT request, then hang up without answering
+            let (mut socket, _) = listener.accept().unwrap();
+            let _ = socket.read(&mut [0u8; BUFFER_SIZE]);
+        });
+        let options = FetchOptions {
+            proxy: Some(parse_proxy_url(&format!("http://127.0.0.1:{proxy_port}")).unwrap()),
+            ..FetchOptions::default()
+        };
+
+        let err =
+            fetch_certificate_chain_from_connect("www.example.com:443", &options).unwrap_err();
+        assert!(matches!(err, CertError::Proxy(message) if message.contains("closed")));
+        proxy.join().unwrap();
+    }
+
+    #[test]
+    fn test_parse_proxy_url() {
+        let url = parse_proxy_url("proxy.example:3128").unwrap();
+        assert_eq!(url.host_str(), Some("proxy.example"));
+        assert_eq!(url.port(), Some(3128));
+        assert!(parse_proxy_url("socks5://proxy.example:1080").is_err());
+    }
+
+    #[test]
+    fn test_no_proxy_matches_domains_and_addresses() {
+        let no_proxy = "internal.example, .corp.test,10.0.0.0/8, ::1";
+        assert!(bypasses_proxy(no_proxy, "internal.example"));
+        assert!(bypasses_proxy(no_proxy, "api.internal.example"));
+        assert!(bypasses_proxy(no_proxy, "corp.test"));
+        assert!(bypasses_proxy(no_proxy, "git.corp.test"));
+        assert!(bypasses_proxy(no_proxy, "10.1.2.3"));
+        assert!(bypasses_proxy(no_proxy, "[::1]"));
+        assert!(!bypasses_proxy(no_proxy, "notinternal.example"));
+        assert!(!bypasses_proxy(no_proxy, "example.com"));
+        assert!(!bypasses_proxy(no_proxy, "11.0.0.1"));
+        assert!(bypasses_proxy("*", "example.com"));
+    }
+
+    #[test]
+    fn test_read_line_accepts_bare_lf_blank_line() {
+        let mut response: &[u8] = b"HTTP/1.1 200 OK\nVia: proxy\n\nTLS";
+        assert_eq!(read_line(&mut response).unwrap(), "HTTP/1.1 200 OK");
+        assert_eq!(read_line(&mut response).unwrap(), "Via: proxy");
+        assert_eq!(read_line(&mut response).unwrap(), "");
+        assert_eq!(response, b"TLS");
+
+        let mut closed: &[u8] = b"";
+        assert_eq!(
+            read_line(&mut closed).unwrap_err().kind(),
+            std::io::ErrorKind::UnexpectedEof
+        );
+    }
+
+    /// Require a client certificate issued under the test root, completing one handshake
+    ///
+    /// Returns the number of certificates the client presented, or `None` if the
+    /// handshake failed.
+    fn mtls_server() -> (u16, thread::JoinHandle<Option<usize>>) {
+        let mut client_roots = rustls::RootCertStore::empty();
+        client_roots
+            .add(&rustls::Certificate(pem_der(ROOT_PEM)))
+            .unwrap();
+        let config = ServerConfig::builder()
+            .with_safe_defaults()
+            .with_client_cert_verifier(Arc::new(rustls::server::AllowAnyAuthenticatedClient::new(
+                client_roots,
+            )))
+            .with_single_cert(
+                vec![
+                    rustls::Certificate(pem_der(LEAF_PEM)),
+                    rustls::Certificate(pem_der(INTERMEDIATE_PEM)),
+                ],
+                rustls::PrivateKey(pem_der(LEAF_KEY)),
+            )
+            .unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            let mut conn = ServerConnection::new(Arc::new(config)).unwrap();
+            while conn.is_handshaking() {
+                conn.complete_io(&mut socket).ok()?;
+            }
+            conn.peer_certificates().map(<[_]>::len)
+        });
+        (port, server)
+    }
+
+    #[test]
+    fn test_client_certificate_completes_mtls_handshake() {
+        let (port, server) = mtls_server();
+        let chain = [LEAF_PEM, INTERMEDIATE_PEM].concat();
+        let options = FetchOptions {
+            servername: Some("www.example.com".to_string()),
+            ca_bundle_roots: vec![pem_der(ROOT_PEM)],
+            client_identity: Some(client_identity_from_pem(&chain, LEAF_KEY).unwrap()),
+            ..FetchOptions::default()
+        };
+
+        let certs = fetch_certificate_chain_via_tls("127.0.0.1", port, &options, true)
+            .unwrap()
+            .certificates;
+        assert_eq!(certs.len(), 2);
+        assert_eq!(server.join().unwrap(), Some(2));
+    }
+
+    #[test]
+    fn test_mtls_server_rejects_missing_client_certificate() {
+        let (port, server) = mtls_server();
+        let options = FetchOptions {
+            servername: Some("www.example.com".to_string()),
+            ca_bundle_roots: vec![pem_der(ROOT_PEM)],
+            ..FetchOptions::default()
+        };
+
+        let _ = fetch_certificate_chain_via_tls("127.0.0.1", port, &options, true);
+        assert_eq!(server.join().unwrap(), None);
+    }
+
+    #[test]
+    fn test_client_key_must_match_certificate() {
+        let err = client_identity_from_pem(LEAF_PEM, include_bytes!("../test/unrelated.key"))
+            .unwrap_err();
+        assert!(
+            matches!(err, CertError::ClientAuth(message) if message.contains("does not match"))
+        );
+        assert!(client_identity_from_pem(LEAF_PEM, LEAF_PEM).is_err());
+    }
+
+    #[test]
+    fn test_scan_directory_reports_invalid_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("leaf.pem"), LEAF_PEM).unwrap();
+        fs::write(dir.path().join("root.crt"), ROOT_PEM).unwrap();
+        fs::write(dir.path().join("broken.pem"), b"not a certificate").unwrap();
+        fs::write(dir.path().join("notes.txt"), b"ignored").unwrap();
+
+        let scan = scan_directory(dir.path().to_str().unwrap(), false, InputFormat::Auto).unwrap();
+
+        assert_eq!(scan.certificates.len(), 2);
+        assert_eq!(scan.failures.len(), 1);
+        assert!(scan.failures[0].0.ends_with("broken.pem"));
+    }
+
+    #[test]
+    fn test_scan_directory_recursive() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("nested");
+        fs::create_dir(&nested).unwrap();
+        fs::write(dir.path().join("leaf.pem"), LEAF_PEM).unwrap();
+        fs::write(nested.join("intermediate.der"), pem_der(INTERMEDIATE_PEM)).unwrap();
+        let path = dir.path().to_str().unwrap();
+
+        let flat = scan_directory(path, false, InputFormat::Auto).unwrap();
+        assert_eq!(flat.certificates.len(), 1);
+
+        let deep = scan_directory(path, true, InputFormat::Auto).unwrap();
+        assert_eq!(deep.certificates.len(), 2);
+        assert!(deep.failures.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_directory_survives_symlink_loop() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("a");
+        fs::create_dir(&nested).unwrap();
+        fs::write(nested.join("leaf.pem"), LEAF_PEM).unwrap();
+        std::os::unix::fs::symlink("..", nested.join("link")).unwrap();
+
+        let scan = scan_directory(dir.path().to_str().unwrap(), true, InputFormat::Auto).unwrap();
+        assert_eq!(scan.certificates.len(), 1);
+        assert!(scan.failures.is_empty());
+    }
+
+    #[test]
+    fn test_unresolvable_host_is_dns_error() {
+        // .invalid is reserved and never resolves (RFC 2606)
+        let result = fetch_certificate_chain_from_url(
+            "https://cert-tree-test.invalid",
+            &FetchOptions::default(),
+        );
+        match result {
+            Err(CertError::DnsResolution(message)) => {
+                assert!(message.contains("cert-tree-test.invalid"));
+            }
+            other => panic!("expected a DNS resolution error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_refused_connection_is_not_dns_error() {
+        let result =
+            fetch_certificate_chain_via_tls("127.0.0.1", 9, &FetchOptions::default(), true);
+        assert!(matches!(result, Err(CertError::Io(_))));
+    }
+
+    #[test]
+    fn test_fetch_missing_issuers_downloads_root_via_aia() {
+        let mut certs = crate::parser::parse_certificate_chain(CHAIN_PEM).unwrap();
+        certs.truncate(2);
+        certs[1].ca_issuer_urls = vec![http_server(pem_der(ROOT_PEM))];
+
+        fetch_missing_issuers(&mut certs, &FetchOptions::default());
+
+        assert_eq!(certs.len(), 3);
+        assert!(certs[2].subject.contains("Root CA"));
+        assert_eq!(certs[2].subject, certs[1].issuer);
+    }
+
+    #[test]
+    fn test_fetch_missing_issuers_skips_complete_chain() {
+        let mut certs = crate::parser::parse_certificate_chain(CHAIN_PEM).unwrap();
+        // Unreachable URL: a complete chain must not trigger any download
+        certs[0].ca_issuer_urls = vec!["http://127.0.0.1:9/unused".to_string()];
+
+        fetch_missing_issuers(&mut certs, &FetchOptions::default());
+        assert_eq!(certs.len(), 3);
+    }
+
+    #[test]
+    fn test_fetch_missing_issuers_completes_lone_leaf() {
+        let chain = crate::parser::parse_certificate_chain(CHAIN_PEM).unwrap();
+        let mut leaf = chain[0].clone();
+        leaf.ca_issuer_urls = vec!["http://aia.test/intermediate".to_string()];
+        let mut intermediate = chain[1].clone();
+        intermediate.ca_issuer_urls = vec!["http://aia.test/root".to_string()];
+        let root = chain[2].clone();
+
+        let mut requested = Vec::new();
+        let mut certs = vec![leaf.clone()];
+        fetch_missing_issuers_with(&mut certs, |url| {
+            requested.push(url.to_string());
+            match url {
+                "http://aia.test/intermediate" => Ok(vec![intermediate.clone()]),
+                "http://aia.test/root" => Ok(vec![root.clone()]),
+                _ => Err(CertError::Tls(format!("unexpected {url}"))),
+            }
+        });
+        assert_eq!(requested.len(), 2);
+        assert_eq!(certs.len(), 3);
+        assert_eq!(certs[2].subject, certs[2].issuer);
+    }
+
+    #[test]
+    fn test_fetch_missing_issuers_stops_on_failure_and_loops() {
+        let chain = crate::parser::parse_certificate_chain(CHAIN_PEM).unwrap();
+        let mut leaf = chain[0].clone();
+        leaf.ca_issuer_urls = vec!["http://aia.test/issuer".to_string()];
+
+        let mut certs = vec![leaf.clone()];
+        fetch_missing_issuers_with(&mut certs, |_| Err(CertError::Tls("refused".to_string())));
+        assert_eq!(certs.len(), 1);
+
+        // The intermediate points back at the URL it came from
+        let mut intermediate = chain[1].clone();
+        intermediate.ca_issuer_urls = leaf.ca_issuer_urls.clone();
+        let mut downloads = 0;
+        let mut certs = vec![leaf.clone()];
+        fetch_missing_issuers_with(&mut certs, |_| {
+            downloads += 1;
+            Ok(vec![intermediate.clone()])
+        });
+        assert_eq!(downloads, 1);
+        assert_eq!(certs.len(), 2);
+
+        // A download that serves a certificate already held adds nothing
+        let mut certs = vec![leaf.clone()];
+        fetch_missing_issuers_with(&mut certs, |_| Ok(vec![leaf.clone()]));
+        assert_eq!(certs.len(), 1);
+    }
+
+    #[test]
+    fn test_fetch_jwks_url() {
+        let url = http_server(include_bytes!("../test/jwks.json").to_vec());
+
+        let certs = fetch_certificate_chain_from_url(&url, &FetchOptions::default()).unwrap();
+
+        assert_eq!(certs.len(), 2);
+    }
+
+    #[test]
+    fn test_fetch_gzipped_pem_bundle() {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(CHAIN_PEM).unwrap();
+        let url = http_server(encoder.finish().unwrap());
+
+        let certs = fetch_certificate_chain_from_url(&url, &FetchOptions::default()).unwrap();
+
+        assert_eq!(certs.len(), 3);
+        assert!(certs[0].subject.contains("www.example.com"));
+    }
+
+    #[test]
+    fn test_fetch_bare_base64_der() {
+        let der = pem_der(LEAF_PEM);
+        let mut body = STANDARD.encode(&der).into_bytes();
+        body.push(b'\n');
+        let url = http_server(body);
+
+        let certs = fetch_certificate_chain_from_url(&url, &FetchOptions::default()).unwrap();
+
+        assert_eq!(certs.len(), 1);
+        assert_eq!(certs[0].der, der);
     }
 }
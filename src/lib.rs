@@ -0,0 +1,37 @@
+//! # cert-tree - X.509 certificate parsing and chain building
+//!
+//! Library half of the `cert-tree` command-line tool. It parses PEM/DER
+//! certificates into [`CertificateInfo`] records, arranges them into an
+//! issuer/subject [`CertificateTree`], and fetches chains over TLS.
+//!
+//! ## Example
+//!
+//! ```
+//! use cert_tree::{build_certificate_tree, parse_certificate_chain, CertificateInfo};
+//!
+//! let pem = include_str!("../test/chain.pem");
+//! let certificates: Vec<CertificateInfo> = parse_certificate_chain(pem.as_bytes()).unwrap();
+//! assert_eq!(certificates.len(), 3);
+//! assert!(certificates[0].subject.contains("www.example.com"));
+//!
+//! let tree = build_certificate_tree(&certificates);
+//! assert_eq!(tree.roots.len(), 1);
+//! ```
+
+#![allow(clippy::missing_docs_in_private_items)]
+#![allow(clippy::too_many_lines)]
+
+mod ct;
+pub mod error;
+mod hostname;
+pub mod io;
+pub mod models;
+mod ocsp;
+mod oid;
+pub mod parser;
+pub mod tree;
+
+pub use error::CertError;
+pub use models::{CertificateInfo, CertificateNode, CertificateTree};
+pub use parser::{extract_cert_info, parse_certificate_chain};
+pub use tree::build_certificate_tree;
@@ -0,0 +1,128 @@
+//! Role-based extension linting
+//!
+//! A certificate is treated as a CA when Basic Constraints say `CA:TRUE` or its
+//! Key Usage allows `keyCertSign`, and as a TLS server leaf otherwise, unless its
+//! Extended Key Usage names other purposes only. Each role has extensions it must
+//! carry: a CA needs `CA:TRUE` and `keyCertSign`, a TLS server leaf needs the
+//! `serverAuth` purpose and a Subject Alternative Name.
+
+use cert_tree::models::CertificateInfo;
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+/// Role a certificate is linted against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Ca,
+    TlsServer,
+    /// A leaf for other purposes (client auth, code signing, …); nothing is required
+    Other,
+}
+
+/// Findings for the extensions `cert` is missing for its role, e.g. "leaf missing SAN"
+///
+/// Empty when nothing is missing or the certificate cannot be re-parsed.
+#[must_use]
+pub fn lint_certificate(cert: &CertificateInfo) -> Vec<String> {
+    X509Certificate::from_der(&cert.der)
+        .map(|(_, parsed)| lint_parsed(&parsed))
+        .unwrap_or_default()
+}
+
+fn lint_parsed(cert: &X509Certificate) -> Vec<String> {
+    let basic_constraints = cert.basic_constraints().ok().flatten().map(|bc| bc.value);
+    let key_cert_sign = cert
+        .key_usage()
+        .ok()
+        .flatten()
+        .is_some_and(|ku| ku.value.key_cert_sign());
+    let eku = cert
+        .extended_key_usage()
+        .ok()
+        .flatten()
+        .map(|eku| eku.value);
+
+    let is_ca = basic_constraints.is_some_and(|bc| bc.ca);
+    let role = if is_ca || key_cert_sign {
+        Role::Ca
+    } else if eku.is_none_or(|eku| eku.server_auth || eku.any) {
+        Role::TlsServer
+    } else {
+        Role::Other
+    };
+
+    let mut findings = Vec::new();
+    match role {
+        Role::Ca => {
+            match basic_constraints {
+                None => findings.push("CA missing BasicConstraints".to_string()),
+                Some(_) if !is_ca => {
+                    findings.push("CA missing BasicConstraints CA:TRUE".to_string());
+                }
+                Some(_) => {}
+            }
+            if !key_cert_sign {
+                findings.push("CA missing keyCertSign".to_string());
+            }
+        }
+        Role::TlsServer => {
+            if eku.is_none() {
+                findings.push("leaf missing serverAuth EKU".to_string());
+            }
+            if !matches!(cert.subject_alternative_name(), Ok(Some(_))) {
+                findings.push("leaf missing SAN".to_string());
+            }
+        }
+        Role::Other => {}
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cert_tree::parser::parse_certificate_chain;
+
+    fn findings(pem: &[u8]) -> Vec<String> {
+        lint_certificate(&parse_certificate_chain(pem).unwrap()[0])
+    }
+
+    #[test]
+    fn test_complete_chain_has_no_findings() {
+        for cert in parse_certificate_chain(include_bytes!("../test/chain.pem")).unwrap() {
+            assert!(lint_certificate(&cert).is_empty(), "{}", cert.subject);
+        }
+    }
+
+    #[test]
+    fn test_leaf_missing_san() {
+        assert_eq!(
+            findings(include_bytes!("../test/leaf_no_san.pem")),
+            vec!["leaf missing SAN"]
+        );
+    }
+
+    #[test]
+    fn test_leaf_without_eku_needs_server_auth() {
+        assert_eq!(
+            findings(include_bytes!("../test/self_signed_leaf.pem")),
+            vec!["leaf missing serverAuth EKU"]
+        );
+    }
+
+    #[test]
+    fn test_ca_missing_basic_constraints() {
+        assert_eq!(
+            findings(include_bytes!("../test/ca_no_basic_constraints.pem")),
+            vec!["CA missing BasicConstraints"]
+        );
+    }
+
+    #[test]
+    fn test_ca_missing_key_cert_sign() {
+        // CA:TRUE but no Key Usage extension at all
+        assert_eq!(
+            findings(include_bytes!("../test/ec_p384.pem")),
+            vec!["CA missing keyCertSign"]
+        );
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! This crate provides a command-line tool for inspecting and displaying X.509 certificates
 //! in a human-readable tree format. It supports multiple input sources (files, URLs) and
-//! output formats (tree view, verbose text, interactive TUI).
+//! output formats (tree view, verbose text, JSON, YAML, interactive TUI).
 //!
 //! ## Features
 //!
@@ -23,6 +23,9 @@
 //!
 //! # Interactive mode
 //! cert-tree --file certificate.pem --interactive
+//!
+//! # Machine-readable output
+//! cert-tree --url https://example.com --format json
 //! ```
 
 #![allow(clippy::missing_docs_in_private_items)]
@@ -35,23 +38,54 @@ static GLOBAL: MiMalloc = MiMalloc;
 
 mod cli;
 mod completions;
+mod diff;
 mod display;
-mod error;
-mod io;
-mod models;
-mod parser;
-mod tree;
+mod dump;
+mod fields;
+mod lint;
+mod pager;
 
 use std::error::Error;
+use std::fs::File;
+use std::io::{BufWriter, IsTerminal, Write};
+use std::path::Path;
+use std::time::Duration;
 
-use cli::{parse_args, Commands, CompletionCommands};
-use completions::{generate_completion, install_completion};
+use cert_tree::error::CertError;
+use cert_tree::io::{
+    check_ocsp_status, fetch_certificate_chain_from_connect,
+    fetch_certificate_chain_from_unix_socket, fetch_certificate_chain_from_url,
+    fetch_missing_issuers, load_certificate_from_file, load_client_identity, parse_proxy_url,
+    proxy_from_env, scan_directory, trust_anchors, FetchOptions,
+};
+use cert_tree::models::{
+    json_output_schema, CertificateInfo, CertificateTree, DisplayTimezone, TrustAnchor,
+    TrustStatus, ValidityStatus,
+};
+use cert_tree::parser::{
+    dedup_certificates, load_oid_map, parse_certificate_chain_as, parse_hex_certificate,
+    set_oid_overrides, InputFormat,
+};
+use cert_tree::tree::{
+    build_certificate_tree, build_unvalidated_tree, check_revocation, limit_depth, prune_tree,
+    sort_roots, subject_conflicts, summarize_chain, topmost_ancestor,
+    validate_certificate_chain_with_anchors, CertificateFilter, SortOrder,
+};
+use cli::{parse_args, Commands, CompletionCommands, DumpFormat, OutputFormat};
+use completions::{generate_completion, install_completion, InstallOptions};
+use diff::{diff_chains, write_diff};
 use display::{
-    display_certificate_tree_text, display_certificate_tree_tui, display_tui, display_verbose,
+    color_enabled, display_certificate_tree_text, display_certificate_tree_tui, display_compact,
+    display_csv, display_fields, display_fields_csv, display_fingerprints, display_json,
+    display_ndjson, display_pem, display_table, display_tui, display_verbose, display_yaml,
+    TreeChars, Verbosity, Watch,
 };
-use io::{fetch_certificate_chain_from_url, load_certificate_from_file};
-use parser::parse_certificate_chain;
-use tree::build_certificate_tree;
+use dump::{asn1_outline, hex_dump};
+use fields::{Field, SelectedFields};
+use indicatif::{ProgressBar, ProgressStyle};
+use lint::lint_certificate;
+use pager::{pager_enabled, write_paged};
+use tracing::{info, warn};
 
 fn main() -> Result<(), Box<dyn Error>> {
     let args = parse_args();
@@ -63,7 +97,15 @@ fn main() -> Result<(), Box<dyn Error>> {
                 generate_completion(shell);
                 return Ok(());
             }
-            CompletionCommands::Install { shell } => match install_completion(shell) {
+            CompletionCommands::Install {
+                shell,
+                dry_run,
+                force,
+            } => match install_completion(
+                shell,
+                InstallOptions { dry_run, force },
+                confirm_overwrite,
+            ) {
                 Ok(message) => {
                     println!("{message}");
                     return Ok(());
@@ -74,50 +116,620 @@ fn main() -> Result<(), Box<dyn Error>> {
                 }
             },
         },
-        None => {
-            // Continue with normal certificate inspection
+        Some(Commands::Schema) => {
+            display_json(&json_output_schema(), &mut std::io::stdout())?;
+            return Ok(());
+        }
+        Some(Commands::Diff { old, new }) => {
+            let load = |path: &str| -> Result<Vec<CertificateInfo>, CertError> {
+                parse_certificate_chain_as(&load_certificate_from_file(path)?, args.input_format)
+            };
+            let diffs = diff_chains(&load(&old)?, &load(&new)?);
+            write_diff(
+                &diffs,
+                &mut std::io::stdout(),
+                color_enabled(args.no_color, false),
+            )?;
+            if !diffs.is_empty() {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Some(Commands::Inspect | Commands::Lint | Commands::Scan { .. }) | None => {
+            // Continue with certificate inspection; `lint` only changes the output below
+            // and `scan` has already become a `--dir`
         }
     }
 
-    let certificates = if let Some(file) = args.file.as_ref() {
-        let data = load_certificate_from_file(file)?;
-        parse_certificate_chain(&data)?
-    } else if let Some(url) = args.url.as_ref() {
-        fetch_certificate_chain_from_url(url)?
-    } else {
-        // Unreachable due to CLI validation
-        unreachable!();
+    tracing_subscriber::fmt()
+        .with_max_level(args.log_level())
+        .with_writer(std::io::stderr)
+        .with_target(false)
+        .with_ansi(color_enabled(args.no_color, false) && std::io::stderr().is_terminal())
+        .init();
+    let verbosity = args.verbosity();
+    if args.text {
+        warn!("-t/--text is deprecated and has no effect; text output is the default");
+    }
+
+    if let Some(path) = args.oid_map.as_deref() {
+        set_oid_overrides(load_oid_map(path)?);
+    }
+
+    let bundle = match args.ca_bundle.as_deref() {
+        Some(path) => load_ca_bundle(path)?,
+        None => Vec::new(),
+    };
+
+    let options = FetchOptions {
+        port: args.port,
+        starttls: args.starttls,
+        servername: args.servername.clone(),
+        ca_bundle_roots: bundle.iter().map(|root| root.der.clone()).collect(),
+        retries: args.retries,
+        proxy: args
+            .proxy
+            .clone()
+            .or_else(proxy_from_env)
+            .map(|proxy| parse_proxy_url(&proxy))
+            .transpose()?,
+        client_identity: match (&args.client_cert, &args.client_key) {
+            (Some(cert), Some(key)) => Some(load_client_identity(cert, key)?),
+            _ => None,
+        },
+    };
+    // Chains are judged against the same roots the TLS handshake trusts
+    let anchors = trust_anchors(&options);
+    let targets: Vec<FetchTarget> = args
+        .url
+        .iter()
+        .map(|url| FetchTarget {
+            name: url,
+            fetch: fetch_certificate_chain_from_url,
+        })
+        .chain(args.connect.iter().map(|target| FetchTarget {
+            name: target,
+            fetch: fetch_certificate_chain_from_connect,
+        }))
+        .chain(args.unix_socket.iter().map(|path| FetchTarget {
+            name: path,
+            fetch: fetch_certificate_chain_from_unix_socket,
+        }))
+        .collect();
+    // Pasted certificates are decoded up front so a typo fails before any fetch
+    let hex_certificates = args
+        .hex
+        .iter()
+        .map(|hex| parse_hex_certificate(hex))
+        .collect::<Result<Vec<_>, _>>()?
+        .concat();
+    let collected = collect_certificates(
+        &args.file,
+        &args.dir,
+        args.recursive,
+        args.input_format,
+        &targets,
+        &options,
+        !args.quiet && std::io::stderr().is_terminal(),
+    )?;
+    let mut certificates = with_hex_certificates(collected.certificates, &hex_certificates);
+    apply_chain_options(&mut certificates, args.leaf_only, args.fetch_root, &options);
+    if args.root {
+        select_root(&mut certificates);
+    }
+    let conflicts = subject_conflicts(&certificates);
+    if !conflicts.is_empty() {
+        warn!(
+            "several certificates share a subject, all are kept: {}",
+            conflicts.join("; ")
+        );
+    }
+
+    let mut out: Box<dyn Write> = match args.output.as_ref() {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(std::io::stdout()),
     };
 
-    if certificates.len() == 1 {
-        let cert_info = &certificates[0];
+    let color = color_enabled(args.no_color, args.output.is_some());
+    let tree_chars = TreeChars::detect(args.ascii);
+    let validation = (!args.no_validate).then_some(anchors.as_slice());
+
+    // --only-expired / --only-expiring / --expire-before narrow what is shown; the
+    // tree is still built from every certificate so issuers resolve before it is pruned
+    let filter = args.certificate_filter();
+    let mut shown: Vec<CertificateInfo> = certificates
+        .iter()
+        .filter(|cert| filter.matches(cert))
+        .cloned()
+        .collect();
+    let filtered_out = certificates.len() - shown.len();
+    if filtered_out > 0 {
+        info!(
+            "{filtered_out} of {} certificates filtered out",
+            certificates.len()
+        );
+    }
+
+    // Rendered in full first so the pager can decide whether it is needed
+    let mut rendered = Vec::new();
+
+    if args.count {
+        writeln!(rendered, "{}", shown.len())?;
+    } else if let Some(algorithm) = args.fingerprint {
+        // Bare lines for grep/diff, never paged
+        let take = if args.all { shown.len() } else { 1 };
+        display_fingerprints(shown.iter().take(take), algorithm, &mut out)?;
+    } else if args.pem_out {
+        // Raw certificates for other tools, never paged
+        display_pem(&shown, &mut out)?;
+    } else if let Some(kind) = args.dump {
+        for (index, cert) in shown.iter().enumerate() {
+            if index > 0 {
+                writeln!(rendered)?;
+            }
+            writeln!(
+                rendered,
+                "{} ({} bytes)",
+                cert_tree::parser::extract_cn(&cert.subject),
+                cert.der.len()
+            )?;
+            match kind {
+                DumpFormat::Hex => hex_dump(&cert.der, &mut rendered, color)?,
+                DumpFormat::Asn1 => asn1_outline(&cert.der, &mut rendered, color)?,
+            }
+        }
+    } else if !args.fields.is_empty() {
+        shown.sort_by(|a, b| args.sort.compare(a, b));
+        display_selected_fields(
+            &shown,
+            &args.fields,
+            args.format,
+            args.timezone,
+            &mut rendered,
+        )?;
+    } else if args.summary {
+        let tree = build_chain_tree(&certificates, validation);
+        if let Some(summary) = summarize_chain(&tree) {
+            writeln!(rendered, "{summary}")?;
+        }
+    } else if args.linting() {
+        let mut clean = true;
+        for cert in &shown {
+            let cn = cert_tree::parser::extract_cn(&cert.subject);
+            for finding in lint_certificate(cert) {
+                clean = false;
+                writeln!(rendered, "{cn}: {finding}")?;
+            }
+        }
+        if clean {
+            writeln!(rendered, "No lint findings in {} certificates", shown.len())?;
+        }
+    } else if shows_single_certificate(certificates.len(), shown.len(), args.watch.is_some()) {
+        let cert_info = &shown[0];
+        // Validated like a chain so --ca-bundle anchoring applies to a lone certificate too
+        let tree = build_chain_tree(&shown, validation);
 
         if args.interactive {
-            display_tui(cert_info)?;
+            display_tui(cert_info, args.timezone)?;
         } else {
-            display_verbose(cert_info);
+            match args.format {
+                OutputFormat::Tree | OutputFormat::Text => {
+                    display_verbose(cert_info, verbosity, args.timezone, &mut rendered)?;
+                    if let Some(root) = tree
+                        .roots
+                        .first()
+                        .filter(|root| !matches!(root.trust_status, TrustStatus::NotChecked))
+                    {
+                        writeln!(rendered, "Trust: {}", root.trust_status.text())?;
+                    }
+                }
+                // The same tree shape as for a chain, so scripts need not know the count
+                OutputFormat::Json => display_json(&tree, &mut rendered)?,
+                OutputFormat::Yaml => display_yaml(&tree, &mut rendered)?,
+                OutputFormat::Csv => display_csv(&tree, args.sort, &mut rendered)?,
+                OutputFormat::Table => {
+                    display_table(&tree, args.sort, args.timezone, &mut rendered)?;
+                }
+                OutputFormat::Compact => {
+                    display_compact(&tree, &mut rendered, color, args.timezone)?;
+                }
+                OutputFormat::Ndjson => display_ndjson(&shown, &mut out)?,
+            }
         }
     } else {
-        let tree = build_certificate_tree(&certificates);
+        let tree = build_display_tree(
+            &certificates,
+            validation,
+            &filter,
+            args.sort,
+            args.max_depth,
+            args.ocsp.then_some(&options),
+        );
 
         if args.interactive {
-            display_certificate_tree_tui(&tree)?;
+            // --watch re-reads every input without the progress bar, which would draw over the TUI
+            let refresh = || -> Result<CertificateTree, Box<dyn Error>> {
+                let mut certificates = with_hex_certificates(
+                    collect_certificates(
+                        &args.file,
+                        &args.dir,
+                        args.recursive,
+                        args.input_format,
+                        &targets,
+                        &options,
+                        false,
+                    )?
+                    .certificates,
+                    &hex_certificates,
+                );
+                apply_chain_options(&mut certificates, args.leaf_only, args.fetch_root, &options);
+                if args.root {
+                    select_root(&mut certificates);
+                }
+                Ok(build_display_tree(
+                    &certificates,
+                    validation,
+                    &filter,
+                    args.sort,
+                    args.max_depth,
+                    args.ocsp.then_some(&options),
+                ))
+            };
+            let watch = args.watch.map(|seconds| Watch {
+                interval: Duration::from_secs(seconds),
+                refresh: &refresh,
+            });
+            display_certificate_tree_tui(
+                &tree,
+                args.sort,
+                color,
+                tree_chars,
+                args.timezone,
+                watch.as_ref(),
+            )?;
         } else {
-            display_certificate_tree_text(&tree);
+            match args.format {
+                OutputFormat::Tree => {
+                    display_certificate_tree_text(
+                        &tree,
+                        &mut rendered,
+                        color,
+                        tree_chars,
+                        args.timezone,
+                    )?;
+                }
+                OutputFormat::Text => {
+                    shown.sort_by(|a, b| args.sort.compare(a, b));
+                    for (index, cert_info) in shown.iter().enumerate() {
+                        if index > 0 && verbosity != Verbosity::Quiet {
+                            writeln!(rendered)?;
+                        }
+                        display_verbose(cert_info, verbosity, args.timezone, &mut rendered)?;
+                    }
+                }
+                OutputFormat::Json => display_json(&tree, &mut rendered)?,
+                OutputFormat::Yaml => display_yaml(&tree, &mut rendered)?,
+                OutputFormat::Csv => display_csv(&tree, args.sort, &mut rendered)?,
+                OutputFormat::Table => {
+                    display_table(&tree, args.sort, args.timezone, &mut rendered)?;
+                }
+                OutputFormat::Compact => {
+                    display_compact(&tree, &mut rendered, color, args.timezone)?;
+                }
+                OutputFormat::Ndjson => {
+                    // Streamed straight to the output rather than buffered for the pager
+                    shown.sort_by(|a, b| args.sort.compare(a, b));
+                    display_ndjson(&shown, &mut out)?;
+                }
+            }
+        }
+    }
+
+    write_paged(
+        &rendered,
+        &mut out,
+        pager_enabled(args.no_pager, args.output.is_some()),
+    )?;
+    out.flush()?;
+
+    if args.check {
+        let code = check_exit_code(&certificates);
+        if code != 0 {
+            std::process::exit(code);
+        }
+    }
+    if collected.failed_targets > 0 && !args.keep_going {
+        std::process::exit(FETCH_FAILED_EXIT_CODE);
+    }
+
+    Ok(())
+}
+
+/// Whether a lone certificate gets the single-certificate views instead of the chain views
+///
+/// `--watch` always takes the chain TUI, the only one that refreshes, so a server
+/// sending just its leaf is still re-fetched.
+fn shows_single_certificate(collected: usize, shown: usize, watching: bool) -> bool {
+    collected == 1 && shown == 1 && !watching
+}
+
+/// Build the chain tree, validated against the trust `anchors` given in `validation`
+///
+/// With `validation` set to `None` (`--no-validate`) no signature is checked
+/// and every node is `NotChecked`.
+fn build_chain_tree(
+    certificates: &[CertificateInfo],
+    validation: Option<&[TrustAnchor]>,
+) -> CertificateTree {
+    match validation {
+        Some(anchors) => {
+            let mut tree = build_certificate_tree(certificates);
+            if !anchors.is_empty() {
+                validate_certificate_chain_with_anchors(&mut tree, anchors);
+            }
+            tree
         }
+        None => build_unvalidated_tree(certificates),
+    }
+}
+
+/// Build the chain tree as displayed: validated, pruned by `filter`, sorted and depth-limited
+///
+/// With `ocsp` options, revocation is then checked through the same proxy as other fetches.
+fn build_display_tree(
+    certificates: &[CertificateInfo],
+    validation: Option<&[TrustAnchor]>,
+    filter: &CertificateFilter,
+    sort: SortOrder,
+    max_depth: Option<u32>,
+    ocsp: Option<&FetchOptions>,
+) -> CertificateTree {
+    let mut tree = build_chain_tree(certificates, validation);
+    prune_tree(&mut tree, &|node| filter.matches(&node.cert));
+    sort_roots(&mut tree, sort);
+    if let Some(max_depth) = max_depth {
+        limit_depth(&mut tree, max_depth as usize);
+    }
+    if let Some(options) = ocsp {
+        check_revocation(&mut tree, &|cert, issuer| {
+            check_ocsp_status(cert, issuer, options)
+        });
     }
+    tree
+}
 
+/// Ask on the terminal whether to replace an existing completion file
+///
+/// Without an interactive stdin there is nobody to ask, so the answer is no.
+fn confirm_overwrite(path: &Path) -> bool {
+    if !std::io::stdin().is_terminal() {
+        return false;
+    }
+    eprint!("{} already exists. Overwrite? [y/N] ", path.display());
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer).is_ok()
+        && matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Write only the `--fields` of each certificate, as a flat list in the chosen format
+///
+/// JSON and YAML are always a list of records, even for a single certificate.
+/// Text lines show validity dates in `zone`; CSV, JSON and YAML keep them in UTC.
+fn display_selected_fields(
+    certificates: &[CertificateInfo],
+    fields: &[Field],
+    format: OutputFormat,
+    zone: DisplayTimezone,
+    out: &mut impl Write,
+) -> Result<(), Box<dyn Error>> {
+    let records: Vec<SelectedFields> = certificates
+        .iter()
+        .map(|cert| SelectedFields { cert, fields })
+        .collect();
+    match format {
+        OutputFormat::Tree | OutputFormat::Text | OutputFormat::Table | OutputFormat::Compact => {
+            for (index, cert) in certificates.iter().enumerate() {
+                if index > 0 {
+                    writeln!(out)?;
+                }
+                display_fields(cert, fields, zone, out)?;
+            }
+        }
+        OutputFormat::Csv => display_fields_csv(certificates, fields, out)?,
+        OutputFormat::Ndjson => display_ndjson(&records, out)?,
+        OutputFormat::Json => display_json(&records, out)?,
+        OutputFormat::Yaml => display_yaml(&records, out)?,
+    }
     Ok(())
 }
 
+/// Signature shared by the `--url` and `--connect` fetchers
+type FetchFn = fn(&str, &FetchOptions) -> Result<Vec<CertificateInfo>, CertError>;
+
+/// A `--url` or `--connect` endpoint and the fetcher that reads its chain
+struct FetchTarget<'a> {
+    name: &'a str,
+    fetch: FetchFn,
+}
+
+/// Progress bar layout while fetching several endpoints
+const PROGRESS_TEMPLATE: &str = "{spinner} fetched {pos}/{len} {wide_msg}";
+
+/// Exit code when a URL, `--connect` or `--unix-socket` target could not be fetched
+const FETCH_FAILED_EXIT_CODE: i32 = 4;
+
+/// Certificates from every input, and how many endpoints could not be fetched
+struct Collected {
+    certificates: Vec<CertificateInfo>,
+    failed_targets: usize,
+}
+
+/// Load every file, directory, URL and --connect endpoint and combine their certificates into one deduplicated set
+///
+/// Certificates from separate inputs end up in a single tree, so a leaf in one
+/// file links to its issuer in another. Endpoints that fail are printed to
+/// stderr once all have been tried, whatever the log level; the call only fails
+/// if no certificates were found at all.
+fn collect_certificates(
+    files: &[String],
+    dirs: &[String],
+    recursive: bool,
+    input_format: InputFormat,
+    targets: &[FetchTarget],
+    options: &FetchOptions,
+    show_progress: bool,
+) -> Result<Collected, Box<dyn Error>> {
+    let mut certificates = Vec::new();
+    for file in files {
+        let data = load_certificate_from_file(file)?;
+        certificates.extend(parse_certificate_chain_as(&data, input_format)?);
+    }
+    for dir in dirs {
+        let scan = scan_directory(dir, recursive, input_format)?;
+        for (path, err) in &scan.failures {
+            warn!("skipping {}: {err}", path.display());
+        }
+        certificates.extend(scan.certificates);
+    }
+
+    let (fetched, mut failures) = fetch_targets(targets, options, show_progress);
+    certificates.extend(fetched);
+    let last_failure = if certificates.is_empty() {
+        failures.pop()
+    } else {
+        None
+    };
+    for (name, err) in &failures {
+        eprintln!("Error: {name}: {err}");
+    }
+    if let Some((_, err)) = last_failure {
+        return Err(err.into());
+    }
+
+    Ok(Collected {
+        certificates: dedup_certificates(certificates),
+        failed_targets: failures.len(),
+    })
+}
+
+/// Append the `--hex` certificates to the collected ones, dropping repeats
+fn with_hex_certificates(
+    mut certificates: Vec<CertificateInfo>,
+    hex_certificates: &[CertificateInfo],
+) -> Vec<CertificateInfo> {
+    certificates.extend_from_slice(hex_certificates);
+    dedup_certificates(certificates)
+}
+
+/// Fetch every endpoint in turn, collecting failures instead of stopping at the first
+///
+/// With `show_progress` and more than one endpoint, a progress bar on stderr
+/// shows how many are done and which one is being fetched.
+fn fetch_targets(
+    targets: &[FetchTarget],
+    options: &FetchOptions,
+    show_progress: bool,
+) -> (Vec<CertificateInfo>, Vec<(String, CertError)>) {
+    let progress = if show_progress && targets.len() > 1 {
+        ProgressBar::new(targets.len() as u64).with_style(
+            ProgressStyle::with_template(PROGRESS_TEMPLATE)
+                .unwrap_or_else(|_| ProgressStyle::default_bar()),
+        )
+    } else {
+        ProgressBar::hidden()
+    };
+
+    let mut certificates = Vec::new();
+    let mut failures = Vec::new();
+    for target in targets {
+        progress.set_message(target.name.to_string());
+        match (target.fetch)(target.name, options) {
+            Ok(fetched) => certificates.extend(fetched),
+            Err(err) => failures.push((target.name.to_string(), err)),
+        }
+        progress.inc(1);
+    }
+    progress.finish_and_clear();
+
+    (certificates, failures)
+}
+
+/// Narrow the set to the leaf, or complete it with issuers fetched via AIA
+fn apply_chain_options(
+    certificates: &mut Vec<CertificateInfo>,
+    leaf_only: bool,
+    fetch_root: bool,
+    options: &FetchOptions,
+) {
+    if leaf_only {
+        certificates.truncate(1);
+        return;
+    }
+    if fetch_root {
+        fetch_missing_issuers(certificates, options);
+    }
+}
+
+/// Reduce the set to the certificate `--root` picks
+///
+/// The first certificate is followed up the built tree to its topmost
+/// ancestor, which is the root when the chain is complete.
+fn select_root(certificates: &mut Vec<CertificateInfo>) {
+    let tree = build_certificate_tree(certificates);
+    let top = certificates
+        .first()
+        .and_then(|first| topmost_ancestor(&tree, first))
+        .cloned();
+    if let Some(top) = top {
+        *certificates = vec![top];
+    }
+}
+
+/// Load the trusted root certificates given with `--ca-bundle`
+fn load_ca_bundle(path: &str) -> Result<Vec<CertificateInfo>, Box<dyn Error>> {
+    let data = std::fs::read(path)?;
+    Ok(parse_certificate_chain_as(&data, InputFormat::Pem)?)
+}
+
+/// Aggregate the validity of every certificate into a `--check` exit code
+///
+/// An expired certificate outranks one that is not yet valid, which in turn
+/// outranks one that is merely expiring soon.
+fn check_exit_code(certificates: &[CertificateInfo]) -> i32 {
+    let statuses: Vec<ValidityStatus> = certificates
+        .iter()
+        .map(|cert| ValidityStatus::from_dates(cert.not_before_dt, cert.not_after_dt))
+        .collect();
+
+    if statuses
+        .iter()
+        .any(|status| matches!(status, ValidityStatus::Expired))
+    {
+        ValidityStatus::Expired.exit_code()
+    } else if statuses
+        .iter()
+        .any(|status| matches!(status, ValidityStatus::NotYetValid))
+    {
+        ValidityStatus::NotYetValid.exit_code()
+    } else if statuses
+        .iter()
+        .any(|status| matches!(status, ValidityStatus::ExpiringSoon))
+    {
+        ValidityStatus::ExpiringSoon.exit_code()
+    } else {
+        ValidityStatus::Valid.exit_code()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::error::CertError;
-    use crate::models::{
-        CertificateInfo, CertificateNode, CertificateTree, ValidationStatus, ValidityStatus,
+    use cert_tree::models::{
+        AnchorStatus, CertificateNode, CertificateRole, CertificateTree, RevocationStatus,
+        TrustStatus, ValidationStatus,
     };
-    use crate::parser::parse_certificate_chain;
+    use cert_tree::parser::parse_certificate_chain;
+    use clap::Parser;
 
     #[test]
     fn test_parse_certificate_chain_invalid_data() {
@@ -126,6 +738,290 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_collect_certificates_links_separate_files() {
+        let files = vec![
+            "test/leaf.pem".to_string(),
+            "test/intermediate.pem".to_string(),
+            "test/chain.pem".to_string(),
+        ];
+        let certificates = collect_certificates(
+            &files,
+            &[],
+            false,
+            InputFormat::Auto,
+            &[],
+            &FetchOptions::default(),
+            false,
+        )
+        .unwrap()
+        .certificates;
+
+        // chain.pem repeats the leaf and intermediate; only the root is new
+        assert_eq!(certificates.len(), 3);
+
+        let tree = build_certificate_tree(&certificates[..2]);
+        assert_eq!(tree.roots.len(), 1);
+        assert!(tree.roots[0].cert.subject.contains("Intermediate"));
+        assert_eq!(tree.roots[0].children.len(), 1);
+        assert!(tree.roots[0].children[0]
+            .cert
+            .subject
+            .contains("www.example.com"));
+    }
+
+    fn fetch_leaf(_: &str, _: &FetchOptions) -> Result<Vec<CertificateInfo>, CertError> {
+        parse_certificate_chain(include_bytes!("../test/leaf.pem"))
+    }
+
+    fn fetch_root(_: &str, _: &FetchOptions) -> Result<Vec<CertificateInfo>, CertError> {
+        parse_certificate_chain(include_bytes!("../test/root.pem"))
+    }
+
+    fn fetch_unresolvable(name: &str, _: &FetchOptions) -> Result<Vec<CertificateInfo>, CertError> {
+        Err(CertError::DnsResolution(name.to_string()))
+    }
+
+    #[test]
+    fn test_fetch_continues_past_failing_host() {
+        let targets = [
+            FetchTarget {
+                name: "https://leaf.example",
+                fetch: fetch_leaf,
+            },
+            FetchTarget {
+                name: "https://down.example",
+                fetch: fetch_unresolvable,
+            },
+            FetchTarget {
+                name: "https://root.example",
+                fetch: fetch_root,
+            },
+        ];
+
+        let (certificates, failures) = fetch_targets(&targets, &FetchOptions::default(), false);
+        assert_eq!(certificates.len(), 2);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, "https://down.example");
+
+        // Partial failures are counted, not fatal, once something was fetched
+        let collected = collect_certificates(
+            &[],
+            &[],
+            false,
+            InputFormat::Auto,
+            &targets,
+            &FetchOptions::default(),
+            false,
+        )
+        .unwrap();
+        assert_eq!(collected.certificates.len(), 2);
+        assert_eq!(collected.failed_targets, 1);
+    }
+
+    #[test]
+    fn test_collect_fails_when_every_host_fails() {
+        let targets = [FetchTarget {
+            name: "https://down.example",
+            fetch: fetch_unresolvable,
+        }];
+
+        let result = collect_certificates(
+            &[],
+            &[],
+            false,
+            InputFormat::Auto,
+            &targets,
+            &FetchOptions::default(),
+            false,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_ca_bundle_option_anchors_internal_root() {
+        let args = cli::Args::try_parse_from([
+            "cert-tree",
+            "--file",
+            "test/leaf.pem",
+            "--file",
+            "test/intermediate.pem",
+            "--ca-bundle",
+            "test/root.pem",
+        ])
+        .unwrap();
+        assert_eq!(args.ca_bundle.as_deref(), Some("test/root.pem"));
+        assert_eq!(args.file, ["test/leaf.pem", "test/intermediate.pem"]);
+
+        let bundle = load_ca_bundle(args.ca_bundle.as_deref().unwrap()).unwrap();
+        assert_eq!(bundle.len(), 1);
+        assert_eq!(bundle[0].subject, bundle[0].issuer);
+        let anchors = trust_anchors(&FetchOptions {
+            ca_bundle_roots: vec![bundle[0].der.clone()],
+            ..FetchOptions::default()
+        });
+
+        let certificates = collect_certificates(
+            &args.file,
+            &args.dir,
+            args.recursive,
+            args.input_format,
+            &[],
+            &FetchOptions::default(),
+            false,
+        )
+        .unwrap()
+        .certificates;
+        let tree = build_chain_tree(&certificates, Some(&anchors));
+
+        assert!(matches!(tree.roots[0].trust_status, TrustStatus::Anchored));
+        assert!(matches!(
+            tree.roots[0].validation_status,
+            ValidationStatus::Valid
+        ));
+
+        // A lone certificate is checked against the bundle as well
+        let leaf = &certificates[..1];
+        let tree = build_chain_tree(leaf, Some(&anchors));
+        assert!(matches!(tree.roots[0].trust_status, TrustStatus::Untrusted));
+    }
+
+    #[test]
+    fn test_bundled_and_public_roots_are_both_trusted() {
+        let bundle = load_ca_bundle("test/root.pem").unwrap();
+        let anchors = trust_anchors(&FetchOptions {
+            ca_bundle_roots: vec![bundle[0].der.clone()],
+            ..FetchOptions::default()
+        });
+
+        // The test chain ends in the bundled root; ISRG Root X1 is a webpki root
+        let mut certificates = load_ca_bundle("test/chain.pem").unwrap();
+        certificates.extend(
+            load_ca_bundle("test/cacert.pem")
+                .unwrap()
+                .into_iter()
+                .filter(|cert| cert.subject.contains("CN=ISRG Root X1")),
+        );
+        let tree = build_chain_tree(&certificates, Some(&anchors));
+
+        assert_eq!(tree.roots.len(), 2);
+        assert!(tree
+            .roots
+            .iter()
+            .all(|root| matches!(root.trust_status, TrustStatus::Anchored)));
+        assert_eq!(tree.anchor_status, Some(AnchorStatus::TrustedRoot));
+
+        // Without the bundle the test root is untrusted, whatever else is present
+        let tree = build_chain_tree(
+            &certificates,
+            Some(&trust_anchors(&FetchOptions::default())),
+        );
+        assert_eq!(tree.anchor_status, Some(AnchorStatus::UntrustedRoot));
+    }
+
+    #[test]
+    fn test_count_covers_files_and_directories() {
+        let bundle = collect_certificates(
+            &["test/cacert.pem".to_string()],
+            &[],
+            false,
+            InputFormat::Auto,
+            &[],
+            &FetchOptions::default(),
+            false,
+        )
+        .unwrap()
+        .certificates;
+        assert_eq!(bundle.len(), 146);
+
+        // The chain's certificates also sit in the directory; each is counted once
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::copy("test/root.pem", dir.path().join("root.pem")).unwrap();
+        std::fs::copy("test/leaf.pem", dir.path().join("leaf.pem")).unwrap();
+        let combined = collect_certificates(
+            &["test/chain.pem".to_string()],
+            &[dir.path().to_string_lossy().into_owned()],
+            false,
+            InputFormat::Auto,
+            &[],
+            &FetchOptions::default(),
+            false,
+        )
+        .unwrap()
+        .certificates;
+        assert_eq!(combined.len(), 3);
+    }
+
+    #[test]
+    fn test_selected_fields_json_is_always_a_list() {
+        let chain = parse_certificate_chain(include_bytes!("../test/chain.pem")).unwrap();
+        for certificates in [&chain[..1], &chain[..]] {
+            let mut out = Vec::new();
+            display_selected_fields(
+                certificates,
+                &[fields::Field::Cn],
+                OutputFormat::Json,
+                DisplayTimezone::Utc,
+                &mut out,
+            )
+            .unwrap();
+            let records: serde_json::Value = serde_json::from_slice(&out).unwrap();
+            assert_eq!(records.as_array().unwrap().len(), certificates.len());
+        }
+    }
+
+    #[test]
+    fn test_no_pager_option_disables_paging() {
+        let args =
+            cli::Args::try_parse_from(["cert-tree", "--no-pager", "--file", "a.pem"]).unwrap();
+        assert!(!pager_enabled(args.no_pager, args.output.is_some()));
+    }
+
+    #[test]
+    fn test_watched_single_certificate_uses_refreshing_view() {
+        let still = cli::Args::try_parse_from(["cert-tree", "--file", "leaf.pem", "-i"]).unwrap();
+        assert!(shows_single_certificate(1, 1, still.watch.is_some()));
+
+        let watched =
+            cli::Args::try_parse_from(["cert-tree", "--file", "leaf.pem", "-i", "--watch", "60"])
+                .unwrap();
+        assert!(!shows_single_certificate(1, 1, watched.watch.is_some()));
+    }
+
+    #[test]
+    fn test_leaf_only_keeps_first_certificate() {
+        let mut certificates =
+            parse_certificate_chain(include_bytes!("../test/chain.pem")).unwrap();
+
+        apply_chain_options(&mut certificates, true, false, &FetchOptions::default());
+
+        assert_eq!(certificates.len(), 1);
+        assert!(certificates[0].subject.contains("www.example.com"));
+    }
+
+    #[test]
+    fn test_select_root_of_chain() {
+        let chain = parse_certificate_chain(include_bytes!("../test/chain.pem")).unwrap();
+
+        // The root is found by walking the tree, whatever the input order
+        let mut certificates = vec![chain[0].clone(), chain[2].clone(), chain[1].clone()];
+        select_root(&mut certificates);
+        assert_eq!(certificates.len(), 1);
+        assert!(certificates[0].subject.contains("Root"));
+    }
+
+    #[test]
+    fn test_leaf_and_first_are_leaf_only() {
+        for flag in ["--leaf", "--first", "--leaf-only"] {
+            let args = cli::Args::try_parse_from(["cert-tree", "--file", "x.pem", flag]).unwrap();
+            assert!(args.leaf_only);
+        }
+        assert!(
+            cli::Args::try_parse_from(["cert-tree", "--file", "x.pem", "--leaf", "--root"])
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_load_certificate_from_file_not_found() {
         let result = load_certificate_from_file("nonexistent.pem");
@@ -140,26 +1036,48 @@ mod tests {
             serial_number: "12345".to_string(),
             not_before: "2023-01-01".to_string(),
             not_after: "2024-01-01".to_string(),
+            not_before_dt: chrono::DateTime::default(),
+            not_after_dt: chrono::DateTime::default(),
             public_key_algorithm: "RSA".to_string(),
+            rsa_key_bits: Some(2048),
             signature_algorithm: "SHA256-RSA".to_string(),
+            signature_oid: "1.2.840.113549.1.1.11".to_string(),
             version: 3,
             extensions: vec![
-                crate::models::ExtensionInfo {
+                cert_tree::models::ExtensionInfo {
                     oid: "2.5.29.14".to_string(),
-                    name: crate::parser::oid_to_name("2.5.29.14"),
+                    name: cert_tree::parser::oid_to_name("2.5.29.14"),
                     critical: false,
                     value: "KeyIdentifier(...)".to_string(),
                 },
-                crate::models::ExtensionInfo {
+                cert_tree::models::ExtensionInfo {
                     oid: "2.5.29.17".to_string(),
-                    name: crate::parser::oid_to_name("2.5.29.17"),
+                    name: cert_tree::parser::oid_to_name("2.5.29.17"),
                     critical: false,
                     value: "GeneralNames(...)".to_string(),
                 },
             ],
             is_ca: false,
+            path_len_constraint: None,
             key_usage: Some("Digital Signature".to_string()),
+            extended_key_usage: vec!["TLS Web Server Authentication".to_string()],
+            policies: vec![],
+            name_constraints: None,
             subject_alt_names: vec!["example.com".to_string()],
+            crl_urls: vec![],
+            ocsp_urls: vec![],
+            ca_issuer_urls: vec![],
+            scts: vec![],
+            violations: vec![],
+            subject_key_id: None,
+            authority_key_id: None,
+            sha256_fingerprint: String::new(),
+            sha1_fingerprint: String::new(),
+            der_size: 0,
+            tbs_size: 0,
+            hostname_check: None,
+            tls_connection: None,
+            der: vec![],
         };
 
         // This will print to stdout, but we can't easily test output
@@ -168,11 +1086,23 @@ mod tests {
             roots: vec![CertificateNode {
                 cert: cert.clone(),
                 children: vec![],
+                role: CertificateRole::Leaf,
                 validity_status: ValidityStatus::Valid,
                 validation_status: ValidationStatus::Valid,
+                revocation_status: RevocationStatus::NotChecked,
+                trust_status: TrustStatus::NotChecked,
+                truncated: 0,
             }],
+            anchor_status: None,
         };
-        crate::display::display_certificate_tree_text(&tree);
+        display::display_certificate_tree_text(
+            &tree,
+            &mut std::io::sink(),
+            true,
+            display::TreeChars::UNICODE,
+            DisplayTimezone::Utc,
+        )
+        .unwrap();
     }
 
     #[test]
@@ -183,19 +1113,84 @@ mod tests {
             serial_number: "67890".to_string(),
             not_before: "2023-01-01".to_string(),
             not_after: "2024-01-01".to_string(),
+            not_before_dt: chrono::DateTime::default(),
+            not_after_dt: chrono::DateTime::default(),
             public_key_algorithm: "ECDSA".to_string(),
+            rsa_key_bits: None,
             signature_algorithm: "SHA256-ECDSA".to_string(),
+            signature_oid: "1.2.840.10045.4.3.2".to_string(),
             version: 3,
             extensions: vec![],
             is_ca: true,
+            path_len_constraint: None,
             key_usage: None,
+            extended_key_usage: vec![],
+            policies: vec![],
+            name_constraints: None,
             subject_alt_names: vec![],
+            crl_urls: vec![],
+            ocsp_urls: vec![],
+            ca_issuer_urls: vec![],
+            scts: vec![],
+            violations: vec![],
+            subject_key_id: None,
+            authority_key_id: None,
+            sha256_fingerprint: String::new(),
+            sha1_fingerprint: String::new(),
+            der_size: 0,
+            tbs_size: 0,
+            hostname_check: None,
+            tls_connection: None,
+            der: vec![],
         };
 
         // Test basic field access
         assert_eq!(cert.subject, "CN=test");
         assert_eq!(cert.issuer, "CN=issuer");
-        assert_eq!(cert.is_ca, true);
+        assert!(cert.is_ca);
         assert_eq!(cert.version, 3);
     }
+
+    /// Build a certificate expiring the given number of days from now
+    fn cert_expiring_in(days: i64) -> CertificateInfo {
+        let not_after = chrono::Utc::now() + chrono::Duration::days(days);
+        CertificateInfo {
+            subject: format!("CN=expires in {days}"),
+            not_after: not_after.format("%Y-%m-%d %H:%M:%S").to_string(),
+            not_after_dt: not_after,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_check_exit_code_all_valid() {
+        let certs = vec![cert_expiring_in(365), cert_expiring_in(90)];
+        assert_eq!(check_exit_code(&certs), 0);
+    }
+
+    #[test]
+    fn test_check_exit_code_expired() {
+        let certs = vec![cert_expiring_in(365), cert_expiring_in(-1)];
+        assert_eq!(check_exit_code(&certs), 1);
+    }
+
+    #[test]
+    fn test_check_exit_code_expiring_soon() {
+        let certs = vec![cert_expiring_in(365), cert_expiring_in(10)];
+        assert_eq!(check_exit_code(&certs), 2);
+    }
+
+    #[test]
+    fn test_check_exit_code_expired_outranks_expiring() {
+        let certs = vec![cert_expiring_in(10), cert_expiring_in(-30)];
+        assert_eq!(check_exit_code(&certs), 1);
+    }
+
+    #[test]
+    fn test_check_exit_code_not_yet_valid() {
+        let mut future = cert_expiring_in(365);
+        future.not_before_dt = chrono::Utc::now() + chrono::Duration::days(1);
+        let certs = vec![cert_expiring_in(10), future];
+        assert_eq!(check_exit_code(&certs), 3);
+    }
 }
@@ -1,36 +1,245 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Local, TimeZone, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use x509_parser::prelude::{FromDer, X509Certificate};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Smallest RSA modulus considered acceptable
+pub const MIN_RSA_KEY_BITS: u32 = 2048;
+
+/// Format of validity dates in human-readable output, followed by the zone
+const ZONED_DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S %Z";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct CertificateInfo {
     pub subject: String,
     pub issuer: String,
     pub serial_number: String,
+    /// `not_before` formatted for display as `YYYY-MM-DD HH:MM:SS` (UTC)
     pub not_before: String,
+    /// `not_after` formatted for display as `YYYY-MM-DD HH:MM:SS` (UTC)
     pub not_after: String,
+    /// Start of the validity period; use this rather than parsing `not_before`
+    pub not_before_dt: DateTime<Utc>,
+    /// End of the validity period; use this rather than parsing `not_after`
+    pub not_after_dt: DateTime<Utc>,
     pub public_key_algorithm: String,
+    /// RSA modulus size in bits; `None` for other key types
+    pub rsa_key_bits: Option<u32>,
     pub signature_algorithm: String,
+    /// Dotted OID of the signature algorithm, e.g. `1.2.840.113549.1.1.11`
+    pub signature_oid: String,
+    /// Raw X.509 version field (zero-based: 2 means v3)
     pub version: u32,
     pub extensions: Vec<ExtensionInfo>,
     pub is_ca: bool,
+    /// Basic Constraints `pathLenConstraint`: how many intermediate CAs may follow
+    pub path_len_constraint: Option<u32>,
     pub key_usage: Option<String>,
+    /// Extended Key Usage purposes by name, or OID for custom purposes
+    pub extended_key_usage: Vec<String>,
+    /// Certificate policies, e.g. "Domain Validated (DV)"; unknown OIDs verbatim
+    pub policies: Vec<String>,
+    /// Name Constraints of a CA certificate, when present
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name_constraints: Option<NameConstraints>,
     pub subject_alt_names: Vec<String>,
+    /// CRL distribution point URIs (HTTP, LDAP, ...)
+    pub crl_urls: Vec<String>,
+    /// OCSP responder URLs from Authority Information Access
+    pub ocsp_urls: Vec<String>,
+    /// CA Issuers URLs from Authority Information Access
+    pub ca_issuer_urls: Vec<String>,
+    /// Signed Certificate Timestamps embedded by Certificate Transparency logs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scts: Vec<SctInfo>,
+    /// RFC 5280 conformance violations detected while parsing
+    pub violations: Vec<String>,
+    /// Subject Key Identifier, colon-separated uppercase hex
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject_key_id: Option<String>,
+    /// Key identifier from the Authority Key Identifier, colon-separated uppercase hex
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authority_key_id: Option<String>,
+    /// SHA-256 fingerprint of the DER encoding, colon-separated uppercase hex
+    pub sha256_fingerprint: String,
+    /// SHA-1 fingerprint of the DER encoding, colon-separated uppercase hex
+    pub sha1_fingerprint: String,
+    /// Length of the DER encoding in bytes
+    pub der_size: usize,
+    /// Length of the signed `TBSCertificate` within the DER encoding in bytes
+    pub tbs_size: usize,
+    /// Result of matching the connected hostname, set on the leaf of a TLS fetch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname_check: Option<HostnameCheck>,
+    /// Protocol version and cipher suite negotiated, set on the leaf of a TLS fetch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_connection: Option<TlsConnectionInfo>,
+    /// Raw DER encoding, retained for signature verification
+    #[serde(skip)]
+    pub der: Vec<u8>,
 }
 
-#[derive(Debug, Clone)]
+impl CertificateInfo {
+    /// User-facing version, e.g. `v3 (0x2)` for the raw encoded value 2
+    #[must_use]
+    pub fn version_display(&self) -> String {
+        format!("v{} ({:#x})", self.version + 1, self.version)
+    }
+
+    /// `not_before_dt` in `zone`, e.g. `2025-06-01 12:00:00 UTC`
+    #[must_use]
+    pub fn not_before_text(&self, zone: DisplayTimezone) -> String {
+        zone.format(self.not_before_dt, ZONED_DATE_FORMAT)
+    }
+
+    /// `not_after_dt` in `zone`, e.g. `2025-06-01 12:00:00 UTC`
+    #[must_use]
+    pub fn not_after_text(&self, zone: DisplayTimezone) -> String {
+        zone.format(self.not_after_dt, ZONED_DATE_FORMAT)
+    }
+
+    /// Encoded size, e.g. `2134 bytes (TBS: 1872 bytes)`
+    #[must_use]
+    pub fn size_text(&self) -> String {
+        format!("{} bytes (TBS: {} bytes)", self.der_size, self.tbs_size)
+    }
+
+    /// Signed whole days until `not_after_dt`, negative once expired
+    #[must_use]
+    pub fn days_until_expiry(&self) -> i64 {
+        days_until_expiry(self.not_after_dt)
+    }
+
+    /// Expiry relative to now, e.g. "expires in 42 days" or "expired 5 days ago"
+    #[must_use]
+    pub fn expiry_text(&self) -> String {
+        describe_expiry(self.days_until_expiry())
+    }
+
+    /// Whether the signature uses a broken hash (MD5 or SHA-1)
+    #[must_use]
+    pub fn is_weak_signature(&self) -> bool {
+        SignatureStrength::from_oid(&self.signature_oid).is_weak()
+    }
+
+    /// Whether the public key is an RSA key under [`MIN_RSA_KEY_BITS`]
+    #[must_use]
+    pub fn is_weak_key(&self) -> bool {
+        self.rsa_key_bits
+            .is_some_and(|bits| bits < MIN_RSA_KEY_BITS)
+    }
+
+    /// CA flag with the path length constraint when present, e.g. `true (pathlen: 0)`
+    #[must_use]
+    pub fn is_ca_display(&self) -> String {
+        match self.path_len_constraint {
+            Some(path_len) if self.is_ca => format!("true (pathlen: {path_len})"),
+            _ => self.is_ca.to_string(),
+        }
+    }
+}
+
+/// Hash strength of a signature algorithm, ordered weakest first
+///
+/// Algorithms not recognized rank lowest so they surface as the weakest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SignatureStrength {
+    Unknown,
+    Md5,
+    Sha1,
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl SignatureStrength {
+    /// Classify a signature algorithm by its dotted OID
+    ///
+    /// RSASSA-PSS and Ed25519 rank with SHA-256, Ed448 with SHA-384.
+    #[must_use]
+    pub fn from_oid(oid: &str) -> Self {
+        match oid {
+            // md2/md4/md5WithRSAEncryption
+            "1.2.840.113549.1.1.2" | "1.2.840.113549.1.1.3" | "1.2.840.113549.1.1.4" => {
+                SignatureStrength::Md5
+            }
+            // sha1WithRSAEncryption (PKCS#1 and OIW), ecdsa-with-SHA1, dsa-with-sha1
+            "1.2.840.113549.1.1.5"
+            | "1.3.14.3.2.29"
+            | "1.2.840.10045.4.1"
+            | "1.2.840.10040.4.3" => SignatureStrength::Sha1,
+            "1.2.840.113549.1.1.14" | "1.2.840.10045.4.3.1" | "2.16.840.1.101.3.4.3.1" => {
+                SignatureStrength::Sha224
+            }
+            "1.2.840.113549.1.1.11"
+            | "1.2.840.10045.4.3.2"
+            | "2.16.840.1.101.3.4.3.2"
+            | "1.2.840.113549.1.1.10"
+            | "1.3.101.112" => SignatureStrength::Sha256,
+            "1.2.840.113549.1.1.12" | "1.2.840.10045.4.3.3" | "1.3.101.113" => {
+                SignatureStrength::Sha384
+            }
+            "1.2.840.113549.1.1.13" | "1.2.840.10045.4.3.4" => SignatureStrength::Sha512,
+            _ => SignatureStrength::Unknown,
+        }
+    }
+
+    /// Whether the hash is broken for signatures: MD5 (and older) or SHA-1
+    #[must_use]
+    pub fn is_weak(self) -> bool {
+        matches!(self, SignatureStrength::Md5 | SignatureStrength::Sha1)
+    }
+}
+
+/// Subtrees a CA may (permitted) or may not (excluded) issue for (RFC 5280 section 4.2.1.10)
+///
+/// Entries are labelled by type, e.g. `DNS:.example.com` or `IP Address:10.0.0.0/8`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct NameConstraints {
+    pub permitted: Vec<String>,
+    pub excluded: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CertificateNode {
     pub cert: CertificateInfo,
     pub children: Vec<CertificateNode>,
+    pub role: CertificateRole,
     pub validity_status: ValidityStatus,
     pub validation_status: ValidationStatus,
+    pub revocation_status: RevocationStatus,
+    #[serde(default)]
+    pub trust_status: TrustStatus,
+    /// Descendants left out below this node by a depth limit
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub truncated: usize,
 }
 
-#[derive(Debug, Clone)]
+/// Serde helper so counts that are usually zero are only written when set
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_zero(count: &usize) -> bool {
+    *count == 0
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct CertificateTree {
     pub roots: Vec<CertificateNode>,
+    /// Where the chains end, once checked against trust anchors (`--ca-bundle`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anchor_status: Option<AnchorStatus>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// JSON Schema of the `--format json` output, the chain tree
+///
+/// Each `--format ndjson` line is one `CertificateInfo`, found under the schema's definitions.
+#[must_use]
+pub fn json_output_schema() -> schemars::schema::RootSchema {
+    schemars::schema_for!(CertificateTree)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ExtensionInfo {
     pub oid: String,
     pub name: Option<String>,
@@ -41,87 +250,610 @@ pub struct ExtensionInfo {
 #[derive(Debug, Clone)]
 pub struct CertificateDisplayItem {
     pub display_name: String,
-    pub valid_until: String,
+    pub valid_until: DateTime<Utc>,
+    pub role: CertificateRole,
     pub validity_status: ValidityStatus,
     pub validation_status: ValidationStatus,
+    pub revocation_status: RevocationStatus,
+    pub trust_status: TrustStatus,
     pub certificate_info: CertificateInfo,
 }
 
-#[derive(Debug, Clone)]
+/// Whole days from now until `not_after`, negative once expired
+///
+/// Rounds down, so a certificate that expired an hour ago is at -1 rather
+/// than 0 and is never reported as still valid.
+#[must_use]
+pub fn days_until_expiry(not_after: DateTime<Utc>) -> i64 {
+    (not_after - Utc::now())
+        .num_seconds()
+        .div_euclid(SECONDS_PER_DAY)
+}
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Human-readable form of a signed day count from [`days_until_expiry`]
+#[must_use]
+pub fn describe_expiry(days: i64) -> String {
+    let plural = |n: i64| if n == 1 { "day" } else { "days" };
+    match days {
+        0 => "expires today".to_string(),
+        days if days > 0 => format!("expires in {days} {}", plural(days)),
+        days => format!("expired {} {} ago", -days, plural(-days)),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
 pub enum ValidityStatus {
     Valid,
     ExpiringSoon, // within 30 days
     Expired,
+    NotYetValid,
 }
 
 impl ValidityStatus {
-    pub fn from_dates(not_after: &str) -> Self {
-        // Try parsing as YYYY-MM-DD HH:MM:SS format first
-        if let Ok(expiry) = DateTime::parse_from_str(not_after, "%Y-%m-%d %H:%M:%S") {
-            let expiry_utc = expiry.with_timezone(&Utc);
-            let now = Utc::now();
-            let days_until_expiry = (expiry_utc - now).num_days();
-
-            if days_until_expiry < 0 {
-                ValidityStatus::Expired
-            } else if days_until_expiry <= 30 {
-                ValidityStatus::ExpiringSoon
-            } else {
-                ValidityStatus::Valid
-            }
-        } else if let Ok(expiry) = DateTime::parse_from_rfc2822(not_after) {
-            // Fallback to RFC 2822 format for backward compatibility
-            let expiry_utc = expiry.with_timezone(&Utc);
-            let now = Utc::now();
-            let days_until_expiry = (expiry_utc - now).num_days();
-
-            if days_until_expiry < 0 {
-                ValidityStatus::Expired
-            } else if days_until_expiry <= 30 {
-                ValidityStatus::ExpiringSoon
-            } else {
-                ValidityStatus::Valid
-            }
-        } else {
-            ValidityStatus::Valid // fallback if date parsing fails
+    /// Status at the current time; a `not_before` still in the future wins over expiry
+    #[must_use]
+    pub fn from_dates(not_before: DateTime<Utc>, not_after: DateTime<Utc>) -> Self {
+        if not_before > Utc::now() {
+            return ValidityStatus::NotYetValid;
+        }
+        match days_until_expiry(not_after) {
+            days if days < 0 => ValidityStatus::Expired,
+            days if days <= 30 => ValidityStatus::ExpiringSoon,
+            _ => ValidityStatus::Valid,
+        }
+    }
+
+    /// Machine-readable status name, matching the serialized form
+    #[must_use]
+    pub fn label(&self) -> &'static str {
+        match self {
+            ValidityStatus::Valid => "valid",
+            ValidityStatus::ExpiringSoon => "expiring_soon",
+            ValidityStatus::Expired => "expired",
+            ValidityStatus::NotYetValid => "not_yet_valid",
         }
     }
 
-    pub fn color(&self) -> ratatui::style::Color {
+    /// Process exit code reported for this status in `--check` mode
+    #[must_use]
+    pub fn exit_code(&self) -> i32 {
         match self {
-            ValidityStatus::Valid => ratatui::style::Color::Green,
-            ValidityStatus::ExpiringSoon => ratatui::style::Color::Yellow,
-            ValidityStatus::Expired => ratatui::style::Color::Red,
+            ValidityStatus::Valid => 0,
+            ValidityStatus::Expired => 1,
+            ValidityStatus::ExpiringSoon => 2,
+            ValidityStatus::NotYetValid => 3,
         }
     }
 
+    #[must_use]
     pub fn text(&self) -> &'static str {
         match self {
             ValidityStatus::Valid => "✓ Valid",
             ValidityStatus::ExpiringSoon => "⚠ Expiring Soon",
             ValidityStatus::Expired => "✗ Expired",
+            ValidityStatus::NotYetValid => "⧗ Not Yet Valid",
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
 pub enum ValidationStatus {
     Valid,
-    InvalidChain,
+    /// The link to the issuer is broken, e.g. "issuer not found" or "issuer is not a CA"
+    InvalidChain(String),
+    /// Validation was skipped with `--no-validate`
+    NotChecked,
 }
 
 impl ValidationStatus {
+    #[must_use]
     pub fn text(&self) -> &'static str {
         match self {
             ValidationStatus::Valid => "✓ Valid Chain",
-            ValidationStatus::InvalidChain => "✗ Invalid Chain",
+            ValidationStatus::InvalidChain(_) => "✗ Invalid Chain",
+            ValidationStatus::NotChecked => "Not Checked",
+        }
+    }
+
+    /// Why the chain is invalid, if it is
+    #[must_use]
+    pub fn reason(&self) -> Option<&str> {
+        match self {
+            ValidationStatus::InvalidChain(reason) => Some(reason),
+            ValidationStatus::Valid | ValidationStatus::NotChecked => None,
+        }
+    }
+}
+
+/// OCSP revocation status of a certificate
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RevocationStatus {
+    #[default]
+    NotChecked,
+    Good,
+    Revoked,
+    Unknown,
+}
+
+impl RevocationStatus {
+    #[must_use]
+    pub fn text(&self) -> &'static str {
+        match self {
+            RevocationStatus::NotChecked => "Not Checked",
+            RevocationStatus::Good => "✓ Good",
+            RevocationStatus::Revoked => "✗ Revoked",
+            RevocationStatus::Unknown => "? Unknown",
+        }
+    }
+}
+
+/// Position of a certificate in its chain, from the CA flag, self-issuance and
+/// whether its issuer is among the loaded certificates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CertificateRole {
+    /// Self-issued CA certificate
+    RootCa,
+    /// Self-issued end-entity certificate
+    SelfSigned,
+    /// CA certificate whose issuer is present
+    Intermediate,
+    /// CA certificate whose issuer is missing from the input
+    OrphanIntermediate,
+    /// End-entity certificate
+    Leaf,
+}
+
+impl CertificateRole {
+    #[must_use]
+    pub fn classify(cert: &CertificateInfo, issuer_present: bool) -> Self {
+        match (cert.subject == cert.issuer, cert.is_ca, issuer_present) {
+            (true, true, _) => CertificateRole::RootCa,
+            (true, false, _) => CertificateRole::SelfSigned,
+            (false, true, true) => CertificateRole::Intermediate,
+            (false, true, false) => CertificateRole::OrphanIntermediate,
+            (false, false, _) => CertificateRole::Leaf,
+        }
+    }
+
+    #[must_use]
+    pub fn text(&self) -> &'static str {
+        match self {
+            CertificateRole::RootCa => "Root CA",
+            CertificateRole::SelfSigned => "Self-Signed",
+            CertificateRole::Intermediate => "Intermediate CA",
+            CertificateRole::OrphanIntermediate => "Intermediate CA (issuer missing)",
+            CertificateRole::Leaf => "Leaf",
+        }
+    }
+}
+
+/// Whether the top of a chain terminates at a trusted root (`--ca-bundle`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TrustStatus {
+    /// No trust anchors were supplied, or the certificate is not a chain top
+    #[default]
+    NotChecked,
+    Anchored,
+    Untrusted,
+}
+
+impl TrustStatus {
+    #[must_use]
+    pub fn text(&self) -> &'static str {
+        match self {
+            TrustStatus::NotChecked => "Not Checked",
+            TrustStatus::Anchored => "✓ Anchored",
+            TrustStatus::Untrusted => "✗ Untrusted",
         }
     }
+}
+
+/// Where the chains of a tree terminate relative to the trust store (`--ca-bundle`)
+///
+/// With several chains the tree takes the worst: an untrusted root over an
+/// incomplete chain over a trusted root.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AnchorStatus {
+    /// Every chain ends at, or was issued by, a root in the trust store
+    TrustedRoot,
+    /// A chain ends at a self-issued root the trust store does not hold
+    UntrustedRoot,
+    /// A chain ends at a certificate whose issuer is neither loaded nor trusted
+    IncompleteChain,
+}
+
+impl AnchorStatus {
+    #[must_use]
+    pub fn text(&self) -> &'static str {
+        match self {
+            AnchorStatus::TrustedRoot => "✓ Trusted Root",
+            AnchorStatus::UntrustedRoot => "✗ Untrusted Root",
+            AnchorStatus::IncompleteChain => "⚠ Incomplete Chain",
+        }
+    }
+}
+
+/// A root the trust store accepts, reduced to what a TLS trust store keeps of it
+///
+/// Built-in roots carry no full certificate, so chains are matched against the
+/// subject name and public key rather than the certificate's DER.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrustAnchor {
+    /// DER-encoded subject `Name`
+    pub subject_der: Vec<u8>,
+    /// DER-encoded `SubjectPublicKeyInfo`
+    pub spki_der: Vec<u8>,
+}
+
+impl TrustAnchor {
+    /// The anchor a root certificate provides, or `None` if its DER does not parse
+    #[must_use]
+    pub fn from_certificate(der: &[u8]) -> Option<Self> {
+        let (_, cert) = X509Certificate::from_der(der).ok()?;
+        Some(Self {
+            subject_der: cert.subject().as_raw().to_vec(),
+            spki_der: cert.public_key().raw.to_vec(),
+        })
+    }
+}
+
+/// Whether a certificate is valid for the hostname it was fetched from
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HostnameMatch {
+    Match,
+    Mismatch,
+}
+
+/// A Signed Certificate Timestamp: a CT log's promise to publish the certificate
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SctInfo {
+    /// Base64 log ID, the SHA-256 hash of the log's public key
+    pub log_id: String,
+    /// Description of a well-known log, e.g. "Google 'Pilot' log"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_name: Option<String>,
+    /// Organization running a well-known log
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operator: Option<String>,
+    /// When the log issued the timestamp
+    pub timestamp: DateTime<Utc>,
+    /// Hash of the log's signature, e.g. "SHA-256"
+    pub hash_algorithm: String,
+    /// Algorithm of the log's signature, e.g. "ECDSA"
+    pub signature_algorithm: String,
+}
+
+impl SctInfo {
+    /// The log (by name and operator when known, else its ID), time and signature algorithms
+    #[must_use]
+    pub fn text(&self) -> String {
+        let log = match (&self.log_name, &self.operator) {
+            (Some(name), Some(operator)) => format!("{name} ({operator})"),
+            _ => self.log_id.clone(),
+        };
+        format!(
+            "{log}, {}, {}/{}",
+            self.timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
+            self.hash_algorithm,
+            self.signature_algorithm
+        )
+    }
+}
 
-    pub fn color(&self) -> ratatui::style::Color {
+/// Time zone of the dates in human-readable output (`--timezone`)
+///
+/// CSV, JSON and YAML (also with `--fields`) keep their UTC strings and timestamps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DisplayTimezone {
+    /// Coordinated Universal Time, shown as `UTC`
+    #[default]
+    Utc,
+    /// The system zone (`TZ` or `/etc/localtime`), shown as its UTC offset, e.g. `+02:00`
+    Local,
+}
+
+impl DisplayTimezone {
+    /// `time` converted to this zone and formatted with chrono's `format`; `%Z` names the zone
+    #[must_use]
+    pub fn format(self, time: DateTime<Utc>, format: &str) -> String {
+        match self {
+            DisplayTimezone::Utc => format_in_zone(time, &Utc, format),
+            DisplayTimezone::Local => format_in_zone(time, &Local, format),
+        }
+    }
+}
+
+fn format_in_zone<Tz: TimeZone>(time: DateTime<Utc>, zone: &Tz, format: &str) -> String
+where
+    Tz::Offset: fmt::Display,
+{
+    time.with_timezone(zone).format(format).to_string()
+}
+
+/// Digest shown by `--fingerprint`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FingerprintAlgorithm {
+    Sha256,
+    Sha1,
+}
+
+impl FingerprintAlgorithm {
+    /// The certificate's fingerprint with this digest, colon-separated uppercase hex
+    #[must_use]
+    pub fn of(self, cert: &CertificateInfo) -> &str {
         match self {
-            ValidationStatus::Valid => ratatui::style::Color::Green,
-            ValidationStatus::InvalidChain => ratatui::style::Color::Red,
+            FingerprintAlgorithm::Sha256 => &cert.sha256_fingerprint,
+            FingerprintAlgorithm::Sha1 => &cert.sha1_fingerprint,
         }
     }
 }
+
+/// TLS parameters negotiated in the handshake a chain was read from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TlsConnectionInfo {
+    /// Protocol version, e.g. "TLS 1.3"
+    pub protocol: String,
+    /// IANA cipher suite name, e.g. `TLS13_AES_256_GCM_SHA384`
+    pub cipher_suite: String,
+    /// Time from the `ClientHello` to the finished handshake, in milliseconds
+    pub handshake_ms: f64,
+    /// Number of certificates the server presented
+    pub certificate_count: usize,
+    /// Total DER size of the presented certificates
+    pub certificate_bytes: usize,
+    /// Why the chain failed standard verification (trust, validity or hostname);
+    /// `None` if it passed
+    pub verification_error: Option<String>,
+}
+
+impl TlsConnectionInfo {
+    /// One-line form: the protocol version, then the cipher suite
+    #[must_use]
+    pub fn text(&self) -> String {
+        format!("{}, {}", self.protocol, self.cipher_suite)
+    }
+
+    /// Standard verification outcome, e.g. "✓ Trusted" or "✗ invalid peer certificate: `UnknownIssuer`"
+    #[must_use]
+    pub fn verification_text(&self) -> String {
+        match &self.verification_error {
+            None => "✓ Trusted".to_string(),
+            Some(error) => format!("✗ {error}"),
+        }
+    }
+
+    /// Handshake diagnostics, e.g. "12.3 ms, 3 certificates (4217 bytes)"
+    #[must_use]
+    pub fn handshake_text(&self) -> String {
+        let plural = if self.certificate_count == 1 { "" } else { "s" };
+        format!(
+            "{:.1} ms, {} certificate{plural} ({} bytes)",
+            self.handshake_ms, self.certificate_count, self.certificate_bytes
+        )
+    }
+}
+
+/// Hostname verification outcome together with the hostname that was checked
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HostnameCheck {
+    pub hostname: String,
+    pub status: HostnameMatch,
+}
+
+impl HostnameCheck {
+    #[must_use]
+    pub fn text(&self) -> String {
+        match self.status {
+            HostnameMatch::Match => format!("✓ Matches {}", self.hostname),
+            HostnameMatch::Mismatch => format!("✗ Does not match {}", self.hostname),
+        }
+    }
+}
+
+/// Overall health of a certificate tree, printed as one `key=value` line by `--summary`
+#[derive(Debug, Clone)]
+pub struct ChainSummary {
+    /// `Valid` only when every certificate in the tree is
+    pub chain: ValidationStatus,
+    /// CN of the deepest certificate, normally the server's leaf
+    pub leaf_cn: String,
+    /// Days until the soonest-expiring certificate expires, negative once expired
+    pub expires_in_days: i64,
+    /// Weakest signature algorithm in the tree, e.g. `SHA256-RSA`
+    pub weakest_sig: String,
+    /// Number of certificates in the longest issuer path
+    pub depth: usize,
+}
+
+impl fmt::Display for ChainSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let chain = match self.chain {
+            ValidationStatus::Valid => "valid",
+            ValidationStatus::InvalidChain(_) => "invalid",
+            ValidationStatus::NotChecked => "not_checked",
+        };
+        write!(
+            f,
+            "chain={chain} leaf_cn={} expires_in_days={} weakest_sig={} depth={}",
+            summary_value(&self.leaf_cn),
+            self.expires_in_days,
+            summary_value(&self.weakest_sig),
+            self.depth
+        )
+    }
+}
+
+/// Quote a summary value containing spaces, quotes or `=` so the line stays splittable
+fn summary_value(value: &str) -> String {
+    if value.is_empty() || value.contains([' ', '"', '=']) {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_signature_strength_by_oid() {
+        let md5 = SignatureStrength::from_oid("1.2.840.113549.1.1.4");
+        let sha1_ecdsa = SignatureStrength::from_oid("1.2.840.10045.4.1");
+        let ed25519 = SignatureStrength::from_oid("1.3.101.112");
+        let sha384_rsa = SignatureStrength::from_oid("1.2.840.113549.1.1.12");
+        let unknown = SignatureStrength::from_oid("1.2.3.4");
+
+        assert!(md5.is_weak() && sha1_ecdsa.is_weak());
+        assert!(!ed25519.is_weak() && !unknown.is_weak());
+        assert!(unknown < md5 && md5 < sha1_ecdsa && sha1_ecdsa < ed25519 && ed25519 < sha384_rsa);
+    }
+
+    #[test]
+    fn test_dates_render_in_chosen_zone() {
+        let time = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let cert = CertificateInfo {
+            not_after_dt: time,
+            ..Default::default()
+        };
+        assert_eq!(
+            cert.not_after_text(DisplayTimezone::Utc),
+            "2025-06-01 12:00:00 UTC"
+        );
+
+        let zone = chrono::FixedOffset::east_opt(2 * 3600).unwrap();
+        assert_eq!(
+            format_in_zone(time, &zone, ZONED_DATE_FORMAT),
+            "2025-06-01 14:00:00 +02:00"
+        );
+        assert_eq!(
+            DisplayTimezone::Utc.format(time, "%H:%M"),
+            format_in_zone(time, &Utc, "%H:%M")
+        );
+    }
+
+    #[test]
+    fn test_same_certificate_renders_in_local_and_utc() {
+        // The process environment (TZ) is shared by parallel tests, so the local
+        // rendering is checked against the host zone and a fixed offset stands in for it
+        let time = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let cert = CertificateInfo {
+            not_before_dt: time,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            cert.not_before_text(DisplayTimezone::Utc),
+            "2025-06-01 12:00:00 UTC"
+        );
+        assert_eq!(
+            cert.not_before_text(DisplayTimezone::Local),
+            format_in_zone(time, &Local, ZONED_DATE_FORMAT)
+        );
+        let tokyo = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
+        assert_eq!(
+            format_in_zone(time, &tokyo, ZONED_DATE_FORMAT),
+            "2025-06-01 21:00:00 +09:00"
+        );
+    }
+
+    #[test]
+    fn test_json_output_schema_describes_certificate() {
+        let text = serde_json::to_string(&json_output_schema()).unwrap();
+        let schema: serde_json::Value = serde_json::from_str(&text).unwrap();
+
+        let certificate = &schema["definitions"]["CertificateInfo"];
+        assert_eq!(certificate["properties"]["subject"]["type"], "string");
+        assert!(certificate["properties"].get("der").is_none());
+        assert_eq!(schema["title"], "CertificateTree");
+        assert!(schema["properties"]["roots"].is_object());
+    }
+
+    #[test]
+    fn test_version_display_is_one_based() {
+        let cert = CertificateInfo {
+            version: 2,
+            ..Default::default()
+        };
+        assert_eq!(cert.version_display(), "v3 (0x2)");
+
+        let cert = CertificateInfo {
+            version: 0,
+            ..Default::default()
+        };
+        assert_eq!(cert.version_display(), "v1 (0x0)");
+    }
+
+    #[test]
+    fn test_is_ca_display_includes_path_len() {
+        let cert = CertificateInfo {
+            is_ca: true,
+            path_len_constraint: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(cert.is_ca_display(), "true (pathlen: 0)");
+
+        let cert = CertificateInfo {
+            is_ca: true,
+            ..Default::default()
+        };
+        assert_eq!(cert.is_ca_display(), "true");
+    }
+
+    #[test]
+    fn test_expiry_text_future() {
+        let cert = CertificateInfo {
+            not_after_dt: Utc::now() + Duration::days(42) + Duration::hours(1),
+            ..Default::default()
+        };
+        assert_eq!(cert.days_until_expiry(), 42);
+        assert_eq!(cert.expiry_text(), "expires in 42 days");
+    }
+
+    #[test]
+    fn test_expiry_text_past() {
+        let cert = CertificateInfo {
+            not_after_dt: Utc::now() - Duration::days(5) + Duration::hours(1),
+            ..Default::default()
+        };
+        assert_eq!(cert.days_until_expiry(), -5);
+        assert_eq!(cert.expiry_text(), "expired 5 days ago");
+    }
+
+    #[test]
+    fn test_recently_expired_is_not_valid() {
+        let not_after = Utc::now() - Duration::hours(1);
+        assert_eq!(days_until_expiry(not_after), -1);
+        assert!(matches!(
+            ValidityStatus::from_dates(DateTime::UNIX_EPOCH, not_after),
+            ValidityStatus::Expired
+        ));
+        assert_eq!(describe_expiry(1), "expires in 1 day");
+        assert_eq!(describe_expiry(0), "expires today");
+    }
+
+    #[test]
+    fn test_future_not_before_is_not_yet_valid() {
+        let cert = CertificateInfo {
+            not_before_dt: Utc::now() + Duration::days(1),
+            not_after_dt: Utc::now() + Duration::days(365),
+            ..Default::default()
+        };
+        let status = ValidityStatus::from_dates(cert.not_before_dt, cert.not_after_dt);
+        assert_eq!(status, ValidityStatus::NotYetValid);
+        assert_eq!(status.text(), "⧗ Not Yet Valid");
+
+        let started = ValidityStatus::from_dates(Utc::now() - Duration::days(1), cert.not_after_dt);
+        assert_eq!(started, ValidityStatus::Valid);
+    }
+}
@@ -0,0 +1,596 @@
+//! OCSP revocation checking
+//!
+//! Builds a minimal RFC 6960 `OCSPRequest` for a certificate/issuer pair, POSTs it to the
+//! certificate's OCSP responder and reads the matching `SingleResponse` status. A reply
+//! counts only if it is signed by the issuer or by a responder the issuer authorized.
+
+use crate::io::{http_client, FetchOptions};
+use crate::models::{CertificateInfo, RevocationStatus};
+use sha1::{Digest, Sha1};
+use std::time::Duration;
+use tracing::debug;
+use x509_parser::der_parser::asn1_rs::{
+    Any, BitString, Class, Enumerated, Integer, Null, OctetString, Sequence, Tag, ToDer,
+};
+use x509_parser::oid_registry::OID_HASH_SHA1;
+use x509_parser::prelude::{
+    ASN1Time, AlgorithmIdentifier, FromDer, SubjectPublicKeyInfo, X509Certificate,
+};
+use x509_parser::verify::verify_signature;
+
+/// `certStatus` tag for `good [0] IMPLICIT NULL`
+const CERT_STATUS_GOOD: Tag = Tag(0);
+
+/// `certStatus` tag for `revoked [1] IMPLICIT RevokedInfo`
+const CERT_STATUS_REVOKED: Tag = Tag(1);
+
+/// Tag of the `certs [0] EXPLICIT SEQUENCE OF Certificate` in a `BasicOCSPResponse`
+const BASIC_RESPONSE_CERTS: Tag = Tag(0);
+
+/// Tag of the `nextUpdate [0] EXPLICIT GeneralizedTime` in a `SingleResponse`
+const SINGLE_RESPONSE_NEXT_UPDATE: Tag = Tag(0);
+
+/// Clock difference tolerated between the responder and this machine
+const OCSP_CLOCK_SKEW_SECS: i64 = 300;
+
+/// Timeout in seconds for OCSP responder requests
+const OCSP_TIMEOUT_SECS: u64 = 10;
+
+/// DER `SEQUENCE` of the already-encoded `items`
+fn der_sequence(items: &[Vec<u8>]) -> Option<Vec<u8>> {
+    Sequence::new(items.concat().into()).to_der_vec().ok()
+}
+
+/// Read one DER value, returning it and the input after it
+fn next_value(input: &[u8]) -> Option<(Any<'_>, &[u8])> {
+    let (rest, value) = Any::from_der(input).ok()?;
+    Some((value, rest))
+}
+
+/// Read one universal DER value with the given `tag`, returning its content and the input after it
+fn expect(input: &[u8], tag: Tag) -> Option<(&[u8], &[u8])> {
+    let (value, rest) = next_value(input)?;
+    (value.header.class() == Class::Universal && value.header.tag() == tag)
+        .then_some((value.data, rest))
+}
+
+/// Build the DER `CertID` identifying `cert` as issued by `issuer`
+fn build_cert_id(cert: &X509Certificate, issuer: &X509Certificate) -> Option<Vec<u8>> {
+    let issuer_name_hash = Sha1::digest(issuer.subject().as_raw());
+    let issuer_key_hash = Sha1::digest(&issuer.public_key().subject_public_key.data);
+
+    let hash_algorithm = der_sequence(&[
+        OID_HASH_SHA1.to_der_vec().ok()?,
+        Null::new().to_der_vec().ok()?,
+    ])?;
+    der_sequence(&[
+        hash_algorithm,
+        OctetString::new(&issuer_name_hash).to_der_vec().ok()?,
+        OctetString::new(&issuer_key_hash).to_der_vec().ok()?,
+        Integer::new(cert.raw_serial()).to_der_vec().ok()?,
+    ])
+}
+
+/// Build a DER-encoded `OCSPRequest` asking about the single certificate `cert_id` names
+fn build_ocsp_request(cert_id: &[u8]) -> Option<Vec<u8>> {
+    let request = der_sequence(&[cert_id.to_vec()])?;
+    let request_list = der_sequence(&[request])?;
+    let tbs_request = der_sequence(&[request_list])?;
+    der_sequence(&[tbs_request])
+}
+
+/// The parts of a `BasicOCSPResponse` needed to authenticate and read it
+struct BasicResponse<'a> {
+    /// Complete DER of `tbsResponseData`, the signed bytes
+    tbs_response_data: &'a [u8],
+    signature_algorithm: AlgorithmIdentifier<'a>,
+    signature: BitString<'a>,
+    /// Certificates the responder included, e.g. its own delegated signing certificate
+    certs: Vec<X509Certificate<'a>>,
+}
+
+impl<'a> BasicResponse<'a> {
+    /// Unwrap a DER `OCSPResponse` down to its `BasicOCSPResponse`
+    ///
+    /// `None` unless the response status is `successful` and the body parses.
+    fn from_ocsp_response(data: &'a [u8]) -> Option<Self> {
+        // OCSPResponse ::= SEQUENCE { responseStatus ENUMERATED, responseBytes [0] EXPLICIT ... }
+        let (response, _) = expect(data, Tag::Sequence)?;
+        let (rest, status) = Enumerated::from_der(response).ok()?;
+        if status.0 != 0 {
+            return None;
+        }
+
+        // ResponseBytes ::= SEQUENCE { responseType OBJECT IDENTIFIER, response OCTET STRING }
+        let (response_bytes, _) = next_value(rest)?;
+        let (response_bytes, _) = expect(response_bytes.data, Tag::Sequence)?;
+        let (_, rest) = expect(response_bytes, Tag::Oid)?;
+        let (basic, _) = expect(rest, Tag::OctetString)?;
+
+        // BasicOCSPResponse ::= SEQUENCE { tbsResponseData, signatureAlgorithm, signature, certs }
+        let (basic, _) = expect(basic, Tag::Sequence)?;
+        let (_, rest) = expect(basic, Tag::Sequence)?;
+        let tbs_response_data = &basic[..basic.len() - rest.len()];
+        let (rest, signature_algorithm) = AlgorithmIdentifier::from_der(rest).ok()?;
+        let (rest, signature) = BitString::from_der(rest).ok()?;
+
+        let mut certs = Vec::new();
+        if let Some((tagged, _)) = next_value(rest) {
+            if tagged.header.tag() == BASIC_RESPONSE_CERTS {
+                let (mut der, _) = expect(tagged.data, Tag::Sequence)?;
+                while !der.is_empty() {
+                    let (rest, cert) = X509Certificate::from_der(der).ok()?;
+                    certs.push(cert);
+                    der = rest;
+                }
+            }
+        }
+
+        Some(BasicResponse {
+            tbs_response_data,
+            signature_algorithm,
+            signature,
+            certs,
+        })
+    }
+
+    /// Whether `key` produced the signature over `tbsResponseData`
+    fn signed_by(&self, key: &SubjectPublicKeyInfo) -> bool {
+        verify_signature(
+            key,
+            &self.signature_algorithm,
+            &self.signature,
+            self.tbs_response_data,
+        )
+        .is_ok()
+    }
+
+    /// Whether the response is signed by `issuer` or by a responder `issuer` authorized
+    ///
+    /// A delegated responder must be an included certificate signed by `issuer`,
+    /// currently valid and carrying `id-kp-OCSPSigning` (RFC 6960 section 4.2.2.2).
+    fn is_authorized_by(&self, issuer: &X509Certificate) -> bool {
+        if self.signed_by(issuer.public_key()) {
+            return true;
+        }
+        self.certs.iter().any(|responder| {
+            responder.issuer() == issuer.subject()
+                && responder
+                    .verify_signature(Some(issuer.public_key()))
+                    .is_ok()
+                && responder.validity().is_valid()
+                && matches!(responder.extended_key_usage(), Ok(Some(eku)) if eku.value.ocsp_signing)
+                && self.signed_by(responder.public_key())
+        })
+    }
+
+    /// Status of the `SingleResponse` for `cert_id`, if the response has one
+    fn status_of(&self, cert_id: &[u8]) -> Option<RevocationStatus> {
+        // ResponseData: the first universal SEQUENCE after the optional version,
+        // responderID and producedAt is `responses`
+        let (mut fields, _) = expect(self.tbs_response_data, Tag::Sequence)?;
+        let mut responses = None;
+        while !fields.is_empty() {
+            let (field, rest) = next_value(fields)?;
+            if field.header.class() == Class::Universal && field.header.tag() == Tag::Sequence {
+                responses = Some(field.data);
+                break;
+            }
+            fields = rest;
+        }
+
+        let mut responses = responses?;
+        while !responses.is_empty() {
+            let (single, rest) = expect(responses, Tag::Sequence)?;
+            responses = rest;
+
+            // SingleResponse ::= SEQUENCE { certID, certStatus, thisUpdate, nextUpdate, ... }
+            let (_, after_cert_id) = expect(single, Tag::Sequence)?;
+            if &single[..single.len() - after_cert_id.len()] != cert_id {
+                continue;
+            }
+            let (status, after_status) = next_value(after_cert_id)?;
+            let (after_this_update, this_update) = ASN1Time::from_der(after_status).ok()?;
+            let next_update = next_value(after_this_update)
+                .filter(|(field, _)| {
+                    field.header.class() == Class::ContextSpecific
+                        && field.header.tag() == SINGLE_RESPONSE_NEXT_UPDATE
+                })
+                .and_then(|(field, _)| ASN1Time::from_der(field.data).ok())
+                .map(|(_, time)| time);
+            if !is_current(this_update, next_update, ASN1Time::now()) {
+                debug!(%this_update, ?next_update, "OCSP response is not current");
+                return Some(RevocationStatus::Unknown);
+            }
+            return Some(match status.header.tag() {
+                CERT_STATUS_GOOD => RevocationStatus::Good,
+                CERT_STATUS_REVOKED => RevocationStatus::Revoked,
+                _ => RevocationStatus::Unknown,
+            });
+        }
+        None
+    }
+}
+
+/// Whether a response issued at `this_update` and valid until `next_update` holds at `now`
+///
+/// RFC 6960 section 3.2: a `thisUpdate` in the future or a `nextUpdate` in the
+/// past makes the response unreliable, whatever its status says. Without a
+/// `nextUpdate` the responder always has newer information, so any past
+/// `thisUpdate` is accepted.
+fn is_current(this_update: ASN1Time, next_update: Option<ASN1Time>, now: ASN1Time) -> bool {
+    let now = now.timestamp();
+    this_update.timestamp() <= now + OCSP_CLOCK_SKEW_SECS
+        && next_update
+            .is_none_or(|next_update| next_update.timestamp() >= now - OCSP_CLOCK_SKEW_SECS)
+}
+
+/// Read the status for `cert_id` from a DER `OCSPResponse` about a certificate `issuer` issued
+///
+/// `None` if the response is malformed, unsuccessful, not signed by an authorized
+/// responder, or says nothing about `cert_id`.
+fn parse_ocsp_response(
+    data: &[u8],
+    cert_id: &[u8],
+    issuer: &X509Certificate,
+) -> Option<RevocationStatus> {
+    let response = BasicResponse::from_ocsp_response(data)?;
+    if !response.is_authorized_by(issuer) {
+        debug!("OCSP response is not signed by the issuer or an authorized responder");
+        return None;
+    }
+    response.status_of(cert_id)
+}
+
+/// Query the certificate's OCSP responder for its revocation status
+///
+/// A certificate without a responder URL stays `NotChecked`. Any other failure
+/// (network error, timeout, malformed or unauthenticated response) yields
+/// `RevocationStatus::Unknown`. The request honours the proxy in `options`.
+#[must_use]
+pub fn check_ocsp_status(
+    cert: &CertificateInfo,
+    issuer: &CertificateInfo,
+    options: &FetchOptions,
+) -> RevocationStatus {
+    let Some(url) = cert.ocsp_urls.first() else {
+        return RevocationStatus::NotChecked;
+    };
+    let (Ok((_, parsed_cert)), Ok((_, parsed_issuer))) = (
+        X509Certificate::from_der(&cert.der),
+        X509Certificate::from_der(&issuer.der),
+    ) else {
+        return RevocationStatus::Unknown;
+    };
+    let Some(cert_id) = build_cert_id(&parsed_cert, &parsed_issuer) else {
+        return RevocationStatus::Unknown;
+    };
+    let Some(request) = build_ocsp_request(&cert_id) else {
+        return RevocationStatus::Unknown;
+    };
+    let Ok(client) = http_client(options) else {
+        return RevocationStatus::Unknown;
+    };
+
+    let response = client
+        .post(url)
+        .header("Content-Type", "application/ocsp-request")
+        .timeout(Duration::from_secs(OCSP_TIMEOUT_SECS))
+        .body(request)
+        .send()
+        .and_then(reqwest::blocking::Response::bytes);
+
+    match response {
+        Ok(body) => parse_ocsp_response(&body, &cert_id, &parsed_issuer)
+            .unwrap_or(RevocationStatus::Unknown),
+        Err(e) => {
+            debug!(url = %url, error = %e, "OCSP request failed");
+            RevocationStatus::Unknown
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_certificate_chain;
+    use rustls::SignatureScheme;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use x509_parser::der_parser::asn1_rs::{Header, Length};
+    use x509_parser::oid_registry::{OID_PKCS1_SHA256WITHRSA, OID_SIG_ECDSA_WITH_SHA256};
+
+    /// The issuer in these tests; `test/leaf.key` is its key
+    const ISSUER_PEM: &[u8] = include_bytes!("../test/leaf.pem");
+    const ISSUER_KEY: &[u8] = include_bytes!("../test/leaf.key");
+    /// Checked certificate, issued by `ISSUER_PEM`
+    const CERT_PEM: &[u8] = include_bytes!("../test/issued_by_leaf.pem");
+    /// Delegated responder issued by `ISSUER_PEM` with `id-kp-OCSPSigning`, keyed with `RESPONDER_KEY`
+    const RESPONDER_PEM: &[u8] = include_bytes!("../test/ocsp_responder.pem");
+    const RESPONDER_KEY: &[u8] = include_bytes!("../test/unrelated.key");
+
+    /// DER value of any class and tag around `content`
+    fn tlv(class: Class, constructed: bool, tag: u32, content: &[u8]) -> Vec<u8> {
+        let header = Header::new(
+            class,
+            constructed,
+            Tag(tag),
+            Length::Definite(content.len()),
+        );
+        Any::new(header, content).to_der_vec().unwrap()
+    }
+
+    /// Sign `data` with a PEM private key, returning the `AlgorithmIdentifier` and signature
+    fn sign(key_pem: &[u8], data: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        let key = rustls::PrivateKey(pem::parse(key_pem).unwrap().into_contents());
+        let signer = rustls::sign::any_supported_type(&key)
+            .unwrap()
+            .choose_scheme(&[
+                SignatureScheme::RSA_PKCS1_SHA256,
+                SignatureScheme::ECDSA_NISTP256_SHA256,
+            ])
+            .unwrap();
+        let algorithm = match signer.scheme() {
+            SignatureScheme::RSA_PKCS1_SHA256 => der_sequence(&[
+                OID_PKCS1_SHA256WITHRSA.to_der_vec().unwrap(),
+                Null::new().to_der_vec().unwrap(),
+            ]),
+            _ => der_sequence(&[OID_SIG_ECDSA_WITH_SHA256.to_der_vec().unwrap()]),
+        };
+        (algorithm.unwrap(), signer.sign(data).unwrap())
+    }
+
+    /// Build a successful `OCSPResponse` with one `SingleResponse` carrying `status`
+    ///
+    /// Signed with `key_pem`, and including the DER `certs` for a delegated responder.
+    fn ocsp_response(cert_id: &[u8], status: &[u8], key_pem: &[u8], certs: &[Vec<u8>]) -> Vec<u8> {
+        dated_ocsp_response(cert_id, status, "20250101000000Z", None, key_pem, certs)
+    }
+
+    /// [`ocsp_response`] with the given `thisUpdate` and optional `nextUpdate` (`YYYYMMDDHHMMSSZ`)
+    fn dated_ocsp_response(
+        cert_id: &[u8],
+        status: &[u8],
+        this_update: &str,
+        next_update: Option<&str>,
+        key_pem: &[u8],
+        certs: &[Vec<u8>],
+    ) -> Vec<u8> {
+        let this_update = tlv(Class::Universal, false, 24, this_update.as_bytes());
+        let mut single = vec![cert_id.to_vec(), status.to_vec(), this_update.clone()];
+        if let Some(next_update) = next_update {
+            let time = tlv(Class::Universal, false, 24, next_update.as_bytes());
+            single.push(tlv(
+                Class::ContextSpecific,
+                true,
+                SINGLE_RESPONSE_NEXT_UPDATE.0,
+                &time,
+            ));
+        }
+        let single = der_sequence(&single);
+        let tbs = der_sequence(&[
+            tlv(
+                Class::ContextSpecific,
+                true,
+                2,
+                &OctetString::new(&[0; 20]).to_der_vec().unwrap(),
+            ),
+            this_update,
+            der_sequence(&[single.unwrap()]).unwrap(),
+        ])
+        .unwrap();
+
+        let (algorithm, signature) = sign(key_pem, &tbs);
+        let mut basic = vec![
+            tbs,
+            algorithm,
+            BitString::new(0, &signature).to_der_vec().unwrap(),
+        ];
+        if !certs.is_empty() {
+            basic.push(tlv(
+                Class::ContextSpecific,
+                true,
+                0,
+                &der_sequence(certs).unwrap(),
+            ));
+        }
+        let basic = der_sequence(&basic).unwrap();
+
+        // id-pkix-ocsp-basic
+        let response_type = Tag::Oid.0;
+        let response_bytes = der_sequence(&[
+            tlv(
+                Class::Universal,
+                false,
+                response_type,
+                &[0x2B, 0x06, 0x01, 0x05, 0x05, 0x07, 0x30, 0x01, 0x01],
+            ),
+            OctetString::new(&basic).to_der_vec().unwrap(),
+        ])
+        .unwrap();
+
+        der_sequence(&[
+            Enumerated::new(0).to_der_vec().unwrap(),
+            tlv(Class::ContextSpecific, true, 0, &response_bytes),
+        ])
+        .unwrap()
+    }
+
+    /// `certStatus` of `good`
+    fn good() -> Vec<u8> {
+        tlv(Class::ContextSpecific, false, CERT_STATUS_GOOD.0, &[])
+    }
+
+    /// `certStatus` of `revoked` with a revocation time
+    fn revoked() -> Vec<u8> {
+        let revocation_time = tlv(Class::Universal, false, 24, b"20250601000000Z");
+        tlv(
+            Class::ContextSpecific,
+            true,
+            CERT_STATUS_REVOKED.0,
+            &revocation_time,
+        )
+    }
+
+    /// Serve a single HTTP response with the given body on a local port
+    fn mock_responder(body: Vec<u8>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buffer = [0u8; 4096];
+            let _ = stream.read(&mut buffer);
+            let header = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/ocsp-response\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            stream.write_all(header.as_bytes()).unwrap();
+            stream.write_all(&body).unwrap();
+        });
+
+        format!("http://{addr}/")
+    }
+
+    /// The checked certificate and its issuer, with the `CertID` of the pair
+    fn checked_pair() -> (CertificateInfo, CertificateInfo, Vec<u8>) {
+        let cert = parse_certificate_chain(CERT_PEM).unwrap().remove(0);
+        let issuer = parse_certificate_chain(ISSUER_PEM).unwrap().remove(0);
+        let cert_id = build_cert_id(
+            &X509Certificate::from_der(&cert.der).unwrap().1,
+            &X509Certificate::from_der(&issuer.der).unwrap().1,
+        )
+        .unwrap();
+        (cert, issuer, cert_id)
+    }
+
+    /// Check `cert` against a mock responder answering with `body`
+    fn check_with_response(
+        cert: &CertificateInfo,
+        issuer: &CertificateInfo,
+        body: Vec<u8>,
+    ) -> RevocationStatus {
+        let mut cert = cert.clone();
+        cert.ocsp_urls = vec![mock_responder(body)];
+        check_ocsp_status(&cert, issuer, &FetchOptions::default())
+    }
+
+    #[test]
+    fn test_ocsp_issuer_signed_good() {
+        let (cert, issuer, cert_id) = checked_pair();
+        let body = ocsp_response(&cert_id, &good(), ISSUER_KEY, &[]);
+
+        assert!(matches!(
+            check_with_response(&cert, &issuer, body),
+            RevocationStatus::Good
+        ));
+    }
+
+    #[test]
+    fn test_ocsp_delegated_responder_revoked() {
+        let (cert, issuer, cert_id) = checked_pair();
+        let responder = pem::parse(RESPONDER_PEM).unwrap().into_contents();
+        let body = ocsp_response(&cert_id, &revoked(), RESPONDER_KEY, &[responder]);
+
+        assert!(matches!(
+            check_with_response(&cert, &issuer, body),
+            RevocationStatus::Revoked
+        ));
+    }
+
+    #[test]
+    fn test_ocsp_unauthorized_signer_is_unknown() {
+        let (cert, issuer, cert_id) = checked_pair();
+
+        // Signed by a key the issuer never certified
+        let forged = ocsp_response(&cert_id, &good(), RESPONDER_KEY, &[]);
+        assert!(matches!(
+            check_with_response(&cert, &issuer, forged),
+            RevocationStatus::Unknown
+        ));
+
+        // Signed by the issuer, then altered in transit
+        let mut tampered = ocsp_response(&cert_id, &good(), ISSUER_KEY, &[]);
+        let this_update = tampered
+            .windows(15)
+            .rposition(|window| window == b"20250101000000Z")
+            .unwrap();
+        tampered[this_update + 3] = b'6';
+        assert!(matches!(
+            check_with_response(&cert, &issuer, tampered),
+            RevocationStatus::Unknown
+        ));
+    }
+
+    #[test]
+    fn test_ocsp_stale_or_future_response_is_unknown() {
+        let (cert, issuer, cert_id) = checked_pair();
+        let dated = |this_update, next_update| {
+            let body =
+                dated_ocsp_response(&cert_id, &good(), this_update, next_update, ISSUER_KEY, &[]);
+            check_with_response(&cert, &issuer, body)
+        };
+
+        // Correctly signed, but its nextUpdate has passed: a replayed response
+        assert!(matches!(
+            dated("20250101000000Z", Some("20250108000000Z")),
+            RevocationStatus::Unknown
+        ));
+        // Issued in the future
+        assert!(matches!(
+            dated("29990101000000Z", None),
+            RevocationStatus::Unknown
+        ));
+        // Within its validity window
+        assert!(matches!(
+            dated("20250101000000Z", Some("29990101000000Z")),
+            RevocationStatus::Good
+        ));
+    }
+
+    #[test]
+    fn test_ocsp_response_for_other_certificate_is_unknown() {
+        let (cert, issuer, _) = checked_pair();
+        let other = parse_certificate_chain(RESPONDER_PEM).unwrap().remove(0);
+        let other_id = build_cert_id(
+            &X509Certificate::from_der(&other.der).unwrap().1,
+            &X509Certificate::from_der(&issuer.der).unwrap().1,
+        )
+        .unwrap();
+        let body = ocsp_response(&other_id, &good(), ISSUER_KEY, &[]);
+
+        assert!(matches!(
+            check_with_response(&cert, &issuer, body),
+            RevocationStatus::Unknown
+        ));
+    }
+
+    #[test]
+    fn test_ocsp_without_responder_url_is_not_checked() {
+        let (mut cert, issuer, _) = checked_pair();
+        cert.ocsp_urls.clear();
+
+        assert!(matches!(
+            check_ocsp_status(&cert, &issuer, &FetchOptions::default()),
+            RevocationStatus::NotChecked
+        ));
+    }
+
+    #[test]
+    fn test_ocsp_unreachable_responder_is_unknown() {
+        let (mut cert, issuer, _) = checked_pair();
+
+        // Bind then drop a listener so the port refuses connections
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        cert.ocsp_urls = vec![format!("http://{addr}/")];
+
+        assert!(matches!(
+            check_ocsp_status(&cert, &issuer, &FetchOptions::default()),
+            RevocationStatus::Unknown
+        ));
+    }
+}
@@ -0,0 +1,296 @@
+use crate::error::CertError;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Names of well-known OIDs: X.509 and PKIX extensions, vendor extensions,
+/// extended key usage purposes, access methods and DN attribute types
+///
+/// Signature algorithms and curves have their own lookups in `parser`.
+const OID_NAMES: &[(&str, &str)] = &[
+    // X.509 certificate and CRL extensions (RFC 5280, ITU-T X.509)
+    ("2.5.29.1", "Authority Key Identifier (obsolete)"),
+    ("2.5.29.2", "Key Attributes (obsolete)"),
+    ("2.5.29.3", "Certificate Policies (obsolete)"),
+    ("2.5.29.4", "Key Usage Restriction (obsolete)"),
+    ("2.5.29.5", "Policy Mapping (obsolete)"),
+    ("2.5.29.6", "Subtrees Constraint (obsolete)"),
+    ("2.5.29.7", "Subject Alternative Name (obsolete)"),
+    ("2.5.29.8", "Issuer Alternative Name (obsolete)"),
+    ("2.5.29.9", "Subject Directory Attributes"),
+    ("2.5.29.10", "Basic Constraints (obsolete)"),
+    ("2.5.29.14", "Subject Key Identifier"),
+    ("2.5.29.15", "Key Usage"),
+    ("2.5.29.16", "Private Key Usage Period"),
+    ("2.5.29.17", "Subject Alternative Name"),
+    ("2.5.29.18", "Issuer Alternative Name"),
+    ("2.5.29.19", "Basic Constraints"),
+    ("2.5.29.20", "CRL Number"),
+    ("2.5.29.21", "CRL Reason Code"),
+    ("2.5.29.23", "Hold Instruction Code"),
+    ("2.5.29.24", "Invalidity Date"),
+    ("2.5.29.27", "Delta CRL Indicator"),
+    ("2.5.29.28", "Issuing Distribution Point"),
+    ("2.5.29.29", "Certificate Issuer"),
+    ("2.5.29.30", "Name Constraints"),
+    ("2.5.29.31", "CRL Distribution Points"),
+    ("2.5.29.32", "Certificate Policies"),
+    ("2.5.29.32.0", "Any Policy"),
+    ("2.5.29.33", "Policy Mappings"),
+    ("2.5.29.35", "Authority Key Identifier"),
+    ("2.5.29.36", "Policy Constraints"),
+    ("2.5.29.37", "Extended Key Usage"),
+    ("2.5.29.37.0", "Any Extended Key Usage"),
+    ("2.5.29.46", "Freshest CRL"),
+    ("2.5.29.54", "Inhibit Any Policy"),
+    ("2.5.29.55", "Target Information"),
+    ("2.5.29.56", "No Revocation Available"),
+    ("2.5.29.60", "Expired Certificates on CRL"),
+    ("2.5.29.72", "Alternative Signature Algorithm"),
+    ("2.5.29.73", "Alternative Signature Value"),
+    // PKIX private extensions (RFC 5280, 3709, 3739, 7633)
+    ("1.3.6.1.5.5.7.1.1", "Authority Information Access"),
+    ("1.3.6.1.5.5.7.1.2", "Biometric Info"),
+    ("1.3.6.1.5.5.7.1.3", "Qualified Certificate Statements"),
+    ("1.3.6.1.5.5.7.1.7", "IP Address Blocks"),
+    ("1.3.6.1.5.5.7.1.8", "AS Identifiers"),
+    ("1.3.6.1.5.5.7.1.11", "Subject Information Access"),
+    ("1.3.6.1.5.5.7.1.12", "Logotype"),
+    ("1.3.6.1.5.5.7.1.24", "TLS Feature"),
+    ("1.3.6.1.5.5.7.1.26", "TN Authorization List"),
+    // Certificate Transparency (RFC 6962)
+    ("1.3.6.1.4.1.11129.2.4.2", "Signed Certificate Timestamp"),
+    ("1.3.6.1.4.1.11129.2.4.3", "CT Precertificate Poison"),
+    (
+        "1.3.6.1.4.1.11129.2.4.4",
+        "CT Precertificate Signing Certificate",
+    ),
+    (
+        "1.3.6.1.4.1.11129.2.4.5",
+        "OCSP Signed Certificate Timestamp",
+    ),
+    // Extended key usage purposes (RFC 5280, 6187, 7299, 9336)
+    ("1.3.6.1.5.5.7.3.1", "TLS Web Server Authentication"),
+    ("1.3.6.1.5.5.7.3.2", "TLS Web Client Authentication"),
+    ("1.3.6.1.5.5.7.3.3", "Code Signing"),
+    ("1.3.6.1.5.5.7.3.4", "Email Protection"),
+    ("1.3.6.1.5.5.7.3.5", "IPsec End System"),
+    ("1.3.6.1.5.5.7.3.6", "IPsec Tunnel"),
+    ("1.3.6.1.5.5.7.3.7", "IPsec User"),
+    ("1.3.6.1.5.5.7.3.8", "Time Stamping"),
+    ("1.3.6.1.5.5.7.3.9", "OCSP Signing"),
+    ("1.3.6.1.5.5.7.3.17", "IPsec IKE"),
+    ("1.3.6.1.5.5.7.3.21", "SSH Client"),
+    ("1.3.6.1.5.5.7.3.22", "SSH Server"),
+    ("1.3.6.1.5.5.7.3.36", "Document Signing"),
+    // Access methods and OCSP (RFC 5280, 6960)
+    ("1.3.6.1.5.5.7.48.1", "OCSP"),
+    ("1.3.6.1.5.5.7.48.1.5", "OCSP No Check"),
+    ("1.3.6.1.5.5.7.48.2", "CA Issuers"),
+    ("1.3.6.1.5.5.7.48.3", "Time Stamping"),
+    ("1.3.6.1.5.5.7.48.5", "CA Repository"),
+    // Policy qualifiers (RFC 5280)
+    ("1.3.6.1.5.5.7.2.1", "CPS"),
+    ("1.3.6.1.5.5.7.2.2", "User Notice"),
+    // DN attribute types (RFC 4519, 5280)
+    ("2.5.4.3", "Common Name"),
+    ("2.5.4.4", "Surname"),
+    ("2.5.4.5", "Serial Number"),
+    ("2.5.4.6", "Country"),
+    ("2.5.4.7", "Locality"),
+    ("2.5.4.8", "State or Province"),
+    ("2.5.4.9", "Street Address"),
+    ("2.5.4.10", "Organization"),
+    ("2.5.4.11", "Organizational Unit"),
+    ("2.5.4.12", "Title"),
+    ("2.5.4.15", "Business Category"),
+    ("2.5.4.17", "Postal Code"),
+    ("2.5.4.42", "Given Name"),
+    ("2.5.4.43", "Initials"),
+    ("2.5.4.46", "DN Qualifier"),
+    ("2.5.4.65", "Pseudonym"),
+    ("2.5.4.97", "Organization Identifier"),
+    ("0.9.2342.19200300.100.1.1", "User ID"),
+    ("0.9.2342.19200300.100.1.25", "Domain Component"),
+    ("1.2.840.113549.1.9.1", "Email Address"),
+    ("1.3.6.1.4.1.311.60.2.1.1", "Jurisdiction Locality"),
+    ("1.3.6.1.4.1.311.60.2.1.2", "Jurisdiction State or Province"),
+    ("1.3.6.1.4.1.311.60.2.1.3", "Jurisdiction Country"),
+    // Public key algorithms
+    ("1.2.840.113549.1.1.1", "RSA Encryption"),
+    ("1.2.840.113549.1.1.10", "RSASSA-PSS"),
+    ("1.2.840.10045.2.1", "EC Public Key"),
+    ("1.2.840.10040.4.1", "DSA"),
+    ("1.3.101.110", "X25519"),
+    ("1.3.101.111", "X448"),
+    ("1.3.101.112", "Ed25519"),
+    ("1.3.101.113", "Ed448"),
+    // Microsoft extensions
+    (
+        "1.3.6.1.4.1.311.2.1.21",
+        "Microsoft Individual Code Signing",
+    ),
+    (
+        "1.3.6.1.4.1.311.2.1.22",
+        "Microsoft Commercial Code Signing",
+    ),
+    ("1.3.6.1.4.1.311.10.3.1", "Microsoft Trust List Signing"),
+    ("1.3.6.1.4.1.311.10.3.3", "Microsoft Server Gated Crypto"),
+    ("1.3.6.1.4.1.311.10.3.4", "Microsoft Encrypted File System"),
+    ("1.3.6.1.4.1.311.10.3.12", "Microsoft Document Signing"),
+    ("1.3.6.1.4.1.311.20.2", "Microsoft Smart Card Login"),
+    ("1.3.6.1.4.1.311.20.2.2", "Microsoft Smart Card Logon"),
+    ("1.3.6.1.4.1.311.20.2.3", "Microsoft User Principal Name"),
+    ("1.3.6.1.4.1.311.21.1", "Microsoft Individual Code Signing"),
+    (
+        "1.3.6.1.4.1.311.21.2",
+        "Microsoft Previous CA Certificate Hash",
+    ),
+    ("1.3.6.1.4.1.311.21.7", "Microsoft Certificate Template"),
+    ("1.3.6.1.4.1.311.21.10", "Microsoft Application Policies"),
+    ("1.3.6.1.4.1.311.25.2", "Microsoft NTDS CA Security"),
+    // Apple extensions
+    ("1.2.840.113635.100.6.1.2", "Apple iPhone Developer"),
+    ("1.2.840.113635.100.6.1.12", "Apple Mac Developer"),
+    (
+        "1.2.840.113635.100.6.1.13",
+        "Apple Developer ID Application",
+    ),
+    ("1.2.840.113635.100.6.2.1", "Apple WWDR Intermediate"),
+    // Entrust extensions
+    ("1.2.840.113533.7.65.0", "Entrust Version Information"),
+    // Netscape extensions
+    ("2.16.840.1.113730.1.1", "Netscape Certificate Type"),
+    ("2.16.840.1.113730.1.2", "Netscape Base URL"),
+    ("2.16.840.1.113730.1.3", "Netscape Revocation URL"),
+    ("2.16.840.1.113730.1.4", "Netscape CA Revocation URL"),
+    ("2.16.840.1.113730.1.7", "Netscape Renewal URL"),
+    ("2.16.840.1.113730.1.8", "Netscape CA Policy URL"),
+    ("2.16.840.1.113730.1.12", "Netscape SSL Server Name"),
+    ("2.16.840.1.113730.1.13", "Netscape Comment"),
+    // VeriSign extensions
+    ("2.23.42.7.0", "VeriSign Individual SHA1 Hash"),
+    // CA/Browser Forum
+    ("2.23.140.1.1", "Extended Validation (EV)"),
+    ("2.23.140.1.2.1", "Domain Validated (DV)"),
+    ("2.23.140.1.2.2", "Organization Validated (OV)"),
+    ("2.23.140.1.2.3", "Individual Validated (IV)"),
+    ("2.23.140.1.31", "Onion Domain"),
+    ("2.23.140.3.1", "CA/B Forum Code Signing"),
+    // ETSI qualified certificates (EN 319 412-5)
+    ("0.4.0.1862.1.1", "QC Compliance"),
+    ("0.4.0.1862.1.4", "QC SSCD"),
+    ("0.4.0.1862.1.5", "QC PDS"),
+    ("0.4.0.1862.1.6", "QC Type"),
+    ("0.4.0.194121.1.1", "QC Natural Person Semantics"),
+    ("0.4.0.194121.1.2", "QC Legal Person Semantics"),
+];
+
+/// User-supplied names from `--oid-map`, consulted before the built-in table
+static OVERRIDES: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+/// Human-readable name of `oid`, preferring `--oid-map` overrides over the built-in table
+#[must_use]
+pub fn oid_name(oid: &str) -> Option<String> {
+    lookup(oid, OVERRIDES.get())
+}
+
+/// Install the `--oid-map` overrides for every later lookup
+///
+/// Only the first call takes effect; returns whether this one did.
+pub fn set_overrides<S: BuildHasher>(overrides: HashMap<String, String, S>) -> bool {
+    OVERRIDES.set(overrides.into_iter().collect()).is_ok()
+}
+
+/// Read an `--oid-map` file mapping dotted OIDs to names
+///
+/// Files ending in `.toml` are read as TOML (`"2.5.29.35" = "AKI"`), anything
+/// else as a JSON object (`{"2.5.29.35": "AKI"}`).
+///
+/// # Errors
+///
+/// Returns [`CertError::Io`] if the file cannot be read and
+/// [`CertError::OidMap`] if it is not a flat map of strings.
+pub fn load_oid_map(path: &str) -> Result<HashMap<String, String>, CertError> {
+    let text = std::fs::read_to_string(path)?;
+    let is_toml = Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"));
+    if is_toml {
+        toml::from_str(&text).map_err(|e| CertError::OidMap(format!("{path}: {e}")))
+    } else {
+        serde_json::from_str(&text).map_err(|e| CertError::OidMap(format!("{path}: {e}")))
+    }
+}
+
+fn lookup(oid: &str, overrides: Option<&HashMap<String, String>>) -> Option<String> {
+    overrides
+        .and_then(|overrides| overrides.get(oid).cloned())
+        .or_else(|| {
+            OID_NAMES
+                .iter()
+                .find(|(known, _)| *known == oid)
+                .map(|(_, name)| (*name).to_string())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_override_takes_precedence_over_builtin() {
+        let overrides = HashMap::from([
+            ("2.5.29.19".to_string(), "BC".to_string()),
+            ("1.2.3.4".to_string(), "Private Extension".to_string()),
+        ]);
+
+        assert_eq!(lookup("2.5.29.19", Some(&overrides)).as_deref(), Some("BC"));
+        assert_eq!(
+            lookup("1.2.3.4", Some(&overrides)).as_deref(),
+            Some("Private Extension")
+        );
+        assert_eq!(
+            lookup("2.5.29.17", Some(&overrides)).as_deref(),
+            Some("Subject Alternative Name")
+        );
+        assert_eq!(
+            lookup("2.5.29.19", None).as_deref(),
+            Some("Basic Constraints")
+        );
+    }
+
+    #[test]
+    fn test_builtin_table_has_unique_oids() {
+        let mut seen = HashSet::new();
+        for (oid, _) in OID_NAMES {
+            assert!(seen.insert(oid), "duplicate entry for {oid}");
+        }
+        assert_eq!(
+            lookup("2.5.29.35", None).as_deref(),
+            Some("Authority Key Identifier")
+        );
+    }
+
+    #[test]
+    fn test_load_oid_map_reads_toml_and_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let toml_path = dir.path().join("oids.toml");
+        std::fs::write(&toml_path, "\"1.2.3.4\" = \"From TOML\"\n").unwrap();
+        let json_path = dir.path().join("oids.json");
+        std::fs::write(&json_path, r#"{"1.2.3.4": "From JSON"}"#).unwrap();
+
+        let map = load_oid_map(toml_path.to_str().unwrap()).unwrap();
+        assert_eq!(map["1.2.3.4"], "From TOML");
+        let map = load_oid_map(json_path.to_str().unwrap()).unwrap();
+        assert_eq!(map["1.2.3.4"], "From JSON");
+
+        std::fs::write(&json_path, r#"{"1.2.3.4": 5}"#).unwrap();
+        assert!(matches!(
+            load_oid_map(json_path.to_str().unwrap()),
+            Err(CertError::OidMap(_))
+        ));
+    }
+}
@@ -0,0 +1,98 @@
+//! Paging of long text output
+//!
+//! Output taller than the terminal is piped through `$PAGER`, or `less -R` so
+//! ANSI colors survive, when stdout is an interactive terminal.
+
+use crossterm::terminal;
+use std::env;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::process::{Command, Stdio};
+use tracing::debug;
+
+/// Pager used when `$PAGER` is unset or empty
+const DEFAULT_PAGER: &str = "less -R";
+
+/// Whether rendered output may be sent to a pager
+///
+/// Paging is skipped with `--no-pager`, when writing to a file, or when stdout
+/// is not a terminal.
+#[must_use]
+pub fn pager_enabled(no_pager: bool, writing_to_file: bool) -> bool {
+    !no_pager && !writing_to_file && io::stdout().is_terminal()
+}
+
+/// Write rendered output, through a pager if enabled and it overflows the terminal
+///
+/// Falls back to writing directly to `out` when the pager cannot be started.
+///
+/// # Errors
+///
+/// Returns an error if writing to `out` or to the pager fails.
+pub fn write_paged(rendered: &[u8], out: &mut impl Write, paging: bool) -> io::Result<()> {
+    if paging && exceeds_terminal_height(rendered) {
+        match spawn_pager(rendered) {
+            Ok(()) => return Ok(()),
+            Err(e) => debug!(error = %e, "pager unavailable; writing directly"),
+        }
+    }
+    out.write_all(rendered)
+}
+
+fn exceeds_terminal_height(rendered: &[u8]) -> bool {
+    let lines = rendered.lines().count();
+    terminal::size().is_ok_and(|(_, rows)| lines > usize::from(rows))
+}
+
+/// The pager command line: `$PAGER` split on whitespace, or [`DEFAULT_PAGER`]
+fn pager_command() -> Vec<String> {
+    let configured = env::var("PAGER").unwrap_or_default();
+    let command = if configured.trim().is_empty() {
+        DEFAULT_PAGER
+    } else {
+        configured.as_str()
+    };
+    command.split_whitespace().map(str::to_string).collect()
+}
+
+fn spawn_pager(rendered: &[u8]) -> io::Result<()> {
+    let command = pager_command();
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "empty pager command"))?;
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // Quitting the pager early closes the pipe; that is not an error
+        match stdin.write_all(rendered) {
+            Err(e) if e.kind() != io::ErrorKind::BrokenPipe => return Err(e),
+            _ => {}
+        }
+    }
+    child.wait()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_pager_writes_to_buffer() {
+        let rendered = "line\n".repeat(500);
+        let paging = pager_enabled(true, false);
+        assert!(!paging);
+
+        let mut buffer = Vec::new();
+        write_paged(rendered.as_bytes(), &mut buffer, paging).unwrap();
+
+        assert_eq!(buffer, rendered.as_bytes());
+    }
+
+    #[test]
+    fn test_pager_disabled_when_writing_to_file() {
+        assert!(!pager_enabled(false, true));
+    }
+}
@@ -1,64 +1,130 @@
 use crate::error::CertError;
-use crate::models::{CertificateInfo, ExtensionInfo};
+use crate::models::{CertificateInfo, ExtensionInfo, NameConstraints, SctInfo};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use cms::cert::x509::der::{oid::ObjectIdentifier, Decode, Encode};
+use cms::cert::CertificateChoices;
+use cms::content_info::ContentInfo;
+use cms::signed_data::SignedData;
 use pem::parse_many;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::net::IpAddr;
 use std::str;
-use x509_parser::prelude::FromDer;
+use tracing::warn;
+use x509_parser::extensions::{
+    DistributionPointName, ExtendedKeyUsage, GeneralName, GeneralSubtree, ParsedExtension,
+    PolicyInformation, SignedCertificateTimestamp, X509Extension,
+};
 use x509_parser::prelude::X509Certificate;
+use x509_parser::prelude::{ASN1Time, FromDer};
 
+pub use crate::oid::{load_oid_map, set_overrides as set_oid_overrides};
+
+/// Common name of a DN string, for compact display
+///
+/// A subject with several CN attributes yields the first in DN order; use
+/// `extract_all_cns` to see them all. Without any CN the whole DN is returned.
+#[must_use]
 pub fn extract_cn(subject: &str) -> String {
     // Parse the DN format: C=US, ST=New Jersey, L=Jersey City, O=The USERTRUST Network, CN=USERTrust RSA Cer...
-    let parts: Vec<&str> = subject.split(',').collect();
+    extract_all_cns(subject)
+        .into_iter()
+        .next()
+        // If no CN found, return the whole subject as fallback
+        .unwrap_or_else(|| subject.to_string())
+}
+
+/// Every CN attribute of a DN string in DN order, including those of multi-valued RDNs
+#[must_use]
+pub fn extract_all_cns(subject: &str) -> Vec<String> {
+    dn_components(subject)
+        .into_iter()
+        .filter(|(name, _)| name.eq_ignore_ascii_case("CN"))
+        .map(|(_, value)| value)
+        .collect()
+}
+
+/// Split a DN string into its ordered `(attribute, value)` pairs with values unescaped
+///
+/// Each attribute of a multi-valued RDN becomes its own pair.
+#[must_use]
+pub fn dn_components(dn: &str) -> Vec<(String, String)> {
+    // RDNs are separated by commas and multi-valued RDNs join attributes with '+';
+    // separators inside quotes or escaped with a backslash belong to the value (RFC 4514)
+    split_dn(dn, &[',', ';'])
+        .into_iter()
+        .flat_map(|rdn| split_dn(rdn, &['+']))
+        .filter_map(|attribute| attribute.split_once('='))
+        .map(|(name, value)| (name.trim().to_string(), unescape_dn_value(value.trim())))
+        .collect()
+}
+
+/// Split a DN string on any of `separators`, ignoring escaped or quoted occurrences
+fn split_dn<'a>(input: &'a str, separators: &[char]) -> Vec<&'a str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    let mut quoted = false;
 
-    for part in parts {
-        let trimmed = part.trim();
-        if let Some(stripped) = trimmed.strip_prefix("CN=") {
-            return stripped.to_string(); // Remove "CN=" prefix
+    for (index, c) in input.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == '"' {
+            quoted = !quoted;
+        } else if !quoted && separators.contains(&c) {
+            parts.push(&input[start..index]);
+            start = index + c.len_utf8();
         }
     }
+    parts.push(&input[start..]);
+    parts
+}
 
-    // If no CN found, return the whole subject as fallback
-    subject.to_string()
+/// Decode an RFC 4514 attribute value: strip quotes and resolve `\c` and `\HH` escapes
+fn unescape_dn_value(value: &str) -> String {
+    let value = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value);
+
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && i + 1 < bytes.len() {
+            let hex = bytes
+                .get(i + 1..i + 3)
+                .and_then(|pair| str::from_utf8(pair).ok())
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok());
+            if let Some(byte) = hex {
+                out.push(byte);
+                i += 3;
+            } else {
+                out.push(bytes[i + 1]);
+                i += 2;
+            }
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
 }
 
-// Function to map OID to human-readable extension name
+/// Human-readable name of an extension (or other well-known) OID
+///
+/// Names given with `--oid-map` (see [`set_oid_overrides`]) win over the built-in table.
+#[must_use]
 pub fn oid_to_name(oid: &str) -> Option<String> {
-    match oid {
-        // Standard X.509 extensions
-        "2.5.29.14" => Some("Subject Key Identifier".to_string()),
-        "2.5.29.15" => Some("Key Usage".to_string()),
-        "2.5.29.16" => Some("Private Key Usage Period".to_string()),
-        "2.5.29.17" => Some("Subject Alternative Name".to_string()),
-        "2.5.29.18" => Some("Issuer Alternative Name".to_string()),
-        "2.5.29.19" => Some("Basic Constraints".to_string()),
-        "2.5.29.30" => Some("Name Constraints".to_string()),
-        "2.5.29.31" => Some("CRL Distribution Points".to_string()),
-        "2.5.29.32" => Some("Certificate Policies".to_string()),
-        "2.5.29.33" => Some("Policy Mappings".to_string()),
-        "2.5.29.35" | "1.3.6.1.5.5.7.1.1" => Some("Authority Information Access".to_string()),
-        "2.5.29.36" => Some("Policy Constraints".to_string()),
-        "2.5.29.37" => Some("Extended Key Usage".to_string()),
-        "2.5.29.46" => Some("Freshest CRL".to_string()),
-
-        // Microsoft extensions
-        "1.3.6.1.4.1.311.20.2" => Some("Microsoft Smart Card Login".to_string()),
-        "1.3.6.1.4.1.311.21.1" => Some("Microsoft Individual Code Signing".to_string()),
-
-        // Entrust extensions
-        "1.2.840.113533.7.65.0" => Some("Entrust Version Information".to_string()),
-
-        // Netscape extensions
-        "2.16.840.1.113730.1.1" => Some("Netscape Certificate Type".to_string()),
-
-        // VeriSign extensions
-        "2.23.42.7.0" => Some("VeriSign Individual SHA1 Hash".to_string()),
-
-        // Other common extensions
-        "1.3.6.1.4.1.11129.2.4.2" => Some("Signed Certificate Timestamp".to_string()),
-        _ => None,
-    }
+    crate::oid::oid_name(oid)
 }
 
 // Function to map signature algorithm OID to human-readable name
+#[must_use]
 pub fn signature_alg_to_name(oid_str: &str) -> Option<String> {
     match oid_str {
         "1.2.840.113549.1.1.1" | "1.2.840.113549.1.1.4" => Some("RSA with MD5".to_string()),
@@ -66,102 +132,716 @@ pub fn signature_alg_to_name(oid_str: &str) -> Option<String> {
         "1.2.840.113549.1.1.11" => Some("SHA256 with RSA".to_string()),
         "1.2.840.113549.1.1.12" => Some("SHA384 with RSA".to_string()),
         "1.2.840.113549.1.1.13" => Some("SHA512 with RSA".to_string()),
+        "1.2.840.113549.1.1.10" => Some("RSA-PSS".to_string()),
         "1.2.840.10045.4.1" => Some("SHA1 with ECDSA".to_string()),
         "1.2.840.10045.4.3.2" => Some("SHA256 with ECDSA".to_string()),
         "1.2.840.10045.4.3.3" => Some("SHA384 with ECDSA".to_string()),
         "1.2.840.10045.4.3.4" => Some("SHA512 with ECDSA".to_string()),
         "1.2.840.10040.4.3" => Some("SHA1 with DSA".to_string()),
+        "1.3.101.112" => Some("Ed25519".to_string()),
+        "1.3.101.113" => Some("Ed448".to_string()),
         _ => None,
     }
 }
 
-// Function to explain signature algorithm in simple terms
-pub fn explain_signature_algorithm(alg: &str) -> String {
-    if alg.contains("RSA") {
-        "This certificate uses RSA encryption with hashing. RSA is like a digital lock that only the certificate issuer has the key to open. The hashing creates a unique fingerprint of the certificate data. Together, they create a digital signature that proves the certificate is genuine and hasn't been tampered with. This is essential for secure websites and encrypted communications.".to_string()
-    } else if alg.contains("ECDSA") {
-        "This certificate uses Elliptic Curve Digital Signature Algorithm (ECDSA). It's a modern, efficient way to create digital signatures using advanced mathematics with elliptic curves. Like RSA, it creates a unique signature that proves the certificate's authenticity, but it's faster and uses smaller keys. This helps keep internet communications secure and private.".to_string()
-    } else if alg.contains("DSA") {
-        "This certificate uses Digital Signature Algorithm (DSA). It's a method for creating digital signatures that verify the authenticity of the certificate. Using mathematical techniques, it creates a unique code that only the legitimate issuer can produce. This prevents fake certificates and ensures trust in online communications.".to_string()
-    } else {
-        "This is a cryptographic signature method that verifies the certificate's authenticity. It uses mathematical algorithms to create a unique digital signature that proves the certificate is legitimate and hasn't been altered. This is crucial for establishing secure and trustworthy connections on the internet.".to_string()
+// Function to map named elliptic curve OID to human-readable name
+#[must_use]
+pub fn curve_oid_to_name(oid: &str) -> Option<String> {
+    match oid {
+        "1.2.840.10045.3.1.7" => Some("P-256".to_string()),
+        "1.3.132.0.34" => Some("P-384".to_string()),
+        "1.3.132.0.35" => Some("P-521".to_string()),
+        "1.3.132.0.10" => Some("secp256k1".to_string()),
+        _ => None,
     }
 }
 
+/// How to interpret certificate input data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputFormat {
+    /// PEM when the data contains a `-----BEGIN` armor line, DER otherwise
+    #[default]
+    Auto,
+    Pem,
+    Der,
+    /// JSON Web Key or Key Set carrying certificate chains in `x5c`
+    Jwk,
+}
+
+/// Parse PEM, DER or JWK data into certificates, detecting the encoding
+///
+/// # Errors
+///
+/// Returns an error if the data is neither valid PEM certificates, a JWK with
+/// `x5c` certificates nor a DER certificate.
 pub fn parse_certificate_chain(data: &[u8]) -> Result<Vec<CertificateInfo>, CertError> {
+    parse_certificate_chain_as(data, InputFormat::Auto)
+}
+
+/// Parse certificates using an explicit input format
+///
+/// In auto mode armored data is only ever parsed as PEM, so a corrupt PEM file
+/// reports the PEM error instead of a misleading DER one.
+///
+/// # Errors
+///
+/// Returns [`CertError::PemParse`] for malformed PEM in which not a single
+/// certificate block decodes, [`CertError::NoCertificates`]
+/// for PEM, PKCS#7 or JWK input that holds no certificate, and
+/// [`CertError::X509Parse`] for invalid certificate contents.
+pub fn parse_certificate_chain_as(
+    data: &[u8],
+    format: InputFormat,
+) -> Result<Vec<CertificateInfo>, CertError> {
+    match format {
+        InputFormat::Pem => parse_pem_chain(data),
+        InputFormat::Der => parse_der_certificate(data),
+        InputFormat::Jwk => parse_jwk(data),
+        InputFormat::Auto => {
+            if data
+                .windows(PEM_ARMOR.len())
+                .any(|window| window == PEM_ARMOR)
+            {
+                parse_pem_chain(data)
+            } else if is_json_object(data) {
+                parse_jwk(data)
+            } else {
+                parse_der_certificate(data)
+            }
+        }
+    }
+}
+
+/// Whether the data looks like a JSON object; DER always starts with a tag byte instead
+pub(crate) fn is_json_object(data: &[u8]) -> bool {
+    data.iter()
+        .find(|byte| !byte.is_ascii_whitespace())
+        .is_some_and(|&byte| byte == b'{')
+}
+
+/// Extract the `x5c` certificate chains of a JWK Set (`{"keys": [...]}`) or a single JWK
+///
+/// Each `x5c` entry is standard base64 of a DER certificate (RFC 7517 section 4.7);
+/// keys without `x5c` are skipped.
+fn parse_jwk(data: &[u8]) -> Result<Vec<CertificateInfo>, CertError> {
+    let value: serde_json::Value =
+        serde_json::from_slice(data).map_err(|e| CertError::X509Parse(format!("JWK: {e}")))?;
+    let keys = match value.get("keys").and_then(serde_json::Value::as_array) {
+        Some(keys) => keys.iter().collect(),
+        None => vec![&value],
+    };
+
     let mut certificates = Vec::new();
+    for key in keys {
+        let Some(chain) = key.get("x5c").and_then(serde_json::Value::as_array) else {
+            continue;
+        };
+        for entry in chain {
+            let encoded = entry.as_str().ok_or_else(|| {
+                CertError::X509Parse("JWK: x5c entry is not a string".to_string())
+            })?;
+            let der = STANDARD
+                .decode(encoded)
+                .map_err(|e| CertError::X509Parse(format!("JWK: x5c entry: {e}")))?;
+            let (_, cert) = X509Certificate::from_der(&der)
+                .map_err(|e| CertError::X509Parse(format!("JWK: {e}")))?;
+            certificates.push(extract_cert_info(&cert, &der));
+        }
+    }
 
-    // Try to parse as PEM with multiple certificates
-    if let Ok(pems) = parse_many(data) {
-        for pem in pems {
-            if pem.tag() == "CERTIFICATE" {
+    if certificates.is_empty() {
+        return Err(CertError::NoCertificates(
+            "JWK has no x5c certificate chain".to_string(),
+        ));
+    }
+
+    Ok(certificates)
+}
+
+/// Start of every PEM armor line
+const PEM_ARMOR: &[u8] = b"-----BEGIN";
+
+fn parse_pem_chain(data: &[u8]) -> Result<Vec<CertificateInfo>, CertError> {
+    let mut certificates = Vec::new();
+    let mut other_blocks: Vec<String> = Vec::new();
+
+    // A single malformed block fails the whole parse; fall back to reading the
+    // certificate blocks one by one so the rest of the bundle still loads
+    let blocks = match parse_many(data) {
+        Ok(blocks) => blocks,
+        Err(e) => {
+            let certificates = scan_certificate_blocks(data);
+            if certificates.is_empty() {
+                return Err(CertError::PemParse(e.to_string()));
+            }
+            return Ok(certificates);
+        }
+    };
+
+    // Parse as PEM with multiple certificates, skipping non-certificate blocks
+    for pem in blocks {
+        match pem.tag() {
+            "CERTIFICATE" => {
                 let (_, cert) = X509Certificate::from_der(pem.contents())
                     .map_err(|e| CertError::X509Parse(e.to_string()))?;
-                let cert_info = extract_cert_info(&cert);
+                let cert_info = extract_cert_info(&cert, pem.contents());
                 certificates.push(cert_info);
             }
+            "PKCS7" | "CMS" => certificates.extend(parse_pkcs7(pem.contents())?),
+            tag => {
+                if !other_blocks.iter().any(|other| other == tag) {
+                    other_blocks.push(tag.to_string());
+                }
+            }
         }
     }
 
-    // If no PEM certificates found, try single DER
     if certificates.is_empty() {
-        let (_, cert) =
-            X509Certificate::from_der(data).map_err(|e| CertError::X509Parse(e.to_string()))?;
-        let cert_info = extract_cert_info(&cert);
-        certificates.push(cert_info);
+        return Err(CertError::NoCertificates(if other_blocks.is_empty() {
+            "PEM data has no CERTIFICATE block".to_string()
+        } else {
+            format!(
+                "PEM data has no CERTIFICATE block, only {}",
+                other_blocks.join(", ")
+            )
+        }));
     }
 
     Ok(certificates)
 }
 
-pub fn extract_cert_info(cert: &X509Certificate) -> CertificateInfo {
-    let subject = cert.subject().to_string();
-    let issuer = cert.issuer().to_string();
-    let serial = format!("{:x}", cert.serial)
-        .as_bytes()
+const PEM_BEGIN_CERTIFICATE: &str = "-----BEGIN CERTIFICATE-----";
+const PEM_END_CERTIFICATE: &str = "-----END CERTIFICATE-----";
+
+/// Decode each `BEGIN`/`END CERTIFICATE` pair on its own, ignoring any text between them
+///
+/// Blocks that are unterminated or do not decode to a certificate are skipped
+/// with a warning naming their line.
+fn scan_certificate_blocks(data: &[u8]) -> Vec<CertificateInfo> {
+    let text = String::from_utf8_lossy(data);
+    let mut certificates = Vec::new();
+    let mut rest = &text[..];
+
+    while let Some(begin) = rest.find(PEM_BEGIN_CERTIFICATE) {
+        let line = text[..text.len() - rest.len() + begin].lines().count() + 1;
+        let body = &rest[begin + PEM_BEGIN_CERTIFICATE.len()..];
+        let Some(end) = body.find(PEM_END_CERTIFICATE) else {
+            warn!(line, "skipping unterminated PEM certificate block");
+            break;
+        };
+        rest = &body[end + PEM_END_CERTIFICATE.len()..];
+
+        let encoded: String = body[..end]
+            .chars()
+            .filter(|c| !c.is_ascii_whitespace())
+            .collect();
+        let der = match STANDARD.decode(&encoded) {
+            Ok(der) => der,
+            Err(e) => {
+                warn!(line, error = %e, "skipping malformed PEM certificate block");
+                continue;
+            }
+        };
+        match X509Certificate::from_der(&der) {
+            Ok((_, cert)) => certificates.push(extract_cert_info(&cert, &der)),
+            Err(e) => warn!(line, error = %e, "skipping malformed PEM certificate block"),
+        }
+    }
+
+    certificates
+}
+
+fn parse_der_certificate(data: &[u8]) -> Result<Vec<CertificateInfo>, CertError> {
+    match X509Certificate::from_der(data) {
+        Ok((rest, cert)) => {
+            let cert_info = extract_cert_info(&cert, &data[..data.len() - rest.len()]);
+            Ok(vec![cert_info])
+        }
+        // A DER PKCS#7 bundle (.p7b/.p7c); report the X.509 error if it is not one either
+        Err(e) => parse_pkcs7(data).map_err(|_| CertError::X509Parse(e.to_string())),
+    }
+}
+
+/// PKCS#7 / CMS `signedData` content type (1.2.840.113549.1.7.2)
+const OID_PKCS7_SIGNED_DATA: ObjectIdentifier =
+    ObjectIdentifier::new_unwrap("1.2.840.113549.1.7.2");
+
+/// Extract the certificates embedded in a DER PKCS#7 / CMS `SignedData` bundle
+fn parse_pkcs7(data: &[u8]) -> Result<Vec<CertificateInfo>, CertError> {
+    let content_info =
+        ContentInfo::from_der(data).map_err(|e| CertError::X509Parse(format!("PKCS#7: {e}")))?;
+    if content_info.content_type != OID_PKCS7_SIGNED_DATA {
+        return Err(CertError::X509Parse(format!(
+            "PKCS#7: unsupported content type {}",
+            content_info.content_type
+        )));
+    }
+    let signed_data: SignedData = content_info
+        .content
+        .decode_as()
+        .map_err(|e| CertError::X509Parse(format!("PKCS#7: {e}")))?;
+
+    let mut certificates = Vec::new();
+    for choice in signed_data.certificates.iter().flat_map(|set| set.0.iter()) {
+        let CertificateChoices::Certificate(cert) = choice else {
+            continue;
+        };
+        let der = cert
+            .to_der()
+            .map_err(|e| CertError::X509Parse(format!("PKCS#7: {e}")))?;
+        let (_, parsed) =
+            X509Certificate::from_der(&der).map_err(|e| CertError::X509Parse(e.to_string()))?;
+        certificates.push(extract_cert_info(&parsed, &der));
+    }
+
+    if certificates.is_empty() {
+        return Err(CertError::NoCertificates(
+            "PKCS#7 bundle is empty".to_string(),
+        ));
+    }
+    Ok(certificates)
+}
+
+/// Decode hex-encoded bytes, ignoring whitespace and the colons of `AB:CD` notation
+///
+/// # Errors
+///
+/// Returns [`CertError::Hex`] for a character that is not a hex digit or an odd
+/// number of digits.
+pub fn decode_hex(input: &str) -> Result<Vec<u8>, CertError> {
+    let nibbles = input
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != ':')
+        .map(|c| {
+            c.to_digit(16)
+                .and_then(|digit| u8::try_from(digit).ok())
+                .ok_or_else(|| CertError::Hex(format!("'{c}' is not a hex digit")))
+        })
+        .collect::<Result<Vec<u8>, _>>()?;
+    if nibbles.len() % 2 != 0 {
+        return Err(CertError::Hex(format!(
+            "odd number of hex digits ({})",
+            nibbles.len()
+        )));
+    }
+    Ok(nibbles
         .chunks(2)
-        .map(|chunk| str::from_utf8(chunk).unwrap_or("??"))
+        .map(|pair| (pair[0] << 4) | pair[1])
+        .collect())
+}
+
+/// Parse certificates from hex-encoded DER, as pasted with `--hex`
+///
+/// # Errors
+///
+/// Returns [`CertError::Hex`] if the input is not valid hex, and the errors of
+/// [`parse_certificate_chain`] if the bytes are not a certificate.
+pub fn parse_hex_certificate(input: &str) -> Result<Vec<CertificateInfo>, CertError> {
+    parse_certificate_chain(&decode_hex(input)?)
+}
+
+/// Drop repeated certificates, keeping the first occurrence of each serial+issuer pair
+#[must_use]
+pub fn dedup_certificates(certificates: Vec<CertificateInfo>) -> Vec<CertificateInfo> {
+    let mut seen = std::collections::HashSet::new();
+    certificates
+        .into_iter()
+        .filter(|cert| seen.insert((cert.serial_number.clone(), cert.issuer.clone())))
+        .collect()
+}
+
+/// Format an IP address from its encoded form (4 or 16 octets)
+fn ip_bytes_to_string(bytes: &[u8]) -> Option<String> {
+    match bytes.len() {
+        4 => <[u8; 4]>::try_from(bytes)
+            .ok()
+            .map(|octets| IpAddr::from(octets).to_string()),
+        16 => <[u8; 16]>::try_from(bytes)
+            .ok()
+            .map(|octets| IpAddr::from(octets).to_string()),
+        _ => None,
+    }
+}
+
+/// Render the SAN entry kinds we display (DNS names, IP addresses and email addresses)
+fn general_name_to_string(name: &GeneralName) -> Option<String> {
+    match name {
+        GeneralName::DNSName(dns) => Some((*dns).to_string()),
+        GeneralName::RFC822Name(email) => Some((*email).to_string()),
+        GeneralName::IPAddress(bytes) => ip_bytes_to_string(bytes),
+        _ => None,
+    }
+}
+
+/// Render any general name with an OpenSSL-style type label (e.g. `DNS:example.com`)
+fn general_name_label(name: &GeneralName) -> String {
+    match name {
+        GeneralName::DNSName(dns) => format!("DNS:{dns}"),
+        GeneralName::RFC822Name(email) => format!("email:{email}"),
+        GeneralName::URI(uri) => format!("URI:{uri}"),
+        GeneralName::IPAddress(bytes) => format!(
+            "IP Address:{}",
+            ip_bytes_to_string(bytes).unwrap_or_else(|| hex_colon(bytes))
+        ),
+        GeneralName::DirectoryName(dn) => format!("DirName:{dn}"),
+        GeneralName::RegisteredID(oid) => format!("Registered ID:{oid}"),
+        GeneralName::OtherName(oid, _) => format!("othername:{oid}"),
+        GeneralName::X400Address(_) => "X400Name:<unsupported>".to_string(),
+        GeneralName::EDIPartyName(_) => "EdiPartyName:<unsupported>".to_string(),
+    }
+}
+
+/// Render a Name Constraints subtree base; IP bases carry a netmask, shown as a prefix length
+fn name_constraint_label(name: &GeneralName) -> String {
+    let GeneralName::IPAddress(bytes) = name else {
+        return general_name_label(name);
+    };
+    let (address, mask) = bytes.split_at(bytes.len() / 2);
+    match (ip_bytes_to_string(address), ip_bytes_to_string(mask)) {
+        (Some(address), Some(mask_text)) => {
+            let prefix: u32 = mask.iter().map(|byte| byte.count_ones()).sum();
+            // Non-contiguous masks cannot be written as a prefix length
+            let contiguous = mask
+                .iter()
+                .flat_map(|byte| (0..8).rev().map(move |bit| (byte >> bit) & 1))
+                .skip_while(|&bit| bit == 1)
+                .all(|bit| bit == 0);
+            if contiguous {
+                format!("IP Address:{address}/{prefix}")
+            } else {
+                format!("IP Address:{address}/{mask_text}")
+            }
+        }
+        _ => format!("IP Address:{}", hex_colon(bytes)),
+    }
+}
+
+/// Label every base of an optional list of Name Constraints subtrees
+fn name_constraint_subtrees(subtrees: Option<&Vec<GeneralSubtree>>) -> Vec<String> {
+    subtrees
+        .into_iter()
+        .flatten()
+        .map(|subtree| name_constraint_label(&subtree.base))
+        .collect()
+}
+
+/// Format bytes as colon-separated uppercase hex (e.g. `0D:F6:70`)
+pub(crate) fn hex_colon(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{b:02X}"))
         .collect::<Vec<_>>()
-        .join(" ");
-    // Store dates in RFC 2822 format initially, then convert to display format
-    let not_before_rfc = cert
-        .validity()
-        .not_before
-        .to_rfc2822()
-        .unwrap_or_else(|_| "Invalid date".to_string());
-    let not_after_rfc = cert
-        .validity()
-        .not_after
-        .to_rfc2822()
-        .unwrap_or_else(|_| "Invalid date".to_string());
-
-    // Convert to display format
-    let not_before = if let Ok(dt) = chrono::DateTime::parse_from_rfc2822(&not_before_rfc) {
-        dt.format("%Y-%m-%d %H:%M:%S").to_string()
-    } else {
-        not_before_rfc
+        .join(":")
+}
+
+/// Map an Extended Key Usage extension to friendly purpose names
+fn extended_key_usage_names(eku: &ExtendedKeyUsage) -> Vec<String> {
+    let known = [
+        (eku.any, "Any Extended Key Usage"),
+        (eku.server_auth, "TLS Web Server Authentication"),
+        (eku.client_auth, "TLS Web Client Authentication"),
+        (eku.code_signing, "Code Signing"),
+        (eku.email_protection, "E-mail Protection"),
+        (eku.time_stamping, "Time Stamping"),
+        (eku.ocsp_signing, "OCSP Signing"),
+    ];
+
+    known
+        .iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, name)| (*name).to_string())
+        .chain(eku.other.iter().map(ToString::to_string))
+        .collect()
+}
+
+/// Friendly name for well-known certificate policy OIDs (CA/Browser Forum and anyPolicy)
+fn policy_name(oid: &str) -> Option<&'static str> {
+    match oid {
+        "2.23.140.1.1" => Some("Extended Validation (EV)"),
+        "2.23.140.1.2.1" => Some("Domain Validated (DV)"),
+        "2.23.140.1.2.2" => Some("Organization Validated (OV)"),
+        "2.23.140.1.2.3" => Some("Individual Validated (IV)"),
+        "2.23.140.1.3" => Some("Extended Validation Code Signing"),
+        "2.23.140.1.4.1" => Some("Code Signing"),
+        "2.5.29.32.0" => Some("Any Policy"),
+        _ => None,
+    }
+}
+
+/// Certificate policy OIDs, named where known and verbatim otherwise
+fn policy_names(policies: &[PolicyInformation]) -> Vec<String> {
+    policies
+        .iter()
+        .map(|policy| {
+            let oid = policy.policy_id.to_id_string();
+            policy_name(&oid).map_or(oid, ToString::to_string)
+        })
+        .collect()
+}
+
+/// Decode embedded SCTs, naming the logs that are well known
+fn sct_infos(scts: &[SignedCertificateTimestamp]) -> Vec<SctInfo> {
+    scts.iter()
+        .map(|sct| {
+            let log_id = STANDARD.encode(sct.id.key_id);
+            let log = crate::ct::log_by_id(&log_id);
+            SctInfo {
+                log_name: log.map(|log| log.name.to_string()),
+                operator: log.map(|log| log.operator.to_string()),
+                log_id,
+                timestamp: i64::try_from(sct.timestamp)
+                    .ok()
+                    .and_then(DateTime::from_timestamp_millis)
+                    .unwrap_or_default(),
+                hash_algorithm: crate::ct::hash_algorithm_name(sct.signature.hash_alg_id),
+                signature_algorithm: crate::ct::signature_algorithm_name(sct.signature.sign_alg_id),
+            }
+        })
+        .collect()
+}
+
+/// Authority Information Access method OID for OCSP responders (id-ad-ocsp)
+const OID_AD_OCSP: &str = "1.3.6.1.5.5.7.48.1";
+
+/// Authority Information Access method OID for issuer certificates (id-ad-caIssuers)
+const OID_AD_CA_ISSUERS: &str = "1.3.6.1.5.5.7.48.2";
+
+/// Map an Authority Information Access method OID to its name
+fn access_method_name(oid: &str) -> String {
+    match oid {
+        OID_AD_OCSP => "OCSP".to_string(),
+        OID_AD_CA_ISSUERS => "CA Issuers".to_string(),
+        _ => oid.to_string(),
+    }
+}
+
+/// Decode an extension value into a human-readable string
+///
+/// Common extensions are rendered from x509-parser's parsed types; anything
+/// else falls back to the colon-separated hex of the raw DER value.
+pub fn format_extension_value(ext: &X509Extension) -> String {
+    match ext.parsed_extension() {
+        ParsedExtension::BasicConstraints(bc) => match (bc.ca, bc.path_len_constraint) {
+            (true, Some(len)) => format!("CA:TRUE, pathlen:{len}"),
+            (true, None) => "CA:TRUE".to_string(),
+            (false, _) => "CA:FALSE".to_string(),
+        },
+        ParsedExtension::KeyUsage(ku) => ku.to_string(),
+        ParsedExtension::ExtendedKeyUsage(eku) => extended_key_usage_names(eku).join(", "),
+        ParsedExtension::SubjectAlternativeName(san) => san
+            .general_names
+            .iter()
+            .map(general_name_label)
+            .collect::<Vec<_>>()
+            .join(", "),
+        ParsedExtension::AuthorityInfoAccess(aia) => aia
+            .iter()
+            .map(|desc| {
+                format!(
+                    "{} - {}",
+                    access_method_name(&desc.access_method.to_id_string()),
+                    general_name_label(&desc.access_location)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        ParsedExtension::CRLDistributionPoints(points) => points
+            .iter()
+            .filter_map(|point| match &point.distribution_point {
+                Some(DistributionPointName::FullName(names)) => Some(
+                    names
+                        .iter()
+                        .map(general_name_label)
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ),
+                Some(DistributionPointName::NameRelativeToCRLIssuer(rdn)) => Some(format!(
+                    "Relative Name:{}",
+                    rdn.iter()
+                        .filter_map(|attr| attr.as_str().ok())
+                        .collect::<Vec<_>>()
+                        .join("+")
+                )),
+                None => None,
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        ParsedExtension::CertificatePolicies(policies) => policy_names(policies).join(", "),
+        ParsedExtension::NameConstraints(constraints) => {
+            let permitted = name_constraint_subtrees(constraints.permitted_subtrees.as_ref());
+            let excluded = name_constraint_subtrees(constraints.excluded_subtrees.as_ref());
+            [("Permitted", permitted), ("Excluded", excluded)]
+                .into_iter()
+                .filter(|(_, entries)| !entries.is_empty())
+                .map(|(label, entries)| format!("{label}: {}", entries.join(", ")))
+                .collect::<Vec<_>>()
+                .join("; ")
+        }
+        ParsedExtension::SCT(scts) => sct_infos(scts)
+            .iter()
+            .map(SctInfo::text)
+            .collect::<Vec<_>>()
+            .join("; "),
+        ParsedExtension::SubjectKeyIdentifier(ski) => hex_colon(ski.0),
+        ParsedExtension::AuthorityKeyIdentifier(aki) => aki
+            .key_identifier
+            .as_ref()
+            .map_or_else(|| hex_colon(ext.value), |key_id| hex_colon(key_id.0)),
+        _ => hex_colon(ext.value),
+    }
+}
+
+/// RFC 5280 section 4.1.2.6: a certificate with an empty subject DN must carry
+/// a Subject Alternative Name extension, and that extension must be critical
+pub(crate) fn check_empty_subject_san(cert: &X509Certificate) -> Option<String> {
+    if cert.subject().iter_rdn().next().is_some() {
+        return None;
+    }
+
+    match cert.subject_alternative_name() {
+        Ok(Some(san)) if san.critical => None,
+        Ok(Some(_)) => Some(
+            "Empty subject requires the Subject Alternative Name extension to be critical (RFC 5280 4.1.2.6)"
+                .to_string(),
+        ),
+        Ok(None) | Err(_) => Some(
+            "Empty subject requires a Subject Alternative Name extension (RFC 5280 4.1.2.6)"
+                .to_string(),
+        ),
+    }
+}
+
+/// Hash the full certificate DER and render it as colon-separated uppercase hex
+pub(crate) fn fingerprint<D: Digest>(der: &[u8]) -> String {
+    hex_colon(&D::digest(der))
+}
+
+/// Format of the display strings kept in `not_before` / `not_after`
+const DISPLAY_DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Convert an X.509 validity time to UTC
+///
+/// X.509 times are limited to years 0000-9999, well inside chrono's range, so
+/// the epoch default is never reached in practice.
+fn asn1_time_to_utc(time: ASN1Time) -> DateTime<Utc> {
+    DateTime::from_timestamp(time.timestamp(), 0).unwrap_or_default()
+}
+
+/// Public key algorithm of a key that was not decoded, naming its raw OID and why
+///
+/// E.g. "Unknown (OID 1.3.101.113, Ed448)" for an unsupported key type, or
+/// "Unknown (unparseable: <reason>; OID 1.2.840.113549.1.1.1, RSA Encryption)"
+/// when the key of a known type is malformed.
+fn unknown_key_algorithm(cert: &X509Certificate, reason: Option<&str>) -> String {
+    let oid = cert.public_key().algorithm.algorithm.to_id_string();
+    let algorithm = match oid_to_name(&oid) {
+        Some(name) => format!("OID {oid}, {name}"),
+        None => format!("OID {oid}"),
+    };
+    match reason {
+        Some(reason) => format!("Unknown (unparseable: {reason}; {algorithm})"),
+        None => format!("Unknown ({algorithm})"),
+    }
+}
+
+/// Size in bits of a big-endian unsigned integer, ignoring DER leading zero bytes
+fn modulus_bits(modulus: &[u8]) -> u32 {
+    let significant: &[u8] = match modulus.iter().position(|b| *b != 0) {
+        Some(start) => &modulus[start..],
+        None => &[],
     };
-    let not_after = if let Ok(dt) = chrono::DateTime::parse_from_rfc2822(&not_after_rfc) {
-        dt.format("%Y-%m-%d %H:%M:%S").to_string()
+    significant.first().map_or(0, |first| {
+        u32::try_from(significant.len() * 8).unwrap_or(u32::MAX) - first.leading_zeros()
+    })
+}
+
+/// Serial number as space-separated lowercase hex bytes, e.g. "01 17 3e"
+///
+/// Works on the DER INTEGER content rather than a hex string, so every byte
+/// keeps both digits: the bytes are those `openssl x509 -serial` prints. The
+/// sign padding byte is dropped, and a negative serial (non-conformant, but
+/// seen in the wild) is shown as `-` followed by its magnitude.
+fn format_serial(raw: &[u8]) -> String {
+    let negative = raw.first().is_some_and(|byte| byte & 0x80 != 0);
+    let magnitude = if negative {
+        twos_complement(raw)
     } else {
-        not_after_rfc
+        raw.to_vec()
     };
+    let start = magnitude
+        .iter()
+        .position(|&byte| byte != 0)
+        .unwrap_or(magnitude.len().saturating_sub(1));
+    let hex = match &magnitude[start..] {
+        [] => "00".to_string(),
+        bytes => bytes
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<Vec<_>>()
+            .join(" "),
+    };
+    if negative {
+        format!("-{hex}")
+    } else {
+        hex
+    }
+}
+
+/// Negate a big-endian two's complement integer: invert every byte and add one
+fn twos_complement(bytes: &[u8]) -> Vec<u8> {
+    let mut negated: Vec<u8> = bytes.iter().map(|byte| !byte).collect();
+    for byte in negated.iter_mut().rev() {
+        let (sum, carry) = byte.overflowing_add(1);
+        *byte = sum;
+        if !carry {
+            break;
+        }
+    }
+    negated
+}
 
+/// Build a `CertificateInfo` from a parsed certificate and the DER bytes it was parsed from
+pub fn extract_cert_info(cert: &X509Certificate, der: &[u8]) -> CertificateInfo {
+    let subject = cert.subject().to_string();
+    let issuer = cert.issuer().to_string();
+    let serial = format_serial(cert.raw_serial());
+    let not_before_dt = asn1_time_to_utc(cert.validity().not_before);
+    let not_after_dt = asn1_time_to_utc(cert.validity().not_after);
+    let not_before = not_before_dt.format(DISPLAY_DATE_FORMAT).to_string();
+    let not_after = not_after_dt.format(DISPLAY_DATE_FORMAT).to_string();
+
+    let mut rsa_key_bits = None;
     let public_key_alg = match cert.public_key().parsed() {
         Ok(pk) => match pk {
             x509_parser::public_key::PublicKey::RSA(rsa_key) => {
-                let key_size = rsa_key.modulus.len() * 8;
+                let key_size = modulus_bits(rsa_key.modulus);
+                rsa_key_bits = Some(key_size);
                 format!("RSA ({key_size} bits)")
             }
-            x509_parser::public_key::PublicKey::EC(_) => "ECDSA".to_string(),
+            x509_parser::public_key::PublicKey::EC(_) => {
+                let curve = cert
+                    .public_key()
+                    .algorithm
+                    .parameters
+                    .as_ref()
+                    .and_then(|params| params.as_oid().ok())
+                    .map(|oid| {
+                        let oid = oid.to_id_string();
+                        curve_oid_to_name(&oid).unwrap_or(oid)
+                    });
+                match curve {
+                    Some(curve) => format!("ECDSA ({curve})"),
+                    None => "ECDSA".to_string(),
+                }
+            }
             x509_parser::public_key::PublicKey::DSA(_) => "DSA".to_string(),
             x509_parser::public_key::PublicKey::GostR3410(_) => "GOST R 34.10".to_string(),
             x509_parser::public_key::PublicKey::GostR3410_2012(_) => {
                 "GOST R 34.10-2012".to_string()
             }
-            x509_parser::public_key::PublicKey::Unknown(_) => "Unknown".to_string(),
+            x509_parser::public_key::PublicKey::Unknown(_) => unknown_key_algorithm(cert, None),
         },
-        Err(_) => "Unknown".to_string(),
+        Err(e) => unknown_key_algorithm(cert, Some(&e.to_string())),
     };
 
     let sig_alg_oid = cert.signature_algorithm.algorithm.to_string();
@@ -169,13 +849,82 @@ pub fn extract_cert_info(cert: &X509Certificate) -> CertificateInfo {
         .unwrap_or_else(|| format!("{:?}", cert.signature_algorithm.algorithm));
 
     let mut extensions = Vec::new();
-    let key_usage = None;
-    let subject_alt_names = Vec::new();
+    let mut key_usage = None;
+    let mut path_len_constraint = None;
+    let mut extended_key_usage = Vec::new();
+    let mut policies = Vec::new();
+    let mut name_constraints = None;
+    let mut subject_alt_names = Vec::new();
+    let mut crl_urls = Vec::new();
+    let mut ocsp_urls = Vec::new();
+    let mut ca_issuer_urls = Vec::new();
+    let mut subject_key_id = None;
+    let mut authority_key_id = None;
+    let mut scts = Vec::new();
 
     for ext in cert.extensions() {
         let oid_str = ext.oid.to_string();
         let critical = ext.critical;
-        let value = format!("{:?}", ext.value);
+        let value = format_extension_value(ext);
+
+        match ext.parsed_extension() {
+            ParsedExtension::KeyUsage(ku) => {
+                key_usage = Some(ku.to_string());
+            }
+            ParsedExtension::BasicConstraints(bc) => {
+                path_len_constraint = bc.path_len_constraint;
+            }
+            ParsedExtension::ExtendedKeyUsage(eku) => {
+                extended_key_usage = extended_key_usage_names(eku);
+            }
+            ParsedExtension::CertificatePolicies(certificate_policies) => {
+                policies = policy_names(certificate_policies);
+            }
+            ParsedExtension::NameConstraints(constraints) => {
+                name_constraints = Some(NameConstraints {
+                    permitted: name_constraint_subtrees(constraints.permitted_subtrees.as_ref()),
+                    excluded: name_constraint_subtrees(constraints.excluded_subtrees.as_ref()),
+                });
+            }
+            ParsedExtension::SubjectAlternativeName(san) => {
+                subject_alt_names
+                    .extend(san.general_names.iter().filter_map(general_name_to_string));
+            }
+            ParsedExtension::CRLDistributionPoints(points) => {
+                for point in points.iter() {
+                    if let Some(DistributionPointName::FullName(names)) = &point.distribution_point
+                    {
+                        crl_urls.extend(names.iter().filter_map(|name| match name {
+                            GeneralName::URI(uri) => Some((*uri).to_string()),
+                            _ => None,
+                        }));
+                    }
+                }
+            }
+            ParsedExtension::SubjectKeyIdentifier(ski) => {
+                subject_key_id = Some(hex_colon(ski.0));
+            }
+            ParsedExtension::AuthorityKeyIdentifier(aki) => {
+                authority_key_id = aki
+                    .key_identifier
+                    .as_ref()
+                    .map(|key_id| hex_colon(key_id.0));
+            }
+            ParsedExtension::SCT(list) => scts = sct_infos(list),
+            ParsedExtension::AuthorityInfoAccess(aia) => {
+                for desc in aia.iter() {
+                    let GeneralName::URI(uri) = desc.access_location else {
+                        continue;
+                    };
+                    match desc.access_method.to_id_string().as_str() {
+                        OID_AD_OCSP => ocsp_urls.push(uri.to_string()),
+                        OID_AD_CA_ISSUERS => ca_issuer_urls.push(uri.to_string()),
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
 
         extensions.push(ExtensionInfo {
             oid: oid_str.clone(),
@@ -186,6 +935,7 @@ pub fn extract_cert_info(cert: &X509Certificate) -> CertificateInfo {
     }
 
     let is_ca = cert.is_ca();
+    let violations = check_empty_subject_san(cert).into_iter().collect();
 
     CertificateInfo {
         subject,
@@ -193,12 +943,655 @@ pub fn extract_cert_info(cert: &X509Certificate) -> CertificateInfo {
         serial_number: serial,
         not_before,
         not_after,
+        not_before_dt,
+        not_after_dt,
         public_key_algorithm: public_key_alg,
+        rsa_key_bits,
         signature_algorithm,
+        signature_oid: sig_alg_oid,
         version: cert.version.0,
         extensions,
         is_ca,
+        path_len_constraint,
         key_usage,
+        extended_key_usage,
+        policies,
+        name_constraints,
         subject_alt_names,
+        crl_urls,
+        ocsp_urls,
+        ca_issuer_urls,
+        scts,
+        violations,
+        subject_key_id,
+        authority_key_id,
+        sha256_fingerprint: fingerprint::<Sha256>(der),
+        sha1_fingerprint: fingerprint::<Sha1>(der),
+        der_size: der.len(),
+        tbs_size: cert.tbs_certificate.as_ref().len(),
+        hostname_check: None,
+        tls_connection: None,
+        der: der.to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEAF_PEM: &[u8] = include_bytes!("../test/leaf.pem");
+    const INTERMEDIATE_PEM: &[u8] = include_bytes!("../test/intermediate.pem");
+
+    #[test]
+    fn test_unsupported_key_type_names_its_oid() {
+        let cert = &parse_certificate_chain(include_bytes!("../test/ed448.pem")).unwrap()[0];
+
+        assert_eq!(
+            cert.public_key_algorithm,
+            "Unknown (OID 1.3.101.113, Ed448)"
+        );
+    }
+
+    #[test]
+    fn test_unparseable_key_reports_reason_and_oid() {
+        // Turn the RSAPublicKey SEQUENCE inside the SPKI bit string into a SET
+        let (_, pem) = x509_parser::pem::parse_x509_pem(LEAF_PEM).unwrap();
+        let mut der = pem.contents;
+        let spki = [0x03, 0x82, 0x01, 0x0F, 0x00, 0x30];
+        let at = der.windows(spki.len()).position(|w| w == spki).unwrap();
+        der[at + spki.len() - 1] = 0x31;
+
+        let cert = &parse_certificate_chain(&der).unwrap()[0];
+        let algorithm = &cert.public_key_algorithm;
+        assert!(
+            algorithm.starts_with("Unknown (unparseable: "),
+            "{algorithm}"
+        );
+        assert!(algorithm.ends_with("; OID 1.2.840.113549.1.1.1, RSA Encryption)"));
+        assert_eq!(cert.rsa_key_bits, None);
+    }
+
+    #[test]
+    fn test_serial_matches_openssl() {
+        // `openssl x509 -serial` prints 01173E0BE849F6B346FA48E72EC8404F2865EF78;
+        // the leading zero of this odd-length value used to be lost
+        let cert = &parse_certificate_chain(include_bytes!("../test/ec_p384.pem")).unwrap()[0];
+        assert_eq!(
+            cert.serial_number,
+            "01 17 3e 0b e8 49 f6 b3 46 fa 48 e7 2e c8 40 4f 28 65 ef 78"
+        );
+
+        // 00 A1 B2 ... in DER: the sign padding is dropped, as openssl does (A1B2C3D4E5F60718)
+        let leaf = &parse_certificate_chain(LEAF_PEM).unwrap()[0];
+        assert_eq!(leaf.serial_number, "a1 b2 c3 d4 e5 f6 07 18");
+    }
+
+    #[test]
+    fn test_format_serial_edge_cases() {
+        assert_eq!(format_serial(&[0x00]), "00");
+        assert_eq!(format_serial(&[]), "00");
+        assert_eq!(format_serial(&[0x0A]), "0a");
+        assert_eq!(format_serial(&[0x00, 0x80, 0x01]), "80 01");
+        assert_eq!(format_serial(&[0xFF]), "-01");
+        assert_eq!(format_serial(&[0xFF, 0x00]), "-01 00");
+        assert_eq!(format_serial(&[0x80, 0x00]), "-80 00");
+        let large = [0x7F; 32];
+        assert_eq!(format_serial(&large).len(), 32 * 3 - 1);
+    }
+
+    #[test]
+    fn test_validity_dates_are_typed() {
+        let (_, der) = x509_parser::pem::parse_x509_pem(LEAF_PEM).unwrap();
+        let parsed = der.parse_x509().unwrap();
+        let leaf = &parse_certificate_chain(LEAF_PEM).unwrap()[0];
+
+        assert_eq!(
+            leaf.not_before_dt.timestamp(),
+            parsed.validity().not_before.timestamp()
+        );
+        assert_eq!(
+            leaf.not_after_dt.timestamp(),
+            parsed.validity().not_after.timestamp()
+        );
+        assert_eq!(leaf.not_before_dt.to_rfc3339(), "2025-01-01T00:00:00+00:00");
+        assert_eq!(leaf.not_after_dt.to_rfc3339(), "2099-12-31T23:59:59+00:00");
+        assert_eq!(leaf.not_after, "2099-12-31 23:59:59");
+    }
+
+    #[test]
+    fn test_dv_certificate_policy_is_named() {
+        let cert = &parse_certificate_chain(include_bytes!("../test/dv_policy.pem")).unwrap()[0];
+        assert_eq!(
+            cert.policies,
+            vec!["Domain Validated (DV)", "1.3.6.1.4.1.44947.1.1.1"]
+        );
+
+        let ext = cert
+            .extensions
+            .iter()
+            .find(|ext| ext.oid == "2.5.29.32")
+            .unwrap();
+        assert_eq!(ext.value, "Domain Validated (DV), 1.3.6.1.4.1.44947.1.1.1");
+    }
+
+    #[test]
+    fn test_intermediate_path_len_constraint() {
+        let intermediate = &parse_certificate_chain(INTERMEDIATE_PEM).unwrap()[0];
+        assert!(intermediate.is_ca);
+        assert_eq!(intermediate.path_len_constraint, Some(0));
+        assert_eq!(intermediate.is_ca_display(), "true (pathlen: 0)");
+
+        let leaf = &parse_certificate_chain(LEAF_PEM).unwrap()[0];
+        assert_eq!(leaf.path_len_constraint, None);
+    }
+
+    #[test]
+    fn test_weak_signature_sha1_rsa() {
+        let cert = &parse_certificate_chain(include_bytes!("../test/single_cert.pem")).unwrap()[0];
+        assert_eq!(cert.signature_algorithm, "SHA1 with RSA");
+        assert_eq!(cert.signature_oid, "1.2.840.113549.1.1.5");
+        assert!(cert.is_weak_signature());
+
+        let leaf = &parse_certificate_chain(LEAF_PEM).unwrap()[0];
+        assert!(!leaf.is_weak_signature());
+        assert!(!leaf.is_weak_key());
+    }
+
+    #[test]
+    fn test_weak_signature_rsa_1024() {
+        let cert = &parse_certificate_chain(include_bytes!("../test/rsa1024.pem")).unwrap()[0];
+        assert_eq!(cert.signature_algorithm, "SHA256 with RSA");
+        assert_eq!(cert.rsa_key_bits, Some(1024));
+        assert_eq!(cert.public_key_algorithm, "RSA (1024 bits)");
+        assert!(cert.is_weak_key());
+        assert!(!cert.is_weak_signature());
+    }
+
+    #[test]
+    fn test_jwks_x5c_chain() {
+        let jwks = include_bytes!("../test/jwks.json");
+
+        let certs = parse_certificate_chain(jwks).unwrap();
+        assert_eq!(certs.len(), 2);
+        assert!(certs[0].subject.contains("www.example.com"));
+        assert!(certs[1].subject.contains("Test Intermediate CA"));
+        assert_eq!(
+            parse_certificate_chain_as(jwks, InputFormat::Jwk)
+                .unwrap()
+                .len(),
+            2
+        );
+
+        // A bare JWK is accepted as well
+        let jwks: serde_json::Value = serde_json::from_slice(jwks).unwrap();
+        let jwk = serde_json::to_vec(&jwks["keys"][0]).unwrap();
+        assert_eq!(parse_certificate_chain(&jwk).unwrap().len(), 2);
+
+        let without_x5c = serde_json::to_vec(&jwks["keys"][1]).unwrap();
+        assert!(parse_certificate_chain(&without_x5c).is_err());
+    }
+
+    #[test]
+    fn test_pkcs7_bundle() {
+        let p7b = include_bytes!("../test/chain.p7b");
+        let certs = parse_certificate_chain(p7b).unwrap();
+        assert_eq!(certs.len(), 2);
+        assert!(certs
+            .iter()
+            .any(|cert| cert.subject.contains("www.example.com")));
+        assert!(certs
+            .iter()
+            .any(|cert| cert.subject.contains("Test Intermediate CA")));
+
+        // The same bundle armored as PEM
+        let armored = pem::encode(&pem::Pem::new("PKCS7", p7b.to_vec()));
+        assert_eq!(
+            parse_certificate_chain(armored.as_bytes()).unwrap().len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_corrupt_pem_reports_pem_error() {
+        let corrupt = b"-----BEGIN CERTIFICATE-----\nnot*base64!\n-----END CERTIFICATE-----\n";
+        let result = parse_certificate_chain(corrupt);
+        assert!(matches!(result, Err(CertError::PemParse(_))));
+    }
+
+    #[test]
+    fn test_commented_bundle_skips_malformed_block() {
+        // Mozilla-style headings between the blocks, and one block with damaged base64
+        let certs =
+            parse_certificate_chain(include_bytes!("../test/commented_bundle.pem")).unwrap();
+
+        assert_eq!(certs.len(), 2);
+        assert!(certs[0].subject.contains("Test Root CA"));
+        assert!(certs[1].subject.contains("Test Intermediate CA"));
+    }
+
+    #[test]
+    fn test_key_only_pem_has_no_certificates() {
+        let key_only = include_bytes!("../test/leaf.key");
+        let result = parse_certificate_chain(key_only);
+        assert!(matches!(
+            result,
+            Err(CertError::NoCertificates(message)) if message.ends_with("only PRIVATE KEY")
+        ));
+
+        let result = parse_certificate_chain_as(key_only, InputFormat::Pem);
+        assert!(matches!(result, Err(CertError::NoCertificates(_))));
+    }
+
+    #[test]
+    fn test_forced_der_on_pem_data_fails() {
+        let result = parse_certificate_chain_as(LEAF_PEM, InputFormat::Der);
+        assert!(matches!(result, Err(CertError::X509Parse(_))));
+
+        let der = pem::parse(LEAF_PEM).unwrap().into_contents();
+        let certs = parse_certificate_chain_as(&der, InputFormat::Der).unwrap();
+        assert_eq!(certs.len(), 1);
+        assert!(parse_certificate_chain_as(&der, InputFormat::Pem).is_err());
+    }
+
+    #[test]
+    fn test_hex_der_parses_like_pem() {
+        let leaf = &parse_certificate_chain(LEAF_PEM).unwrap()[0];
+        // Colon-separated and wrapped over lines, like a pasted hex dump
+        let hex = leaf
+            .der
+            .chunks(16)
+            .map(|line| {
+                line.iter()
+                    .map(|byte| format!("{byte:02x}"))
+                    .collect::<Vec<_>>()
+                    .join(":")
+            })
+            .collect::<Vec<_>>()
+            .join("\n    ");
+
+        let parsed = parse_hex_certificate(&hex).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].sha256_fingerprint, leaf.sha256_fingerprint);
+        assert_eq!(decode_hex("30 82 0A ff").unwrap(), [0x30, 0x82, 0x0a, 0xff]);
+    }
+
+    #[test]
+    fn test_invalid_hex_is_rejected() {
+        match decode_hex("30820") {
+            Err(CertError::Hex(message)) => assert!(message.contains("odd number")),
+            other => panic!("expected a hex error, got {other:?}"),
+        }
+        match decode_hex("3082zz") {
+            Err(CertError::Hex(message)) => assert!(message.contains("'z'")),
+            other => panic!("expected a hex error, got {other:?}"),
+        }
+        assert!(matches!(
+            parse_hex_certificate("3082"),
+            Err(CertError::X509Parse(_) | CertError::NoCertificates(_))
+        ));
+    }
+
+    #[test]
+    fn test_extract_cn_escaped_comma() {
+        assert_eq!(extract_cn(r"CN=Smith\, John, O=Example"), "Smith, John");
+        assert_eq!(extract_cn(r"O=Example\2C Inc, CN=Hex\2Cname"), "Hex,name");
+    }
+
+    #[test]
+    fn test_extract_cn_quoted_value() {
+        assert_eq!(
+            extract_cn(r#"O="Acme, Inc.", CN="Doe, Jane + Co""#),
+            "Doe, Jane + Co"
+        );
+    }
+
+    #[test]
+    fn test_extract_cn_multi_valued_rdn() {
+        assert_eq!(extract_cn("OU=Sales+CN=J. Smith, O=Widget"), "J. Smith");
+        assert_eq!(
+            extract_cn("C=LT, O=No Common Name"),
+            "C=LT, O=No Common Name"
+        );
+    }
+
+    #[test]
+    fn test_several_cn_components() {
+        let subject = "CN=www.example.com, O=Example, CN=example.com";
+        assert_eq!(extract_cn(subject), "www.example.com");
+        assert_eq!(
+            extract_all_cns(subject),
+            vec!["www.example.com", "example.com"]
+        );
+        assert!(extract_all_cns("C=LT, O=No Common Name").is_empty());
+    }
+
+    #[test]
+    fn test_dn_components_in_order() {
+        let pairs = dn_components(
+            r"C=US, ST=New Jersey, L=Jersey City, O=Smith\, Jones, OU=IT+OU=Ops, CN=example.com",
+        );
+        let expected = [
+            ("C", "US"),
+            ("ST", "New Jersey"),
+            ("L", "Jersey City"),
+            ("O", "Smith, Jones"),
+            ("OU", "IT"),
+            ("OU", "Ops"),
+            ("CN", "example.com"),
+        ];
+        assert_eq!(
+            pairs,
+            expected
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect::<Vec<_>>()
+        );
+        assert!(dn_components("").is_empty());
+    }
+
+    #[test]
+    fn test_name_constraints_of_constrained_intermediate() {
+        let cert =
+            &parse_certificate_chain(include_bytes!("../test/name_constrained_intermediate.pem"))
+                .unwrap()[0];
+
+        let constraints = cert.name_constraints.as_ref().unwrap();
+        assert_eq!(
+            constraints.permitted,
+            [
+                "DNS:.example.com",
+                "IP Address:10.0.0.0/8",
+                "email:example.com"
+            ]
+        );
+        assert_eq!(
+            constraints.excluded,
+            ["DNS:internal.example.com", "IP Address:10.1.0.0/16"]
+        );
+
+        let ext = cert
+            .extensions
+            .iter()
+            .find(|ext| ext.oid == "2.5.29.30")
+            .unwrap();
+        assert!(ext.critical);
+        assert!(ext.value.starts_with("Permitted: DNS:.example.com"));
+        assert!(ext.value.contains("; Excluded: DNS:internal.example.com"));
+
+        let leaf = &parse_certificate_chain(LEAF_PEM).unwrap()[0];
+        assert!(leaf.name_constraints.is_none());
+    }
+
+    #[test]
+    fn test_extract_cert_info_key_usage_and_sans() {
+        let certs = parse_certificate_chain(LEAF_PEM).unwrap();
+        let cert = &certs[0];
+
+        assert_eq!(
+            cert.key_usage.as_deref(),
+            Some("Digital Signature, Key Encipherment")
+        );
+        assert_eq!(
+            cert.extended_key_usage,
+            vec![
+                "TLS Web Server Authentication",
+                "TLS Web Client Authentication"
+            ]
+        );
+        assert_eq!(
+            cert.subject_alt_names,
+            vec![
+                "www.example.com",
+                "example.com",
+                "localhost",
+                "127.0.0.1",
+                "::1",
+                "admin@example.com",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_embedded_scts_are_decoded() {
+        // Two real SCTs from the Google Pilot and Symantec logs, embedded in a test leaf
+        let cert = &parse_certificate_chain(include_bytes!("../test/sct_leaf.pem")).unwrap()[0];
+
+        assert_eq!(cert.scts.len(), 2);
+        let pilot = &cert.scts[0];
+        assert_eq!(pilot.log_id, "pLkJkLQYWBSHuxOizGdwCjw1mAT5G9+443fNDsgN3BA=");
+        assert_eq!(pilot.operator.as_deref(), Some("Google"));
+        assert_eq!(pilot.timestamp.timestamp_millis(), 1_498_648_485_628);
+        assert_eq!(pilot.hash_algorithm, "SHA-256");
+        assert_eq!(pilot.signature_algorithm, "ECDSA");
+        assert_eq!(
+            cert.scts[1].text(),
+            "Symantec log (Symantec), 2017-06-28 11:14:45 UTC, SHA-256/ECDSA"
+        );
+
+        let ext = cert
+            .extensions
+            .iter()
+            .find(|ext| ext.oid == "1.3.6.1.4.1.11129.2.4.2")
+            .unwrap();
+        assert!(ext
+            .value
+            .starts_with("Google 'Pilot' log (Google), 2017-06-28"));
+    }
+
+    #[test]
+    fn test_extract_cert_info_without_key_usage() {
+        // The Chunghwa ePKI root in the bundle carries neither Key Usage nor SAN
+        let bundle = parse_certificate_chain(include_bytes!("../test/cacert.pem")).unwrap();
+        let epki = bundle
+            .iter()
+            .find(|c| c.subject.contains("ePKI Root Certification Authority"))
+            .unwrap();
+
+        assert!(epki.key_usage.is_none());
+        assert!(epki.subject_alt_names.is_empty());
+    }
+
+    #[test]
+    fn test_empty_subject_with_non_critical_san_is_violation() {
+        let certs =
+            parse_certificate_chain(include_bytes!("../test/empty_subject_san_noncritical.pem"))
+                .unwrap();
+
+        assert_eq!(certs[0].subject, "");
+        assert_eq!(certs[0].violations.len(), 1);
+        assert!(certs[0].violations[0].contains("critical"));
+    }
+
+    #[test]
+    fn test_empty_subject_with_critical_san_conforms() {
+        let certs =
+            parse_certificate_chain(include_bytes!("../test/empty_subject_san_critical.pem"))
+                .unwrap();
+
+        assert_eq!(certs[0].subject, "");
+        assert!(certs[0].violations.is_empty());
+    }
+
+    #[test]
+    fn test_non_empty_subject_has_no_san_violation() {
+        let certs = parse_certificate_chain(LEAF_PEM).unwrap();
+        assert!(certs[0].violations.is_empty());
+    }
+
+    /// Find the decoded value of the extension with the given OID
+    fn extension_value(cert: &CertificateInfo, oid: &str) -> String {
+        cert.extensions
+            .iter()
+            .find(|ext| ext.oid == oid)
+            .map(|ext| ext.value.clone())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_format_basic_constraints() {
+        let leaf = &parse_certificate_chain(LEAF_PEM).unwrap()[0];
+        let intermediate = &parse_certificate_chain(INTERMEDIATE_PEM).unwrap()[0];
+
+        assert_eq!(extension_value(leaf, "2.5.29.19"), "CA:FALSE");
+        assert_eq!(
+            extension_value(intermediate, "2.5.29.19"),
+            "CA:TRUE, pathlen:0"
+        );
+    }
+
+    #[test]
+    fn test_format_key_usage() {
+        let leaf = &parse_certificate_chain(LEAF_PEM).unwrap()[0];
+        assert_eq!(
+            extension_value(leaf, "2.5.29.15"),
+            "Digital Signature, Key Encipherment"
+        );
+    }
+
+    #[test]
+    fn test_format_extended_key_usage() {
+        let leaf = &parse_certificate_chain(LEAF_PEM).unwrap()[0];
+        assert_eq!(
+            extension_value(leaf, "2.5.29.37"),
+            "TLS Web Server Authentication, TLS Web Client Authentication"
+        );
+    }
+
+    #[test]
+    fn test_format_subject_alternative_name() {
+        let leaf = &parse_certificate_chain(LEAF_PEM).unwrap()[0];
+        assert_eq!(
+            extension_value(leaf, "2.5.29.17"),
+            "DNS:www.example.com, DNS:example.com, DNS:localhost, IP Address:127.0.0.1, \
+             IP Address:::1, email:admin@example.com"
+        );
+    }
+
+    #[test]
+    fn test_format_authority_information_access() {
+        let leaf = &parse_certificate_chain(LEAF_PEM).unwrap()[0];
+        assert_eq!(
+            extension_value(leaf, "1.3.6.1.5.5.7.1.1"),
+            "OCSP - URI:http://ocsp.example.com, \
+             CA Issuers - URI:http://pki.example.com/intermediate.der"
+        );
+    }
+
+    #[test]
+    fn test_format_crl_distribution_points() {
+        let leaf = &parse_certificate_chain(LEAF_PEM).unwrap()[0];
+        assert_eq!(
+            extension_value(leaf, "2.5.29.31"),
+            "URI:http://crl.example.com/intermediate.crl, \
+             URI:ldap://ldap.example.com/cn=Intermediate,o=Example?certificateRevocationList"
+        );
+    }
+
+    #[test]
+    fn test_format_unknown_extension_as_hex() {
+        // Entrust version information (1.2.840.113533.7.65.0) has no parsed form
+        let certs = parse_certificate_chain(include_bytes!("../test/single_cert.pem")).unwrap();
+        let value = extension_value(&certs[0], "1.2.840.113533.7.65.0");
+
+        assert!(value.starts_with("30:"));
+        assert!(!value.contains('['));
+    }
+
+    #[test]
+    fn test_fingerprints_match_openssl() {
+        // openssl x509 -in test/leaf.pem -noout -fingerprint -sha256 / -sha1
+        let leaf = &parse_certificate_chain(LEAF_PEM).unwrap()[0];
+
+        assert_eq!(
+            leaf.sha256_fingerprint,
+            "4B:C8:4B:D0:32:53:15:19:1F:5B:4A:AC:A0:BA:31:37:A3:C5:E8:AE:DF:5F:A0:94:D9:B7:CC:50:31:E5:F2:3B"
+        );
+        assert_eq!(
+            leaf.sha1_fingerprint,
+            "5F:8C:95:38:6D:C6:93:2C:0D:D3:9C:26:02:02:FB:D2:F8:A1:44:B2"
+        );
+    }
+
+    #[test]
+    fn test_sizes_match_der_encoding() {
+        // openssl asn1parse -in test/sct_leaf.pem: 691 + 4 bytes, TBS 601 + 4
+        let cert = &parse_certificate_chain(include_bytes!("../test/sct_leaf.pem")).unwrap()[0];
+        assert_eq!(cert.der_size, 695);
+        assert_eq!(cert.tbs_size, 605);
+        assert_eq!(cert.size_text(), "695 bytes (TBS: 605 bytes)");
+
+        let from_der = &parse_certificate_chain(&cert.der).unwrap()[0];
+        assert_eq!(from_der.der_size, cert.der.len());
+    }
+
+    #[test]
+    fn test_signature_alg_to_name_modern_algorithms() {
+        assert_eq!(
+            signature_alg_to_name("1.3.101.112").as_deref(),
+            Some("Ed25519")
+        );
+        assert_eq!(
+            signature_alg_to_name("1.3.101.113").as_deref(),
+            Some("Ed448")
+        );
+        assert_eq!(
+            signature_alg_to_name("1.2.840.113549.1.1.10").as_deref(),
+            Some("RSA-PSS")
+        );
+    }
+
+    #[test]
+    fn test_ec_public_key_shows_curve() {
+        let certs = parse_certificate_chain(include_bytes!("../test/ec_p384.pem")).unwrap();
+        assert_eq!(certs[0].public_key_algorithm, "ECDSA (P-384)");
+    }
+
+    #[test]
+    fn test_curve_oid_to_name() {
+        assert_eq!(
+            curve_oid_to_name("1.2.840.10045.3.1.7").as_deref(),
+            Some("P-256")
+        );
+        assert_eq!(
+            curve_oid_to_name("1.3.132.0.10").as_deref(),
+            Some("secp256k1")
+        );
+        assert_eq!(curve_oid_to_name("1.2.3.4"), None);
+    }
+
+    #[test]
+    fn test_crl_urls_extracted() {
+        let leaf = &parse_certificate_chain(LEAF_PEM).unwrap()[0];
+        assert_eq!(
+            leaf.crl_urls,
+            vec![
+                "http://crl.example.com/intermediate.crl",
+                "ldap://ldap.example.com/cn=Intermediate,o=Example?certificateRevocationList",
+            ]
+        );
+
+        let intermediate = &parse_certificate_chain(INTERMEDIATE_PEM).unwrap()[0];
+        assert_eq!(
+            intermediate.crl_urls,
+            vec!["http://crl.example.com/root.crl"]
+        );
+    }
+
+    #[test]
+    fn test_aia_urls_extracted() {
+        let leaf = &parse_certificate_chain(LEAF_PEM).unwrap()[0];
+        assert_eq!(leaf.ocsp_urls, vec!["http://ocsp.example.com"]);
+        assert_eq!(
+            leaf.ca_issuer_urls,
+            vec!["http://pki.example.com/intermediate.der"]
+        );
+
+        // The intermediate only carries a CA Issuers access description
+        let intermediate = &parse_certificate_chain(INTERMEDIATE_PEM).unwrap()[0];
+        assert!(intermediate.ocsp_urls.is_empty());
+        assert_eq!(
+            intermediate.ca_issuer_urls,
+            vec!["http://pki.example.com/root.der"]
+        );
     }
 }
@@ -1,98 +1,1095 @@
-use crate::models::{CertificateInfo, CertificateNode, CertificateTree, ValidationStatus};
-use std::collections::HashMap;
+use crate::models::{
+    AnchorStatus, CertificateInfo, CertificateNode, CertificateRole, CertificateTree, ChainSummary,
+    RevocationStatus, SignatureStrength, TrustAnchor, TrustStatus, ValidationStatus,
+    ValidityStatus,
+};
+use crate::parser::extract_cn;
+use chrono::{DateTime, Utc};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use tracing::trace;
+use x509_parser::prelude::{FromDer, SubjectPublicKeyInfo, X509Certificate};
 
+/// Deepest a chain is nested; anything below continues as a subtree of its own
+///
+/// Keeps the recursive builders and renderers within the stack on pathological input.
+const MAX_TREE_DEPTH: usize = 64;
+
+/// Arrange the certificates into issuer/subject trees and validate every chain
+#[must_use]
 pub fn build_certificate_tree(certificates: &[CertificateInfo]) -> CertificateTree {
-    let mut cert_map: HashMap<String, CertificateInfo> = HashMap::new();
-    let mut issuer_map: HashMap<String, Vec<String>> = HashMap::new();
+    let mut tree = build_unvalidated_tree(certificates);
+    validate_certificate_chain(&mut tree);
+    tree
+}
 
-    // Build maps for quick lookup
-    for cert in certificates {
-        cert_map.insert(cert.subject.clone(), cert.clone());
+/// Arrange the certificates into issuer/subject trees without checking signatures
+///
+/// Every node's validation status is `NotChecked`; this is what `--no-validate`
+/// displays, and the cheap path for large bundles. Each root selection and
+/// parent-child link is logged at trace level (`-vv`).
+#[must_use]
+pub fn build_unvalidated_tree(certificates: &[CertificateInfo]) -> CertificateTree {
+    let mut subjects: HashSet<&str> = HashSet::new();
+    let mut by_subject: HashMap<&str, Vec<usize>> = HashMap::new();
+    let mut by_key_id: HashMap<&str, Vec<usize>> = HashMap::new();
 
-        // Group certificates by issuer
-        issuer_map
-            .entry(cert.issuer.clone())
-            .or_default()
-            .push(cert.subject.clone());
+    // Build maps for quick lookup; certificates are tracked by index so that
+    // distinct certificates sharing a subject (e.g. cross-signed CAs) all appear
+    for (index, cert) in certificates.iter().enumerate() {
+        subjects.insert(&cert.subject);
+        by_subject.entry(&cert.subject).or_default().push(index);
+        if let Some(key_id) = &cert.subject_key_id {
+            by_key_id.entry(key_id).or_default().push(index);
+        }
+    }
+
+    // Link each certificate to the certificates that may have issued it
+    let mut issued: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut has_issuer = vec![false; certificates.len()];
+    for (index, cert) in certificates.iter().enumerate() {
+        for &parent in issuer_candidates(cert, &by_subject, &by_key_id) {
+            if parent != index {
+                trace!(
+                    child = ?cert.subject,
+                    parent = ?certificates[parent].subject,
+                    "found issuer candidate"
+                );
+                issued.entry(parent).or_default().push(index);
+                has_issuer[index] = true;
+            }
+        }
     }
 
-    // Find root certificates (self-signed or where issuer is not in our set)
     let mut roots = Vec::new();
-    let mut processed = std::collections::HashSet::new();
+    let mut processed = HashSet::new();
 
-    for cert in certificates {
-        if !cert_map.contains_key(&cert.issuer) || cert.subject == cert.issuer {
-            // This is a root certificate
-            if !processed.contains(&cert.subject) {
-                let node = build_tree_node(cert, &cert_map, &issuer_map, &mut processed);
-                roots.push(node);
-            }
+    // Find root certificates (self-signed or where no issuer is in our set)
+    for (index, cert) in certificates.iter().enumerate() {
+        let is_root = cert.subject == cert.issuer || !has_issuer[index];
+        if is_root && !processed.contains(&index) {
+            let reason = if cert.subject == cert.issuer {
+                "self-issued"
+            } else {
+                "issuer not in input"
+            };
+            trace!(subject = ?cert.subject, reason, "selected root");
+            roots.push(build_tree_node(
+                index,
+                certificates,
+                &issued,
+                &mut processed,
+                subjects.contains(cert.issuer.as_str()),
+                1,
+            ));
         }
     }
 
-    // Handle any remaining certificates that might not have been processed
+    // Whatever remains hangs off an issuer cycle (A issued by B, B issued by A)
+    // or below MAX_TREE_DEPTH; break each cycle at a deterministic member and
+    // build from there
+    while let Some(index) = cycle_root(certificates, &processed) {
+        trace!(
+            subject = ?certificates[index].subject,
+            reason = "breaks an issuer cycle or continues below the depth limit",
+            "selected root"
+        );
+        roots.push(build_tree_node(
+            index,
+            certificates,
+            &issued,
+            &mut processed,
+            true,
+            1,
+        ));
+    }
+
+    CertificateTree {
+        roots,
+        anchor_status: None,
+    }
+}
+
+/// Certificates that may have issued `cert`
+///
+/// The Authority Key Identifier is authoritative: certificates whose Subject
+/// Key Identifier matches it are the issuers even if other certificates share
+/// the issuer DN. Without a key match, every certificate whose subject equals
+/// the issuer DN is a candidate.
+fn issuer_candidates<'a>(
+    cert: &CertificateInfo,
+    by_subject: &'a HashMap<&str, Vec<usize>>,
+    by_key_id: &'a HashMap<&str, Vec<usize>>,
+) -> &'a [usize] {
+    cert.authority_key_id
+        .as_deref()
+        .and_then(|key_id| by_key_id.get(key_id))
+        .or_else(|| by_subject.get(cert.issuer.as_str()))
+        .map_or(&[], Vec::as_slice)
+}
+
+/// Top of the tree branch that holds `cert`, i.e. its topmost ancestor in the set
+///
+/// Certificates are identified by serial number and issuer. Returns `None` if
+/// `cert` is not in the tree.
+#[must_use]
+pub fn topmost_ancestor<'a>(
+    tree: &'a CertificateTree,
+    cert: &CertificateInfo,
+) -> Option<&'a CertificateInfo> {
+    fn contains(node: &CertificateNode, cert: &CertificateInfo) -> bool {
+        (node.cert.serial_number == cert.serial_number && node.cert.issuer == cert.issuer)
+            || node.children.iter().any(|child| contains(child, cert))
+    }
+
+    tree.roots
+        .iter()
+        .find(|root| contains(root, cert))
+        .map(|root| &root.cert)
+}
+
+/// Subjects claimed by more than one distinct certificate, sorted
+///
+/// Renewed certificates (same subject, new serial) and cross-signed CAs both
+/// show up here; the tree keeps every one of them under its own issuer.
+#[must_use]
+pub fn subject_conflicts(certificates: &[CertificateInfo]) -> Vec<String> {
+    let mut by_subject: HashMap<&str, HashSet<(&str, &str)>> = HashMap::new();
     for cert in certificates {
-        if !processed.contains(&cert.subject) {
-            let node = build_tree_node(cert, &cert_map, &issuer_map, &mut processed);
-            roots.push(node);
+        by_subject
+            .entry(&cert.subject)
+            .or_default()
+            .insert((&cert.serial_number, &cert.issuer));
+    }
+
+    let mut conflicts: Vec<String> = by_subject
+        .into_iter()
+        .filter(|(_, certs)| certs.len() > 1)
+        .map(|(subject, _)| subject.to_string())
+        .collect();
+    conflicts.sort();
+    conflicts
+}
+
+/// Which certificates `--only-expired`, `--only-expiring` and `--expire-before` keep
+#[derive(Debug, Clone, Default)]
+pub struct CertificateFilter {
+    /// Validity statuses to keep; empty keeps every status
+    pub statuses: Vec<ValidityStatus>,
+    /// Keep only certificates whose `not_after` is earlier than this
+    pub expire_before: Option<DateTime<Utc>>,
+}
+
+impl CertificateFilter {
+    /// Whether `cert` passes every condition that is set
+    #[must_use]
+    pub fn matches(&self, cert: &CertificateInfo) -> bool {
+        let status = ValidityStatus::from_dates(cert.not_before_dt, cert.not_after_dt);
+        (self.statuses.is_empty() || self.statuses.contains(&status))
+            && self
+                .expire_before
+                .is_none_or(|cutoff| cert.not_after_dt < cutoff)
+    }
+}
+
+/// Ordering applied to certificate lists and the tree's top-level roots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortOrder {
+    /// Soonest `not_after` first
+    Expiry,
+    /// Subject common name, case-insensitive
+    Cn,
+    /// Issuer common name, case-insensitive
+    Issuer,
+    /// Keep tree order
+    #[default]
+    None,
+}
+
+impl SortOrder {
+    /// Compare two certificates under this ordering; `None` treats all as equal
+    #[must_use]
+    pub fn compare(self, a: &CertificateInfo, b: &CertificateInfo) -> Ordering {
+        match self {
+            SortOrder::Expiry => a.not_after_dt.cmp(&b.not_after_dt),
+            SortOrder::Cn => extract_cn(&a.subject)
+                .to_lowercase()
+                .cmp(&extract_cn(&b.subject).to_lowercase()),
+            SortOrder::Issuer => extract_cn(&a.issuer)
+                .to_lowercase()
+                .cmp(&extract_cn(&b.issuer).to_lowercase()),
+            SortOrder::None => Ordering::Equal,
         }
     }
+}
 
-    let mut tree = CertificateTree { roots };
-    validate_certificate_chain(&mut tree);
-    tree
+/// Reorder the tree's top-level roots; children keep their chain order
+pub fn sort_roots(tree: &mut CertificateTree, order: SortOrder) {
+    tree.roots.sort_by(|a, b| order.compare(&a.cert, &b.cert));
+}
+
+/// Pick the certificate at which to break the next unprocessed issuer cycle
+///
+/// Starting from the lowest unprocessed certificate, issuer links are followed
+/// until one repeats; the cycle member with the smallest subject (then serial)
+/// becomes the root, so the result does not depend on input order.
+fn cycle_root(certificates: &[CertificateInfo], processed: &HashSet<usize>) -> Option<usize> {
+    let sort_key = |index: usize| {
+        let cert = &certificates[index];
+        (&cert.subject, &cert.serial_number, index)
+    };
+
+    let start = (0..certificates.len())
+        .filter(|index| !processed.contains(index))
+        .min_by_key(|&index| sort_key(index))?;
+
+    // Map each subject to its unprocessed certificates to walk towards issuers
+    let mut by_subject: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, cert) in certificates.iter().enumerate() {
+        if !processed.contains(&index) {
+            by_subject
+                .entry(cert.subject.as_str())
+                .or_default()
+                .push(index);
+        }
+    }
+
+    let mut path = vec![start];
+    let mut current = start;
+    loop {
+        let issuer = certificates[current].issuer.as_str();
+        let Some(next) = by_subject
+            .get(issuer)
+            .and_then(|candidates| candidates.iter().copied().min_by_key(|&i| sort_key(i)))
+        else {
+            // Issuer already placed elsewhere; this certificate starts its own subtree
+            return Some(current);
+        };
+
+        if let Some(position) = path.iter().position(|&index| index == next) {
+            return path[position..]
+                .iter()
+                .copied()
+                .min_by_key(|&index| sort_key(index));
+        }
+        path.push(next);
+        current = next;
+    }
 }
 
 fn build_tree_node(
-    cert: &CertificateInfo,
-    cert_map: &HashMap<String, CertificateInfo>,
-    issuer_map: &HashMap<String, Vec<String>>,
-    processed: &mut std::collections::HashSet<String>,
+    index: usize,
+    certificates: &[CertificateInfo],
+    issued: &HashMap<usize, Vec<usize>>,
+    processed: &mut HashSet<usize>,
+    issuer_present: bool,
+    depth: usize,
 ) -> CertificateNode {
-    processed.insert(cert.subject.clone());
+    processed.insert(index);
+    let cert = &certificates[index];
 
-    let validity_status = crate::models::ValidityStatus::from_dates(&cert.not_after);
+    let validity_status = ValidityStatus::from_dates(cert.not_before_dt, cert.not_after_dt);
 
     let mut children = Vec::new();
-    if let Some(issued_certs) = issuer_map.get(&cert.subject) {
-        for subject in issued_certs {
-            if let Some(child_cert) = cert_map.get(subject) {
-                if !processed.contains(subject) {
-                    let child_node = build_tree_node(child_cert, cert_map, issuer_map, processed);
-                    children.push(child_node);
-                }
-            }
+    if depth >= MAX_TREE_DEPTH && issued.contains_key(&index) {
+        trace!(subject = ?cert.subject, "depth limit reached; children are not linked here");
+    }
+    let issued_certs = issued.get(&index).filter(|_| depth < MAX_TREE_DEPTH);
+    for &child in issued_certs.into_iter().flatten() {
+        if processed.contains(&child) {
+            trace!(
+                child = ?certificates[child].subject,
+                parent = ?cert.subject,
+                "child already placed elsewhere; not linked again"
+            );
+        } else {
+            trace!(
+                child = ?certificates[child].subject,
+                parent = ?cert.subject,
+                "linked child under parent"
+            );
+            let child_node =
+                build_tree_node(child, certificates, issued, processed, true, depth + 1);
+            children.push(child_node);
         }
     }
 
     CertificateNode {
         cert: cert.clone(),
         children,
+        role: CertificateRole::classify(cert, issuer_present),
         validity_status,
-        validation_status: ValidationStatus::Valid,
+        validation_status: ValidationStatus::NotChecked,
+        revocation_status: RevocationStatus::NotChecked,
+        trust_status: TrustStatus::NotChecked,
+        truncated: 0,
     }
 }
 
 pub fn validate_certificate_chain(tree: &mut CertificateTree) {
+    validate_certificate_chain_with_anchors(tree, &[]);
+}
+
+/// Validate the tree and terminate each chain against trust anchors
+///
+/// A chain's top certificate is `Anchored` when it is one of `anchors` (same
+/// subject and public key) or was signed by one; an intermediate signed by an
+/// anchor is then a complete chain rather than an `InvalidChain` with a missing
+/// issuer. Without anchors the trust status stays `NotChecked`.
+///
+/// With anchors the tree's `anchor_status` records where its chains end.
+pub fn validate_certificate_chain_with_anchors(
+    tree: &mut CertificateTree,
+    anchors: &[TrustAnchor],
+) {
     for root in &mut tree.roots {
         validate_node(root, None);
+        if anchors.is_empty() {
+            continue;
+        }
+
+        let parsed = X509Certificate::from_der(&root.cert.der).ok();
+        let is_anchor = parsed.as_ref().is_some_and(|(_, cert)| {
+            anchors.iter().any(|anchor| {
+                anchor.subject_der == cert.subject().as_raw()
+                    && anchor.spki_der == cert.public_key().raw
+            })
+        });
+        let signed_by_anchor = parsed.as_ref().is_some_and(|(_, cert)| {
+            anchors.iter().any(|anchor| {
+                anchor.subject_der == cert.issuer().as_raw()
+                    && verify_signed_by_anchor(cert, anchor)
+            })
+        });
+
+        root.trust_status = if is_anchor || signed_by_anchor {
+            if signed_by_anchor && root.validation_status.reason() == Some(ISSUER_NOT_FOUND) {
+                root.validation_status = ValidationStatus::Valid;
+            }
+            TrustStatus::Anchored
+        } else {
+            TrustStatus::Untrusted
+        };
+    }
+
+    if !anchors.is_empty() {
+        tree.anchor_status = Some(tree_anchor_status(&tree.roots));
     }
 }
 
+/// Worst anchor status over the chain tops in `roots`
+fn tree_anchor_status(roots: &[CertificateNode]) -> AnchorStatus {
+    let statuses: Vec<AnchorStatus> = roots
+        .iter()
+        .map(|root| match root.trust_status {
+            TrustStatus::Anchored | TrustStatus::NotChecked => AnchorStatus::TrustedRoot,
+            TrustStatus::Untrusted if root.cert.subject == root.cert.issuer => {
+                AnchorStatus::UntrustedRoot
+            }
+            TrustStatus::Untrusted => AnchorStatus::IncompleteChain,
+        })
+        .collect();
+    [AnchorStatus::UntrustedRoot, AnchorStatus::IncompleteChain]
+        .into_iter()
+        .find(|status| statuses.contains(status))
+        .unwrap_or(AnchorStatus::TrustedRoot)
+}
+
+/// Run a revocation check for every certificate whose issuer is its parent in the tree
+///
+/// Roots have no issuer to build a request against and stay `NotChecked`.
+pub fn check_revocation(
+    tree: &mut CertificateTree,
+    check: &dyn Fn(&CertificateInfo, &CertificateInfo) -> RevocationStatus,
+) {
+    fn check_children(
+        node: &mut CertificateNode,
+        check: &dyn Fn(&CertificateInfo, &CertificateInfo) -> RevocationStatus,
+    ) {
+        for child in &mut node.children {
+            child.revocation_status = check(&child.cert, &node.cert);
+            check_children(child, check);
+        }
+    }
+
+    for root in &mut tree.roots {
+        check_children(root, check);
+    }
+}
+
+/// Drop every certificate `keep` rejects
+///
+/// A kept certificate whose issuer is removed becomes a root of its own,
+/// keeping its kept descendants, rather than being attached to a certificate
+/// that did not issue it.
+pub fn prune_tree(tree: &mut CertificateTree, keep: &dyn Fn(&CertificateNode) -> bool) {
+    fn prune(
+        nodes: Vec<CertificateNode>,
+        keep: &dyn Fn(&CertificateNode) -> bool,
+        promoted: &mut Vec<CertificateNode>,
+    ) -> Vec<CertificateNode> {
+        let mut kept = Vec::new();
+        for mut node in nodes {
+            let children = prune(std::mem::take(&mut node.children), keep, promoted);
+            if keep(&node) {
+                node.children = children;
+                kept.push(node);
+            } else {
+                promoted.extend(children);
+            }
+        }
+        kept
+    }
+
+    let mut promoted = Vec::new();
+    let mut roots = prune(std::mem::take(&mut tree.roots), keep, &mut promoted);
+    roots.append(&mut promoted);
+    tree.roots = roots;
+}
+
+/// Cut the tree below `max_depth` levels (roots being level 1), returning how many certificates were hidden
+///
+/// Each node at the limit records in `truncated` how many descendants it lost.
+pub fn limit_depth(tree: &mut CertificateTree, max_depth: usize) -> usize {
+    fn count(nodes: &[CertificateNode]) -> usize {
+        nodes
+            .iter()
+            .map(|node| 1 + node.truncated + count(&node.children))
+            .sum()
+    }
+
+    fn limit(nodes: &mut [CertificateNode], depth: usize, max_depth: usize) -> usize {
+        let mut hidden = 0;
+        for node in nodes {
+            if depth >= max_depth {
+                node.truncated += count(&node.children);
+                hidden += count(&node.children);
+                node.children.clear();
+            } else {
+                hidden += limit(&mut node.children, depth + 1, max_depth);
+            }
+        }
+        hidden
+    }
+
+    limit(&mut tree.roots, 1, max_depth)
+}
+
+/// Aggregate the whole tree into one summary for dashboards, or `None` if it is empty
+#[must_use]
+pub fn summarize_chain(tree: &CertificateTree) -> Option<ChainSummary> {
+    fn walk<'a>(
+        node: &'a CertificateNode,
+        depth: usize,
+        nodes: &mut Vec<(&'a CertificateNode, usize)>,
+    ) {
+        nodes.push((node, depth));
+        for child in &node.children {
+            walk(child, depth + 1, nodes);
+        }
+    }
+
+    let mut nodes = Vec::new();
+    for root in &tree.roots {
+        walk(root, 1, &mut nodes);
+    }
+
+    // First of the deepest nodes, so ties keep tree order
+    let (leaf, depth) = nodes
+        .iter()
+        .copied()
+        .rev()
+        .max_by_key(|(_, depth)| *depth)?;
+    let chain = if nodes
+        .iter()
+        .all(|(node, _)| matches!(node.validation_status, ValidationStatus::Valid))
+    {
+        ValidationStatus::Valid
+    } else if let Some(invalid) = nodes
+        .iter()
+        .find(|(node, _)| matches!(node.validation_status, ValidationStatus::InvalidChain(_)))
+    {
+        invalid.0.validation_status.clone()
+    } else {
+        ValidationStatus::NotChecked
+    };
+    let expires_in_days = nodes
+        .iter()
+        .map(|(node, _)| node.cert.days_until_expiry())
+        .min()?;
+    let weakest = nodes
+        .iter()
+        .map(|(node, _)| &node.cert)
+        .min_by_key(|cert| SignatureStrength::from_oid(&cert.signature_oid))?
+        .signature_algorithm
+        .as_str();
+
+    Some(ChainSummary {
+        chain,
+        leaf_cn: extract_cn(&leaf.cert.subject),
+        expires_in_days,
+        weakest_sig: weakest.replace(" with ", "-"),
+        depth,
+    })
+}
+
+/// Cryptographically verify that `child` was signed with `issuer`'s public key
+///
+/// Matching DN strings alone are trivially spoofable, so the child's TBS
+/// signature is checked against the issuer's SPKI.
+#[must_use]
+pub fn verify_issued_by(child: &CertificateInfo, issuer: &CertificateInfo) -> bool {
+    let Ok((_, child_cert)) = X509Certificate::from_der(&child.der) else {
+        return false;
+    };
+    let Ok((_, issuer_cert)) = X509Certificate::from_der(&issuer.der) else {
+        return false;
+    };
+
+    child_cert
+        .verify_signature(Some(issuer_cert.public_key()))
+        .is_ok()
+}
+
+/// Whether `cert`'s signature verifies with the trust anchor's public key
+fn verify_signed_by_anchor(cert: &X509Certificate, anchor: &TrustAnchor) -> bool {
+    SubjectPublicKeyInfo::from_der(&anchor.spki_der)
+        .is_ok_and(|(_, spki)| cert.verify_signature(Some(&spki)).is_ok())
+}
+
+/// Reason for a chain top that is not self-signed and whose issuer is not in the input
+const ISSUER_NOT_FOUND: &str = "issuer not found";
+
 fn validate_node(node: &mut CertificateNode, parent_cert: Option<&CertificateInfo>) {
-    if let Some(parent) = parent_cert {
-        if parent.subject == node.cert.issuer {
-            node.validation_status = ValidationStatus::Valid;
+    node.validation_status = if let Some(parent) = parent_cert {
+        if parent.subject != node.cert.issuer {
+            ValidationStatus::InvalidChain("issuer name does not match".to_string())
+        } else if !verify_issued_by(&node.cert, parent) {
+            ValidationStatus::InvalidChain("signature does not verify".to_string())
+        } else if !parent.is_ca {
+            // Only a CA may sign other certificates (RFC 5280 section 4.2.1.9)
+            ValidationStatus::InvalidChain("issuer is not a CA".to_string())
         } else {
-            node.validation_status = ValidationStatus::InvalidChain;
+            ValidationStatus::Valid
         }
     } else if node.cert.subject == node.cert.issuer {
-        node.validation_status = ValidationStatus::Valid;
+        ValidationStatus::Valid
     } else {
-        node.validation_status = ValidationStatus::InvalidChain;
-    }
+        ValidationStatus::InvalidChain(ISSUER_NOT_FOUND.to_string())
+    };
 
     for child in &mut node.children {
         validate_node(child, Some(&node.cert));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_certificate_chain;
+
+    const CHAIN_PEM: &[u8] = include_bytes!("../test/chain.pem");
+
+    /// Collect every node's subject and validation status in depth-first order
+    fn statuses(tree: &CertificateTree) -> Vec<(String, ValidationStatus)> {
+        fn walk(node: &CertificateNode, out: &mut Vec<(String, ValidationStatus)>) {
+            out.push((node.cert.subject.clone(), node.validation_status.clone()));
+            for child in &node.children {
+                walk(child, out);
+            }
+        }
+
+        let mut out = Vec::new();
+        for root in &tree.roots {
+            walk(root, &mut out);
+        }
+        out
+    }
+
+    #[test]
+    fn test_valid_chain_verifies_signatures() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+        let tree = build_certificate_tree(&certs);
+
+        let statuses = statuses(&tree);
+        assert_eq!(statuses.len(), 3);
+        assert!(statuses
+            .iter()
+            .all(|(_, status)| matches!(status, ValidationStatus::Valid)));
+    }
+
+    #[test]
+    fn test_tampered_signature_is_invalid_chain() {
+        let mut certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+
+        // Flip the last signature byte of the leaf; its issuer DN still matches
+        let mut der = certs[0].der.clone();
+        *der.last_mut().unwrap() ^= 0xFF;
+        certs[0] = parse_certificate_chain(&der).unwrap().remove(0);
+
+        let tree = build_certificate_tree(&certs);
+        let statuses = statuses(&tree);
+        let (_, leaf_status) = statuses
+            .iter()
+            .find(|(subject, _)| subject.contains("www.example.com"))
+            .unwrap();
+
+        assert!(matches!(leaf_status, ValidationStatus::InvalidChain(_)));
+    }
+
+    #[test]
+    fn test_summary_of_three_cert_chain() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+        let tree = build_certificate_tree(&certs);
+
+        let summary = summarize_chain(&tree).unwrap();
+
+        assert!(matches!(summary.chain, ValidationStatus::Valid));
+        assert_eq!(summary.leaf_cn, "www.example.com");
+        assert_eq!(summary.weakest_sig, "SHA256-RSA");
+        assert_eq!(summary.depth, 3);
+        let expires_in_days = certs.iter().map(CertificateInfo::days_until_expiry).min();
+        assert_eq!(Some(summary.expires_in_days), expires_in_days);
+        assert_eq!(
+            summary.to_string(),
+            format!(
+                "chain=valid leaf_cn=www.example.com expires_in_days={} weakest_sig=SHA256-RSA depth=3",
+                summary.expires_in_days
+            )
+        );
+    }
+
+    #[test]
+    fn test_summary_reports_weakest_signature_and_broken_chain() {
+        let mut certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+        certs.truncate(2);
+        certs.extend(parse_certificate_chain(include_bytes!("../test/single_cert.pem")).unwrap());
+        let tree = build_certificate_tree(&certs);
+
+        let summary = summarize_chain(&tree).unwrap();
+
+        assert!(matches!(summary.chain, ValidationStatus::InvalidChain(_)));
+        assert_eq!(summary.weakest_sig, "SHA1-RSA");
+        assert_eq!(summary.depth, 2);
+    }
+
+    #[test]
+    fn test_summary_of_empty_tree() {
+        assert!(summarize_chain(&CertificateTree::default()).is_none());
+    }
+
+    #[test]
+    fn test_check_revocation_skips_roots() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+        let mut tree = build_certificate_tree(&certs);
+
+        check_revocation(&mut tree, &|_, _| RevocationStatus::Good);
+
+        let root = &tree.roots[0];
+        assert!(matches!(
+            root.revocation_status,
+            RevocationStatus::NotChecked
+        ));
+        assert!(matches!(
+            root.children[0].revocation_status,
+            RevocationStatus::Good
+        ));
+        assert!(matches!(
+            root.children[0].children[0].revocation_status,
+            RevocationStatus::Good
+        ));
+    }
+
+    fn named_cert(subject: &str, issuer: &str, serial: &str) -> CertificateInfo {
+        CertificateInfo {
+            subject: subject.to_string(),
+            issuer: issuer.to_string(),
+            serial_number: serial.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Trust anchors for every certificate in `pem`
+    fn anchors_in(pem: &[u8]) -> Vec<TrustAnchor> {
+        parse_certificate_chain(pem)
+            .unwrap()
+            .iter()
+            .filter_map(|cert| TrustAnchor::from_certificate(&cert.der))
+            .collect()
+    }
+
+    #[test]
+    fn test_chain_terminates_at_trusted_anchor() {
+        let chain = parse_certificate_chain(CHAIN_PEM).unwrap();
+        let root = anchors_in(include_bytes!("../test/root.pem"));
+
+        // Leaf and intermediate only: the intermediate's issuer is missing
+        let mut tree = build_certificate_tree(&chain[..2]);
+        assert!(matches!(
+            tree.roots[0].validation_status,
+            ValidationStatus::InvalidChain(_)
+        ));
+
+        validate_certificate_chain_with_anchors(&mut tree, &root);
+        let top = &tree.roots[0];
+        assert!(top.cert.subject.contains("Intermediate"));
+        assert!(matches!(top.trust_status, TrustStatus::Anchored));
+        assert!(matches!(top.validation_status, ValidationStatus::Valid));
+        assert!(matches!(
+            top.children[0].trust_status,
+            TrustStatus::NotChecked
+        ));
+
+        // The full chain is anchored by the root being one of the anchors
+        let mut tree = build_certificate_tree(&chain);
+        validate_certificate_chain_with_anchors(&mut tree, &root);
+        assert!(matches!(tree.roots[0].trust_status, TrustStatus::Anchored));
+    }
+
+    #[test]
+    fn test_chain_without_matching_anchor_is_untrusted() {
+        let chain = parse_certificate_chain(CHAIN_PEM).unwrap();
+        let other = anchors_in(include_bytes!("../test/single_cert.pem"));
+
+        let mut tree = build_certificate_tree(&chain[..2]);
+        validate_certificate_chain_with_anchors(&mut tree, &other);
+        assert!(matches!(tree.roots[0].trust_status, TrustStatus::Untrusted));
+        assert!(matches!(
+            tree.roots[0].validation_status,
+            ValidationStatus::InvalidChain(_)
+        ));
+    }
+
+    /// Log output shared between the test and the subscriber writing it
+    #[derive(Clone, Default)]
+    struct CapturedLog(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLog {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_tree_building_decisions_are_traced() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+        let log = CapturedLog::default();
+        let writer = log.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+
+        tracing::subscriber::with_default(subscriber, || build_certificate_tree(&certs));
+
+        let output = String::from_utf8(log.0.lock().unwrap().clone()).unwrap();
+        let root = &certs[2].subject;
+        let intermediate = &certs[1].subject;
+        assert!(output.contains(&format!(
+            "selected root subject={root:?} reason=\"self-issued\""
+        )));
+        assert!(output.contains(&format!(
+            "linked child under parent child={intermediate:?} parent={root:?}"
+        )));
+        assert!(output.contains(&format!(
+            "linked child under parent child={:?} parent={intermediate:?}",
+            certs[0].subject
+        )));
+    }
+
+    #[test]
+    fn test_anchor_status_of_chain_ends() {
+        let chain = parse_certificate_chain(CHAIN_PEM).unwrap();
+        let root = anchors_in(include_bytes!("../test/root.pem"));
+        let other = anchors_in(include_bytes!("../test/single_cert.pem"));
+
+        // Without anchors nothing is checked
+        assert_eq!(build_certificate_tree(&chain).anchor_status, None);
+
+        let mut tree = build_certificate_tree(&chain);
+        validate_certificate_chain_with_anchors(&mut tree, &root);
+        assert_eq!(tree.anchor_status, Some(AnchorStatus::TrustedRoot));
+
+        // Ends at its self-signed root, which the bundle does not hold
+        let mut tree = build_certificate_tree(&chain);
+        validate_certificate_chain_with_anchors(&mut tree, &other);
+        assert_eq!(tree.anchor_status, Some(AnchorStatus::UntrustedRoot));
+
+        // Ends at the intermediate, whose issuer is missing
+        let mut tree = build_certificate_tree(&chain[..2]);
+        validate_certificate_chain_with_anchors(&mut tree, &other);
+        assert_eq!(tree.anchor_status, Some(AnchorStatus::IncompleteChain));
+    }
+
+    #[test]
+    fn test_prune_keeps_only_expired() {
+        let mut certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+        // Expire the leaf (first) and root (last); the intermediate stays valid
+        let past = chrono::Utc::now() - chrono::Duration::days(3);
+        certs[0].not_after_dt = past;
+        certs[2].not_after_dt = past;
+        let mut tree = build_certificate_tree(&certs);
+
+        prune_tree(&mut tree, &|node| {
+            node.validity_status == ValidityStatus::Expired
+        });
+
+        // The leaf is not re-parented under the root, which did not issue it
+        assert_eq!(tree.roots.len(), 2);
+        assert!(tree.roots[0].cert.subject.contains("Root CA"));
+        assert!(tree.roots[0].children.is_empty());
+        let leaf = &tree.roots[1];
+        assert!(leaf.cert.subject.contains("www.example.com"));
+        assert!(leaf.children.is_empty());
+        // Roles and chain validation still reflect the full set
+        assert_eq!(leaf.role, CertificateRole::Leaf);
+    }
+
+    #[test]
+    fn test_expire_before_keeps_earlier_certificates() {
+        let date = |ymd: &str| {
+            chrono::NaiveDate::parse_from_str(ymd, "%Y-%m-%d")
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_utc()
+        };
+        let expiring = |serial, not_after| CertificateInfo {
+            not_before_dt: date("2024-01-01"),
+            not_after_dt: date(not_after),
+            ..named_cert("CN=a", "CN=a", serial)
+        };
+        let certs = [
+            expiring("01", "2025-03-01"),
+            expiring("02", "2025-05-31"),
+            expiring("03", "2025-06-01"),
+            expiring("04", "2026-01-01"),
+        ];
+
+        let filter = CertificateFilter {
+            expire_before: Some(date("2025-06-01")),
+            ..CertificateFilter::default()
+        };
+        let kept: Vec<_> = certs
+            .iter()
+            .filter(|cert| filter.matches(cert))
+            .map(|cert| cert.serial_number.as_str())
+            .collect();
+        assert_eq!(kept, ["01", "02"]);
+
+        // Combined with a status filter, both must hold
+        let filter = CertificateFilter {
+            statuses: vec![ValidityStatus::Valid],
+            expire_before: Some(date("2027-01-01")),
+        };
+        assert!(certs.iter().all(|cert| !filter.matches(cert)));
+    }
+
+    #[test]
+    fn test_roles_in_complete_chain() {
+        let tree = build_certificate_tree(&parse_certificate_chain(CHAIN_PEM).unwrap());
+        let root = &tree.roots[0];
+        assert_eq!(root.role, CertificateRole::RootCa);
+        assert_eq!(root.children[0].role, CertificateRole::Intermediate);
+        assert_eq!(root.children[0].children[0].role, CertificateRole::Leaf);
+    }
+
+    #[test]
+    fn test_self_signed_leaf_role() {
+        let certs =
+            parse_certificate_chain(include_bytes!("../test/self_signed_leaf.pem")).unwrap();
+        let tree = build_certificate_tree(&certs);
+        assert_eq!(tree.roots[0].role, CertificateRole::SelfSigned);
+    }
+
+    #[test]
+    fn test_orphan_intermediate_role() {
+        // Leaf and intermediate without the root that issued the intermediate
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+        let tree = build_certificate_tree(&certs[..2]);
+        let top = &tree.roots[0];
+        assert_eq!(top.role, CertificateRole::OrphanIntermediate);
+        assert_eq!(top.children[0].role, CertificateRole::Leaf);
+    }
+
+    #[test]
+    fn test_cross_signed_pair_appears_once() {
+        let certs = vec![
+            named_cert("CN=Leaf", "CN=B", "03"),
+            named_cert("CN=B", "CN=A", "02"),
+            named_cert("CN=A", "CN=B", "01"),
+        ];
+
+        let tree = build_certificate_tree(&certs);
+        let subjects: Vec<String> = statuses(&tree).into_iter().map(|(s, _)| s).collect();
+
+        assert_eq!(subjects.len(), 3);
+        let unique: HashSet<&String> = subjects.iter().collect();
+        assert_eq!(unique.len(), 3);
+
+        // The cycle breaks at the smallest subject regardless of input order
+        assert_eq!(tree.roots.len(), 1);
+        assert_eq!(tree.roots[0].cert.subject, "CN=A");
+
+        let mut reversed = certs.clone();
+        reversed.reverse();
+        let tree = build_certificate_tree(&reversed);
+        assert_eq!(tree.roots.len(), 1);
+        assert_eq!(tree.roots[0].cert.subject, "CN=A");
+        assert_eq!(statuses(&tree).len(), 3);
+    }
+
+    #[test]
+    fn test_same_subject_with_different_serials_keeps_both() {
+        let certs = vec![
+            named_cert("CN=Root", "CN=Root", "01"),
+            named_cert("CN=Intermediate", "CN=Root", "02"),
+            named_cert("CN=Intermediate", "CN=Root", "03"),
+            named_cert("CN=Leaf", "CN=Other", "04"),
+        ];
+
+        assert_eq!(subject_conflicts(&certs), vec!["CN=Intermediate"]);
+
+        let tree = build_certificate_tree(&certs);
+        let root = tree
+            .roots
+            .iter()
+            .find(|node| node.cert.subject == "CN=Root")
+            .unwrap();
+        let serials: Vec<&str> = root
+            .children
+            .iter()
+            .map(|child| child.cert.serial_number.as_str())
+            .collect();
+        assert_eq!(serials, vec!["02", "03"]);
+        assert_eq!(statuses(&tree).len(), 4);
+    }
+
+    #[test]
+    fn test_key_identifiers_pick_parent_among_shared_dn() {
+        let key_ids = |cert: CertificateInfo, ski: &str, aki: &str| CertificateInfo {
+            subject_key_id: Some(ski.to_string()),
+            authority_key_id: Some(aki.to_string()),
+            ..cert
+        };
+        let certs = vec![
+            key_ids(named_cert("CN=Root", "CN=Root", "01"), "AA", "AA"),
+            key_ids(named_cert("CN=CA", "CN=Root", "02"), "BB", "AA"),
+            key_ids(named_cert("CN=CA", "CN=Root", "03"), "CC", "AA"),
+            key_ids(named_cert("CN=Leaf", "CN=CA", "04"), "DD", "CC"),
+        ];
+
+        let tree = build_certificate_tree(&certs);
+        assert_eq!(tree.roots.len(), 1);
+        let cas = &tree.roots[0].children;
+        assert_eq!(cas[0].cert.serial_number, "02");
+        assert!(cas[0].children.is_empty());
+        assert_eq!(cas[1].cert.serial_number, "03");
+        assert_eq!(cas[1].children[0].cert.serial_number, "04");
+
+        // An identifier matching no certificate falls back to the issuer DN
+        let mut unmatched = certs.clone();
+        unmatched[3].authority_key_id = Some("EE".to_string());
+        let tree = build_certificate_tree(&unmatched);
+        assert_eq!(tree.roots[0].children[0].children.len(), 1);
+    }
+
+    #[test]
+    fn test_topmost_ancestor_walks_to_root() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+        let tree = build_certificate_tree(&certs);
+
+        let top = topmost_ancestor(&tree, &certs[0]).unwrap();
+        assert!(top.subject.contains("Root"));
+        assert_eq!(
+            topmost_ancestor(&tree, &certs[2]).unwrap().serial_number,
+            top.serial_number
+        );
+        assert!(topmost_ancestor(&tree, &named_cert("CN=Other", "CN=Other", "01")).is_none());
+    }
+
+    #[test]
+    fn test_non_ca_issuer_invalidates_chain() {
+        // www.example.com is CA:FALSE yet signed issued-by-leaf.example.com
+        let certs = parse_certificate_chain(include_bytes!("../test/leaf.pem"))
+            .unwrap()
+            .into_iter()
+            .chain(parse_certificate_chain(include_bytes!("../test/issued_by_leaf.pem")).unwrap())
+            .collect::<Vec<_>>();
+
+        let tree = build_certificate_tree(&certs);
+
+        // The leaf keeps its own reason; the certificate it signed is the invalid link
+        let leaf = &tree.roots[0];
+        assert_eq!(leaf.validation_status.reason(), Some(ISSUER_NOT_FOUND));
+        assert_eq!(
+            leaf.children[0].validation_status.reason(),
+            Some("issuer is not a CA")
+        );
+    }
+
+    #[test]
+    fn test_unvalidated_tree_marks_nodes_not_checked() {
+        let certs = parse_certificate_chain(CHAIN_PEM).unwrap();
+        let tree = build_unvalidated_tree(&certs);
+
+        let all = statuses(&tree);
+        assert_eq!(all.len(), 3);
+        assert!(all
+            .iter()
+            .all(|(_, status)| matches!(status, ValidationStatus::NotChecked)));
+        assert!(matches!(
+            summarize_chain(&tree).unwrap().chain,
+            ValidationStatus::NotChecked
+        ));
+    }
+
+    /// A chain of `length` certificates, each issued by the one before
+    fn linear_chain(length: usize) -> Vec<CertificateInfo> {
+        (0..length)
+            .map(|level| {
+                let issuer = level.saturating_sub(1);
+                named_cert(
+                    &format!("CN=Level {level}"),
+                    &format!("CN=Level {issuer}"),
+                    &format!("{level:02}"),
+                )
+            })
+            .collect()
+    }
+
+    /// Number of levels below and including `node`
+    fn depth(node: &CertificateNode) -> usize {
+        1 + node.children.iter().map(depth).max().unwrap_or(0)
+    }
+
+    #[test]
+    fn test_limit_depth_truncates_five_deep_chain() {
+        let mut tree = build_certificate_tree(&linear_chain(5));
+        assert_eq!(depth(&tree.roots[0]), 5);
+
+        assert_eq!(limit_depth(&mut tree, 2), 3);
+
+        let root = &tree.roots[0];
+        assert_eq!(root.truncated, 0);
+        assert_eq!(root.children.len(), 1);
+        let second = &root.children[0];
+        assert_eq!(second.cert.subject, "CN=Level 1");
+        assert!(second.children.is_empty());
+        assert_eq!(second.truncated, 3);
+    }
+
+    #[test]
+    fn test_pathologically_deep_chain_is_capped() {
+        let tree = build_certificate_tree(&linear_chain(MAX_TREE_DEPTH * 3));
+
+        assert_eq!(statuses(&tree).len(), MAX_TREE_DEPTH * 3);
+        assert!(tree.roots.iter().all(|root| depth(root) <= MAX_TREE_DEPTH));
+    }
+}